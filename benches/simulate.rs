@@ -0,0 +1,90 @@
+use battle_ship::{
+    run_game, simulate, Board, Difficulty, FleetConfig, GameSettings, MonteCarloStrategy,
+    RandomStrategy,
+};
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use std::hint::black_box;
+use std::time::Instant;
+
+// How many headless games each pairing plays.
+const GAMES: usize = 10_000;
+
+// How many times a board is cloned, as a Monte Carlo rollout would.
+const CLONES: usize = 1_000_000;
+
+// How many games the Monte Carlo strategy plays to time its decisions.
+const EXPERT_GAMES: usize = 100;
+
+// Benchmark of the board in bulk: 10k seeded headless games for each pairing of computer players, then a
+// million clones of a board partway through a game, then the average time the Monte Carlo strategy takes to
+// pick a shot. Run with `cargo bench` and compare the times between builds.
+fn main() {
+    let settings = GameSettings {
+        size: 10,
+        difficulty: Difficulty::Normal,
+        fleet: FleetConfig::standard(),
+        salvo: false,
+        hit_again: false,
+        heat: false,
+        no_touching: false,
+        spread: false,
+        diagonal: false,
+        land: 0,
+        hotseat: false,
+        seed: Some(7),
+        max_turns: None,
+        torpedoes: 0,
+        scans: 0,
+        sweeps: 0,
+        auto_advance: None,
+    };
+    for players in [
+        [Difficulty::Easy, Difficulty::Easy],
+        [Difficulty::Normal, Difficulty::Normal],
+        [Difficulty::Hard, Difficulty::Hard],
+    ] {
+        let start = Instant::now();
+        let report = simulate(players, GAMES, &settings).unwrap();
+        println!(
+            "{:>6} vs {:<6} {} games in {:>8.1?}",
+            format!("{:?}", players[0]),
+            format!("{:?}", players[1]),
+            report.games,
+            start.elapsed()
+        );
+    }
+
+    let mut board = Board::new(10);
+    board
+        .place_fleet(&FleetConfig::standard(), &mut StdRng::seed_from_u64(7))
+        .unwrap();
+    for row in 0..10 {
+        board.fire(row, row);
+    }
+    let start = Instant::now();
+    for _ in 0..CLONES {
+        black_box(black_box(&board).clone());
+    }
+    println!("{} board clones in {:>8.1?}", CLONES, start.elapsed());
+
+    // The random side takes next to no time, so nearly all of it goes on the expert's shots
+    let mut rng = StdRng::seed_from_u64(7);
+    let mut shots = 0;
+    let start = Instant::now();
+    for _ in 0..EXPERT_GAMES {
+        let result = run_game(
+            &mut MonteCarloStrategy,
+            &mut RandomStrategy,
+            &settings,
+            &mut rng,
+        )
+        .unwrap();
+        shots += result.shots[0];
+    }
+    println!(
+        "Expert decisions average {:.2?} over {} shots",
+        start.elapsed() / shots as u32,
+        shots
+    );
+}
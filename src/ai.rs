@@ -0,0 +1,548 @@
+use crate::bitboard::Bitboard;
+use crate::board::{is_valid_coord, ship_cells, Board, BoardView, CellState, Lane, ShotOutcome};
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::{Rng, SeedableRng};
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::fmt;
+use std::str::FromStr;
+
+// How hard the computer opponent tries: Easy fires at random, Normal hunts and then targets around hits,
+// Hard fires wherever the remaining ships are most likely to be, hunting on a parity grid, and Expert fires
+// where the remaining ships turn up most often in thousands of random layouts that fit what it has seen.
+// Saves and the command line still accept the old name "medium" for Normal, and the command line also takes
+// the strategy names random, hunt, density and montecarlo.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub enum Difficulty {
+    Easy,
+    #[serde(alias = "Medium")]
+    Normal,
+    Hard,
+    Expert,
+}
+
+impl fmt::Display for Difficulty {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Difficulty::Easy => write!(f, "Easy"),
+            Difficulty::Normal => write!(f, "Normal"),
+            Difficulty::Hard => write!(f, "Hard"),
+            Difficulty::Expert => write!(f, "Expert"),
+        }
+    }
+}
+
+impl FromStr for Difficulty {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            // Each difficulty can also be named after the strategy it plays with
+            "easy" | "random" => Ok(Difficulty::Easy),
+            "normal" | "medium" | "hunt" => Ok(Difficulty::Normal),
+            "hard" | "density" => Ok(Difficulty::Hard),
+            "expert" | "montecarlo" => Ok(Difficulty::Expert),
+            _ => Err(format!("unknown difficulty '{}'", s)),
+        }
+    }
+}
+
+// A way of choosing where to fire. A strategy only gets a view of the board it is firing at, with the ships
+// hidden, and a random number generator, so the strategies are interchangeable and the AI can switch between
+// them by difficulty.
+pub trait Strategy {
+    fn choose_target(&mut self, board: &BoardView, rng: &mut StdRng) -> (usize, usize);
+
+    // Method to learn how a shot the strategy chose turned out. Strategies that work everything out from the
+    // board alone can ignore it.
+    fn notify(&mut self, _target: (usize, usize), _outcome: &ShotOutcome) {}
+}
+
+// Strategy that fires at a random cell it hasn't tried yet.
+#[derive(Default)]
+pub struct RandomStrategy;
+
+impl Strategy for RandomStrategy {
+    fn choose_target(&mut self, board: &BoardView, rng: &mut StdRng) -> (usize, usize) {
+        random_untried(board, rng)
+    }
+}
+
+// Strategy that hunts at random until it scores a hit, then targets the neighbours of that hit, following
+// the line once two hits line up.
+#[derive(Default, Serialize, Deserialize)]
+pub struct HuntTargetStrategy {
+    hits: Vec<(usize, usize)>,
+    targets: VecDeque<(usize, usize)>,
+}
+
+impl Strategy for HuntTargetStrategy {
+    // Method to pick the next hunt/target shot.
+    fn choose_target(&mut self, board: &BoardView, rng: &mut StdRng) -> (usize, usize) {
+        // Forget hits on ships that are now sunk and only keep chasing the rest.
+        if self.hits.iter().any(|&(r, c)| board.is_sunk_at(r, c)) {
+            self.hits.retain(|&(r, c)| !board.is_sunk_at(r, c));
+            self.plan_targets();
+        }
+
+        // Target mode: work through the queued neighbours, skipping cells that are off the board, were
+        // already fired at or are land.
+        while let Some((row, col)) = self.targets.pop_front() {
+            if is_valid_coord(row, col, board.size()) && is_untried(board, row, col) {
+                return (row, col);
+            }
+        }
+
+        // Hunt mode: pick a random cell that hasn't been tried yet.
+        random_untried(board, rng)
+    }
+
+    // Method to start targeting around every hit.
+    fn notify(&mut self, target: (usize, usize), outcome: &ShotOutcome) {
+        if matches!(outcome, ShotOutcome::Hit | ShotOutcome::Sunk(_)) {
+            self.hits.push(target);
+            self.plan_targets();
+        }
+    }
+}
+
+impl HuntTargetStrategy {
+    // Helper method to rebuild the target queue from the hits that haven't sunk a ship yet.
+    fn plan_targets(&mut self) {
+        self.targets.clear();
+        // Once two hits line up, follow that line in both directions before anything else.
+        if self.hits.len() >= 2 {
+            let (row, col) = self.hits[0];
+            if self.hits.iter().all(|&(r, _)| r == row) {
+                let min = self.hits.iter().map(|&(_, c)| c).min().unwrap();
+                let max = self.hits.iter().map(|&(_, c)| c).max().unwrap();
+                self.targets.push_back((row, min.wrapping_sub(1)));
+                self.targets.push_back((row, max + 1));
+            } else if self.hits.iter().all(|&(_, c)| c == col) {
+                let min = self.hits.iter().map(|&(r, _)| r).min().unwrap();
+                let max = self.hits.iter().map(|&(r, _)| r).max().unwrap();
+                self.targets.push_back((min.wrapping_sub(1), col));
+                self.targets.push_back((max + 1, col));
+            }
+        }
+        // Fall back to probing around every outstanding hit if the line is blocked at both ends.
+        for (r, c) in self.hits.clone() {
+            self.queue_neighbors(r, c);
+        }
+    }
+
+    // Helper method to queue the four orthogonal neighbours of a hit as future targets. Neighbours off the
+    // board are dropped when they come up; stepping off the top or left edge wraps round to a huge index,
+    // which the bounds check rejects like any other.
+    fn queue_neighbors(&mut self, row: usize, col: usize) {
+        self.targets.push_back((row.wrapping_sub(1), col));
+        self.targets.push_back((row + 1, col));
+        self.targets.push_back((row, col.wrapping_sub(1)));
+        self.targets.push_back((row, col + 1));
+    }
+}
+
+// Strategy that fires wherever the remaining ships are most likely to be, according to `heatmap`. While
+// hunting with no unresolved hits, only every n-th cell along a diagonal is considered, where n is the
+// smallest ship left, since every ship must cover one of them.
+#[derive(Default)]
+pub struct DensityStrategy;
+
+impl Strategy for DensityStrategy {
+    fn choose_target(&mut self, board: &BoardView, rng: &mut StdRng) -> (usize, usize) {
+        let size = board.size();
+        let scores = heatmap(board);
+        let mut untried: Vec<(usize, usize)> = (0..size)
+            .flat_map(|r| (0..size).map(move |c| (r, c)))
+            .filter(|&(r, c)| is_untried(board, r, c))
+            .collect();
+        let hunting = (0..size)
+            .flat_map(|r| (0..size).map(move |c| (r, c)))
+            .all(|(r, c)| board.cell(r, c) != CellState::Hit || board.is_sunk_at(r, c));
+        let parity = hunt_spacing(board);
+        if hunting && untried.iter().any(|&(r, c)| (r + c) % parity == 0) {
+            untried.retain(|&(r, c)| (r + c) % parity == 0);
+        }
+        let best = untried
+            .iter()
+            .map(|&(r, c)| scores[r][c])
+            .max()
+            .expect("No cells left to fire at");
+        let candidates: Vec<(usize, usize)> = untried
+            .into_iter()
+            .filter(|&(r, c)| scores[r][c] == best)
+            .collect();
+        *candidates.choose(rng).unwrap()
+    }
+}
+
+// How many layouts that fit the board `MonteCarloStrategy` tallies before choosing a cell.
+const SAMPLES: usize = 2000;
+
+// How many layouts `MonteCarloStrategy` tries in all before making do with the ones that fit.
+const SAMPLE_ATTEMPTS: usize = 10_000;
+
+// Fewest fitting layouts `MonteCarloStrategy` trusts; with fewer it falls back on the density count.
+const MIN_SAMPLES: usize = 100;
+
+// How many random spots a layout tries for each ship before the layout is given up.
+const SHIP_ATTEMPTS: usize = 20;
+
+// Strategy that lays the ships still afloat out at random thousands of times, keeping only the layouts that
+// fit everything seen so far: no ship crosses a miss or a sunk ship (nor, under the no-touching rule, the
+// cells around one), and between them they cover every hit that hasn't sunk a ship. It fires at the cell
+// that holds a ship in the most of those layouts, hunting on the same parity grid as `DensityStrategy` while
+// there are no open hits. With several hits to explain, fitting layouts can get too rare to find, and then
+// it falls back on the density count of `DensityStrategy`.
+#[derive(Default)]
+pub struct MonteCarloStrategy;
+
+impl Strategy for MonteCarloStrategy {
+    fn choose_target(&mut self, board: &BoardView, rng: &mut StdRng) -> (usize, usize) {
+        let size = board.size();
+        let sampler = Sampler::new(board);
+        let mut tally = vec![vec![0; size]; size];
+        let mut layout = Vec::new();
+        let mut samples = 0;
+        for _ in 0..SAMPLE_ATTEMPTS {
+            if samples == SAMPLES {
+                break;
+            }
+            if sampler.sample(&mut layout, rng) {
+                samples += 1;
+                for (spots, &spot) in sampler.spots.iter().zip(&layout) {
+                    for &(r, c) in &spots[spot].unfired {
+                        tally[r][c] += 1;
+                    }
+                }
+            }
+        }
+        let mut untried: Vec<(usize, usize)> = (0..size)
+            .flat_map(|r| (0..size).map(move |c| (r, c)))
+            .filter(|&(r, c)| is_untried(board, r, c))
+            .collect();
+        let parity = hunt_spacing(board);
+        if sampler.hits.is_empty() && untried.iter().any(|&(r, c)| (r + c) % parity == 0) {
+            untried.retain(|&(r, c)| (r + c) % parity == 0);
+        }
+        let best = untried.iter().map(|&(r, c)| tally[r][c]).max().unwrap_or(0);
+        if samples < MIN_SAMPLES || best == 0 {
+            return DensityStrategy.choose_target(board, rng);
+        }
+        let candidates: Vec<(usize, usize)> = untried
+            .into_iter()
+            .filter(|&(r, c)| tally[r][c] == best)
+            .collect();
+        *candidates.choose(rng).unwrap()
+    }
+}
+
+// One place a ship could lie: the cells it would cover, the cells it would keep other ships out of (its own,
+// and under the no-touching rule the ones around them too), and which of its cells haven't been fired at.
+struct Spot {
+    cells: Bitboard,
+    area: Bitboard,
+    unfired: Vec<(usize, usize)>,
+}
+
+// What `MonteCarloStrategy` knows about the board it is firing at: the hits still to be explained, and every
+// spot each ship still afloat could lie in without crossing a miss or a sunk ship. Spots are kept as bits,
+// so checking a random layout is a handful of bitwise operations.
+struct Sampler {
+    hits: Bitboard,
+    spots: Vec<Vec<Spot>>,
+}
+
+impl Sampler {
+    // Constructor reading the misses, land, sunk ships and open hits off a view of the board and listing the
+    // spots for each ship. A spot made up only of hits is left out, as a ship hit all over would have sunk.
+    fn new(board: &BoardView) -> Self {
+        let size = board.size();
+        let reach = if board.no_touching() { 1 } else { 0 };
+        let around = |r: usize, c: usize| {
+            let rows = r.saturating_sub(reach)..=(r + reach).min(size - 1);
+            rows.flat_map(move |nr| {
+                (c.saturating_sub(reach)..=(c + reach).min(size - 1)).map(move |nc| (nr, nc))
+            })
+        };
+        let mut blocked = Bitboard::EMPTY;
+        let mut hits = Bitboard::EMPTY;
+        for r in 0..size {
+            for c in 0..size {
+                if matches!(board.cell(r, c), CellState::Miss | CellState::Land) {
+                    blocked.insert(r, c);
+                } else if board.is_sunk_at(r, c) {
+                    for (nr, nc) in around(r, c) {
+                        blocked.insert(nr, nc);
+                    }
+                } else if board.cell(r, c) == CellState::Hit {
+                    hits.insert(r, c);
+                }
+            }
+        }
+        let spots_for = |ship: usize| {
+            let mut spots = Vec::new();
+            for row in 0..size {
+                for col in 0..size {
+                    for &orientation in board.orientations() {
+                        if !orientation.fits(row, col, ship, size) {
+                            continue;
+                        }
+                        let mut spot = Spot {
+                            cells: Bitboard::EMPTY,
+                            area: Bitboard::EMPTY,
+                            unfired: Vec::new(),
+                        };
+                        for (r, c) in ship_cells(row, col, ship, orientation) {
+                            spot.cells.insert(r, c);
+                            if !hits.contains(r, c) {
+                                spot.unfired.push((r, c));
+                            }
+                            for (nr, nc) in around(r, c) {
+                                spot.area.insert(nr, nc);
+                            }
+                        }
+                        if (spot.cells & blocked).is_empty() && !spot.unfired.is_empty() {
+                            spots.push(spot);
+                        }
+                    }
+                }
+            }
+            spots
+        };
+        Sampler {
+            hits,
+            spots: board.remaining_ships().into_iter().map(spots_for).collect(),
+        }
+    }
+
+    // Method to lay the remaining ships out at random, each in one of its spots clear of the ships laid before
+    // it, filling `layout` with the spot picked for each. Returns false if a ship found no room, or if the
+    // layout leaves one of the open hits uncovered. Layouts aren't steered towards the hits, as that would
+    // skew which of them turn up.
+    fn sample(&self, layout: &mut Vec<usize>, rng: &mut StdRng) -> bool {
+        layout.clear();
+        let mut taken = Bitboard::EMPTY;
+        let mut covered = Bitboard::EMPTY;
+        for spots in &self.spots {
+            if spots.is_empty() {
+                return false;
+            }
+            let Some(spot) = (0..SHIP_ATTEMPTS)
+                .map(|_| rng.gen_range(0..spots.len()))
+                .find(|&spot| (spots[spot].cells & taken).is_empty())
+            else {
+                return false;
+            };
+            taken = taken | spots[spot].area;
+            covered = covered | spots[spot].cells;
+            layout.push(spot);
+        }
+        (self.hits & !covered).is_empty()
+    }
+}
+
+// Opponent AI whose behaviour depends on the chosen difficulty: Easy uses the random strategy, Normal the
+// hunt/target strategy, Hard the probability-density strategy and Expert the Monte Carlo strategy. Everything
+// but the random number generator is saved with the game; a loaded AI carries on from a fresh generator.
+#[derive(Serialize, Deserialize)]
+pub struct OpponentAi {
+    difficulty: Difficulty,
+    #[serde(skip, default = "StdRng::from_entropy")]
+    rng: StdRng,
+    #[serde(flatten)]
+    hunt_target: HuntTargetStrategy,
+    #[serde(skip)]
+    random: RandomStrategy,
+    #[serde(skip)]
+    density: DensityStrategy,
+    #[serde(skip)]
+    monte_carlo: MonteCarloStrategy,
+}
+
+impl OpponentAi {
+    // Constructor for the AI, starting out in hunt mode with no shots recorded. The seed drives every random
+    // choice the AI makes, so the same seed and the same board always produce the same shots.
+    pub fn new(difficulty: Difficulty, seed: u64) -> Self {
+        OpponentAi {
+            difficulty,
+            rng: StdRng::seed_from_u64(seed),
+            hunt_target: HuntTargetStrategy::default(),
+            random: RandomStrategy,
+            density: DensityStrategy,
+            monte_carlo: MonteCarloStrategy,
+        }
+    }
+
+    // Accessor for the difficulty this AI plays at.
+    pub fn difficulty(&self) -> Difficulty {
+        self.difficulty
+    }
+
+    // Method to choose the next cell to fire at with the strategy for the AI's difficulty. The strategy only
+    // sees the board through a view with the ships hidden.
+    pub fn next_move(&mut self, board: &Board) -> (usize, usize) {
+        self.choose_target(&BoardView::new(board))
+    }
+
+    // Method to choose the next cell to fire at from a view of the board.
+    pub fn choose_target(&mut self, board: &BoardView) -> (usize, usize) {
+        let (strategy, rng) = self.strategy();
+        strategy.choose_target(board, rng)
+    }
+
+    // Method to pass the result of the AI's last shot on to its strategy.
+    pub fn notify(&mut self, target: (usize, usize), outcome: &ShotOutcome) {
+        self.strategy().0.notify(target, outcome);
+    }
+
+    // Method to clear what the AI has learned from its shots so far, such as hits it is still chasing, for
+    // when shots are taken back.
+    pub fn forget(&mut self) {
+        self.hunt_target = HuntTargetStrategy::default();
+    }
+
+    // Helper method to get the strategy for the AI's difficulty along with the random number generator it
+    // draws from. The stateless strategies are kept as fields too, so every difficulty is handled the same way.
+    fn strategy(&mut self) -> (&mut dyn Strategy, &mut StdRng) {
+        let strategy: &mut dyn Strategy = match self.difficulty {
+            Difficulty::Easy => &mut self.random,
+            Difficulty::Normal => &mut self.hunt_target,
+            Difficulty::Hard => &mut self.density,
+            Difficulty::Expert => &mut self.monte_carlo,
+        };
+        (strategy, &mut self.rng)
+    }
+}
+
+impl Default for OpponentAi {
+    fn default() -> Self {
+        Self::new(Difficulty::Normal, rand::thread_rng().gen())
+    }
+}
+
+// Function to check whether a cell is still worth a shot: not fired at yet, and not land, where no ship can be.
+fn is_untried(board: &BoardView, row: usize, col: usize) -> bool {
+    !board.has_been_fired_at(row, col) && board.cell(row, col) != CellState::Land
+}
+
+// Function to pick a random cell that hasn't been fired at yet, leaving out land.
+fn random_untried(board: &BoardView, rng: &mut impl Rng) -> (usize, usize) {
+    let size = board.size();
+    let untried: Vec<(usize, usize)> = (0..size)
+        .flat_map(|r| (0..size).map(move |c| (r, c)))
+        .filter(|&(r, c)| is_untried(board, r, c))
+        .collect();
+    *untried.choose(rng).expect("No cells left to fire at")
+}
+
+// Function to find the spacing of the parity grid to hunt on: the length of the shortest ship still afloat, as
+// every straight ship at least that long crosses a line of the grid. A diagonal ship can slip between the lines,
+// so under the diagonal rule every cell is fair game (a spacing of 1).
+fn hunt_spacing(board: &BoardView) -> usize {
+    if board
+        .orientations()
+        .iter()
+        .any(|orientation| orientation.is_diagonal())
+    {
+        return 1;
+    }
+    board.remaining_ships().into_iter().min().unwrap_or(1)
+}
+
+// Function to score every cell by how many placements of the ships still afloat would cover it. Placements may
+// not cross a miss, land or a sunk ship (nor, under the no-touching rule, the cells around a sunk ship), and
+// those covering unresolved hits are weighted heavily, so the cells next to a hit score highest. Cells that were
+// already fired at, and land, score zero.
+pub fn heatmap(board: &BoardView) -> Vec<Vec<usize>> {
+    const HIT_WEIGHT: usize = 20;
+    let size = board.size();
+    let mut blocked = vec![vec![false; size]; size];
+    for r in 0..size {
+        for c in 0..size {
+            if matches!(board.cell(r, c), CellState::Miss | CellState::Land) {
+                blocked[r][c] = true;
+            } else if board.is_sunk_at(r, c) {
+                let reach = if board.no_touching() { 1 } else { 0 };
+                let rows = r.saturating_sub(reach)..=(r + reach).min(size - 1);
+                let cols = c.saturating_sub(reach)..=(c + reach).min(size - 1);
+                for blocked_row in &mut blocked[rows] {
+                    blocked_row[cols.clone()].fill(true);
+                }
+            }
+        }
+    }
+    let mut scores = vec![vec![0; size]; size];
+
+    for ship_size in board.remaining_ships() {
+        for row in 0..size {
+            for col in 0..size {
+                for &orientation in board.orientations() {
+                    if !orientation.fits(row, col, ship_size, size) {
+                        continue;
+                    }
+                    // Walked again rather than collected, as this runs for every placement of every shot
+                    let cells = || ship_cells(row, col, ship_size, orientation);
+                    if cells().any(|(r, c)| blocked[r][c]) {
+                        continue;
+                    }
+                    let hits = cells()
+                        .filter(|&(r, c)| board.cell(r, c) == CellState::Hit)
+                        .count();
+                    let weight = 1 + hits * HIT_WEIGHT;
+                    for (r, c) in cells() {
+                        scores[r][c] += weight;
+                    }
+                }
+            }
+        }
+    }
+
+    for (r, row) in scores.iter_mut().enumerate() {
+        for (c, score) in row.iter_mut().enumerate() {
+            if !is_untried(board, r, c) {
+                *score = 0;
+            }
+        }
+    }
+    scores
+}
+
+// Seed for the random choices behind a hint, so the same board always gets the same suggestion.
+const HINT_SEED: u64 = 0;
+
+// Function to suggest where to fire next the way the Hard opponent would, for the hint command. It only looks
+// through a view of the board, so it goes on the hits, misses and sizes of the ships still afloat, never on
+// where the hidden ships are.
+pub fn suggest_target(board: &BoardView) -> (usize, usize) {
+    DensityStrategy.choose_target(board, &mut StdRng::seed_from_u64(HINT_SEED))
+}
+
+// Function to decide whether the computer should spend a torpedo, and along which lane. It waits until a fifth
+// of the board has been fired at, never fires one while a hit ship is still afloat to finish off, and then aims
+// along the row or column with the most cells not yet fired at, as long as at least half of that lane is open.
+pub fn torpedo_lane(board: &BoardView) -> Option<Lane> {
+    let size = board.size();
+    let cells = || (0..size).flat_map(move |r| (0..size).map(move |c| (r, c)));
+    let fired = cells()
+        .filter(|&(r, c)| board.has_been_fired_at(r, c))
+        .count();
+    let wounded =
+        cells().any(|(r, c)| board.cell(r, c) == CellState::Hit && !board.is_sunk_at(r, c));
+    if fired * 5 < size * size || wounded {
+        return None;
+    }
+    (0..size)
+        .flat_map(|i| [Lane::Row(i), Lane::Column(i)])
+        .map(|lane| {
+            let open = lane
+                .cells(size)
+                .filter(|&(r, c)| is_untried(board, r, c))
+                .count();
+            (open, lane)
+        })
+        .filter(|&(open, _)| open * 2 >= size)
+        .max_by_key(|&(open, _)| open)
+        .map(|(_, lane)| lane)
+}
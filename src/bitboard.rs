@@ -0,0 +1,100 @@
+use crate::board::MAX_BOARD_SIZE;
+use std::ops::{BitAnd, BitOr, Not};
+
+// How many bits one row takes up. Every board uses the stride of the largest one, so a cell's bit doesn't
+// depend on the board's size.
+const STRIDE: usize = MAX_BOARD_SIZE;
+
+// How many u128 words it takes to give every cell of the largest board its own bit. A 10x10 board fits in the
+// first one.
+const WORDS: usize = (STRIDE * STRIDE).div_ceil(128);
+
+// A set of cells on a board, one bit per cell. Copying one is a handful of words, and combining layers (such
+// as ship cells that haven't been hit) is a bitwise operation per word.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Bitboard([u128; WORDS]);
+
+impl Bitboard {
+    // The set with no cells in it.
+    pub const EMPTY: Bitboard = Bitboard([0; WORDS]);
+
+    // Helper function to find the word and the bit within it that stand for a cell.
+    fn bit(row: usize, col: usize) -> (usize, u128) {
+        let index = row * STRIDE + col;
+        (index / 128, 1 << (index % 128))
+    }
+
+    // Method to check whether a cell is in the set.
+    pub fn contains(&self, row: usize, col: usize) -> bool {
+        let (word, bit) = Self::bit(row, col);
+        self.0[word] & bit != 0
+    }
+
+    // Method to add a cell to the set.
+    pub fn insert(&mut self, row: usize, col: usize) {
+        let (word, bit) = Self::bit(row, col);
+        self.0[word] |= bit;
+    }
+
+    // Method to take a cell out of the set.
+    pub fn remove(&mut self, row: usize, col: usize) {
+        let (word, bit) = Self::bit(row, col);
+        self.0[word] &= !bit;
+    }
+
+    // Method to check whether the set has no cells in it.
+    pub fn is_empty(&self) -> bool {
+        self.0.iter().all(|&word| word == 0)
+    }
+
+    // Method to count the cells in the set.
+    pub fn count(&self) -> usize {
+        self.0.iter().map(|word| word.count_ones() as usize).sum()
+    }
+
+    // Method to list the cells in the set, row by row.
+    pub fn cells(&self) -> impl Iterator<Item = (usize, usize)> + '_ {
+        self.0.iter().enumerate().flat_map(|(word, &bits)| {
+            (0..128)
+                .filter(move |bit| bits & (1 << bit) != 0)
+                .map(move |bit| {
+                    let index = word * 128 + bit;
+                    (index / STRIDE, index % STRIDE)
+                })
+        })
+    }
+}
+
+impl BitAnd for Bitboard {
+    type Output = Bitboard;
+
+    fn bitand(mut self, other: Bitboard) -> Bitboard {
+        for (word, other) in self.0.iter_mut().zip(other.0) {
+            *word &= other;
+        }
+        self
+    }
+}
+
+impl BitOr for Bitboard {
+    type Output = Bitboard;
+
+    fn bitor(mut self, other: Bitboard) -> Bitboard {
+        for (word, other) in self.0.iter_mut().zip(other.0) {
+            *word |= other;
+        }
+        self
+    }
+}
+
+// The complement takes in the bits past the board's edge as well, so it's only meant for masking another set.
+impl Not for Bitboard {
+    type Output = Bitboard;
+
+    fn not(mut self) -> Bitboard {
+        for word in &mut self.0 {
+            *word = !*word;
+        }
+        self
+    }
+}
@@ -0,0 +1,1032 @@
+use crate::bitboard::Bitboard;
+use crate::fleet::FleetConfig;
+use rand::seq::SliceRandom;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::ops::Range;
+
+// default size of the game board when none is requested
+pub const DEFAULT_BOARD_SIZE: usize = 10;
+
+// largest board that can still be addressed with a single column letter
+pub const MAX_BOARD_SIZE: usize = 26;
+
+// Function to check whether (row, col) lies on a board of the given size. Everything that takes coordinates
+// from outside the board itself (typed input, the AI, saved files) checks them with this.
+pub fn is_valid_coord(row: usize, col: usize, size: usize) -> bool {
+    row < size && col < size
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Board {
+    grid: Grid,
+    size: usize,
+    ships: Vec<Ship>,
+    no_touching: bool,
+    #[serde(default)]
+    spread: bool,
+    #[serde(default)]
+    diagonal: bool,
+    #[serde(default)]
+    stats: Stats,
+    #[serde(default)]
+    last_shot: Option<(usize, usize)>,
+    #[serde(default)]
+    revealed: Vec<(usize, usize)>,
+}
+
+// The cells of a board kept as four layers of bits: where the ships are (hit or not), which cells were hit,
+// which were missed and which are land. Save files still hold it as rows of cell states, so older saves load unchanged, and the
+// shape read from a file is kept for `Board::validate` to check against the board's size.
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(try_from = "Vec<Vec<CellState>>", into = "Vec<Vec<CellState>>")]
+struct Grid {
+    rows: usize,
+    cols: usize,
+    ships: Bitboard,
+    hits: Bitboard,
+    misses: Bitboard,
+    land: Bitboard,
+}
+
+impl Grid {
+    // Constructor for an empty size x size grid.
+    fn new(size: usize) -> Self {
+        Grid {
+            rows: size,
+            cols: size,
+            ships: Bitboard::EMPTY,
+            hits: Bitboard::EMPTY,
+            misses: Bitboard::EMPTY,
+            land: Bitboard::EMPTY,
+        }
+    }
+
+    // Method to read a cell's state back out of the layers. Misses and land never share a cell with a ship.
+    fn get(&self, row: usize, col: usize) -> CellState {
+        if self.land.contains(row, col) {
+            CellState::Land
+        } else if self.ships.contains(row, col) {
+            if self.hits.contains(row, col) {
+                CellState::Hit
+            } else {
+                CellState::Ship
+            }
+        } else if self.misses.contains(row, col) {
+            CellState::Miss
+        } else {
+            CellState::Empty
+        }
+    }
+
+    // Method to set a cell's state. A hit cell keeps its place in the ship layer, so taking the hit back leaves
+    // the ship where it was.
+    fn set(&mut self, row: usize, col: usize, state: CellState) {
+        self.hits.remove(row, col);
+        self.misses.remove(row, col);
+        self.land.remove(row, col);
+        match state {
+            CellState::Empty => self.ships.remove(row, col),
+            CellState::Ship => self.ships.insert(row, col),
+            CellState::Hit => {
+                self.ships.insert(row, col);
+                self.hits.insert(row, col);
+            }
+            CellState::Miss => {
+                self.ships.remove(row, col);
+                self.misses.insert(row, col);
+            }
+            CellState::Land => {
+                self.ships.remove(row, col);
+                self.land.insert(row, col);
+            }
+        }
+    }
+}
+
+impl TryFrom<Vec<Vec<CellState>>> for Grid {
+    type Error = String;
+
+    fn try_from(cells: Vec<Vec<CellState>>) -> Result<Self, String> {
+        let cols = cells.first().map_or(0, Vec::len);
+        if cells.iter().any(|row| row.len() != cols) {
+            return Err("the grid's rows aren't all the same length".to_string());
+        }
+        if cells.len() > MAX_BOARD_SIZE || cols > MAX_BOARD_SIZE {
+            return Err(format!("the grid is larger than {0}x{0}", MAX_BOARD_SIZE));
+        }
+        let mut grid = Grid::new(0);
+        grid.rows = cells.len();
+        grid.cols = cols;
+        for (row, states) in cells.into_iter().enumerate() {
+            for (col, state) in states.into_iter().enumerate() {
+                grid.set(row, col, state);
+            }
+        }
+        Ok(grid)
+    }
+}
+
+impl From<Grid> for Vec<Vec<CellState>> {
+    fn from(grid: Grid) -> Self {
+        (0..grid.rows)
+            .map(|row| (0..grid.cols).map(|col| grid.get(row, col)).collect())
+            .collect()
+    }
+}
+
+// Tally of the shots fired at a board. Repeated shots at an already fired cell aren't counted.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct Stats {
+    pub shots: usize,
+    pub hits: usize,
+    pub misses: usize,
+}
+
+impl Stats {
+    // Percentage of shots that hit a ship, or 0 before any shots have been fired.
+    pub fn accuracy(&self) -> f64 {
+        if self.shots == 0 {
+            0.0
+        } else {
+            self.hits as f64 * 100.0 / self.shots as f64
+        }
+    }
+}
+
+// A single vessel on the board, remembering where it was placed and which cells it covers.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Ship {
+    pub name: String,
+    pub size: usize,
+    pub orientation: Orientation,
+    pub origin: (usize, usize),
+    pub cells: Vec<(usize, usize)>,
+    pub hits: usize,
+}
+
+impl Ship {
+    // A ship is sunk once every one of its cells has taken a hit.
+    pub fn is_sunk(&self) -> bool {
+        self.hits == self.cells.len()
+    }
+}
+
+// Which way a ship runs from its origin cell: rightwards along a row, downwards along a column, or (under the
+// diagonal rule) downwards to the right or to the left.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub enum Orientation {
+    Horizontal,
+    Vertical,
+    DiagonalDownRight,
+    DiagonalDownLeft,
+}
+
+impl Orientation {
+    // Both straight orientations, for code that tries every way a ship could lie under the usual rules.
+    pub const ALL: [Orientation; 2] = [Orientation::Horizontal, Orientation::Vertical];
+
+    // Every orientation, diagonals included, for boards with the diagonal rule on.
+    pub const WITH_DIAGONALS: [Orientation; 4] = [
+        Orientation::Horizontal,
+        Orientation::Vertical,
+        Orientation::DiagonalDownRight,
+        Orientation::DiagonalDownLeft,
+    ];
+
+    // Method to check whether a ship of the given size starting at (row, col) and running this way stays on a
+    // board of the given size.
+    pub fn fits(self, row: usize, col: usize, size: usize, board_size: usize) -> bool {
+        let rows_fit = row + size <= board_size;
+        let cols_fit = col + size <= board_size;
+        is_valid_coord(row, col, board_size)
+            && match self {
+                Orientation::Horizontal => cols_fit,
+                Orientation::Vertical => rows_fit,
+                Orientation::DiagonalDownRight => rows_fit && cols_fit,
+                Orientation::DiagonalDownLeft => rows_fit && col + 1 >= size,
+            }
+    }
+
+    // Method to check whether this is one of the diagonal orientations.
+    pub fn is_diagonal(self) -> bool {
+        matches!(
+            self,
+            Orientation::DiagonalDownRight | Orientation::DiagonalDownLeft
+        )
+    }
+}
+
+// A whole row or column of the board, which a torpedo runs along: rows from the left edge rightwards, and
+// columns from the top edge downwards.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub enum Lane {
+    Row(usize),
+    Column(usize),
+}
+
+impl Lane {
+    // Method to list the lane's cells in the order a torpedo reaches them on a board of the given size.
+    pub fn cells(self, size: usize) -> impl Iterator<Item = (usize, usize)> {
+        (0..size).map(move |i| match self {
+            Lane::Row(row) => (row, i),
+            Lane::Column(col) => (i, col),
+        })
+    }
+
+    // Method to check whether the lane lies on a board of the given size.
+    pub fn is_on_board(self, size: usize) -> bool {
+        match self {
+            Lane::Row(index) | Lane::Column(index) => index < size,
+        }
+    }
+}
+
+impl fmt::Display for Lane {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Lane::Row(row) => write!(f, "row {}", row),
+            Lane::Column(col) => write!(f, "column {}", (b'A' + *col as u8) as char),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub enum CellState {
+    Empty,
+    Ship,
+    Hit,
+    Miss,
+    Land,
+}
+
+// Result of firing at a cell, reporting the name of the ship when the shot finishes it off. A cell that was
+// already fired at, one that is land, or one that isn't on the board at all, leaves the board as it was.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub enum ShotOutcome {
+    Miss,
+    Hit,
+    Sunk(String),
+    AlreadyFired,
+    OffBoard,
+    Land,
+}
+
+// Result of firing a torpedo: the cell it struck and what that shot did, or nothing if it ran the whole lane
+// without meeting a ship.
+#[derive(Clone, Debug, PartialEq)]
+pub enum TorpedoOutcome {
+    Struck {
+        row: usize,
+        col: usize,
+        outcome: ShotOutcome,
+    },
+    Missed,
+}
+
+// Function listing the cells a ship would cover. Only meaningful for a ship that `Orientation::fits` the board,
+// as one running down to the left past the left edge wraps around to huge column numbers.
+pub(crate) fn ship_cells(
+    row: usize,
+    col: usize,
+    size: usize,
+    orientation: Orientation,
+) -> impl Iterator<Item = (usize, usize)> {
+    (0..size).map(move |i| match orientation {
+        Orientation::Horizontal => (row, col + i),
+        Orientation::Vertical => (row + i, col),
+        Orientation::DiagonalDownRight => (row + i, col + i),
+        Orientation::DiagonalDownLeft => (row + i, col.wrapping_sub(i)),
+    })
+}
+
+// Reasons a ship can't be placed at a requested location.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum PlacementError {
+    OutOfBounds,
+    Overlap,
+    Adjacent,
+    NoRoom,
+    Diagonal,
+    Land,
+}
+
+impl fmt::Display for PlacementError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            PlacementError::OutOfBounds => write!(f, "the ship would stick out of the board"),
+            PlacementError::Overlap => write!(f, "the ship would overlap another ship"),
+            PlacementError::Adjacent => write!(f, "the ship would touch another ship"),
+            PlacementError::NoRoom => write!(f, "there is no room left on the board for it"),
+            PlacementError::Diagonal => write!(f, "ships can't lie diagonally in this game"),
+            PlacementError::Land => write!(f, "the ship would run aground on land"),
+        }
+    }
+}
+
+// How many random positions `place_ship` tries before deciding there is no room for a ship.
+const PLACEMENT_ATTEMPTS: usize = 1000;
+
+// How many times `place_fleet` starts over before giving up on a fleet.
+const FLEET_ATTEMPTS: usize = 100;
+
+// Implement methods for the Board struct.
+impl Board {
+    // Constructor for Board, initializes a size x size grid with all cells empty and no ships.
+    pub fn new(size: usize) -> Self {
+        Board {
+            grid: Grid::new(size),
+            size,
+            ships: Vec::new(),
+            no_touching: false,
+            spread: false,
+            diagonal: false,
+            stats: Stats::default(),
+            last_shot: None,
+            revealed: Vec::new(),
+        }
+    }
+
+    // Method to randomly place a named ship of given size on the board using the given random number generator,
+    // ensuring it doesn't overlap or go out of bounds. With spread placement on, it first tries to fit the ship
+    // wholly inside the quarter of the board holding the fewest ship cells. After `PLACEMENT_ATTEMPTS` random
+    // tries anywhere it scans every position in order, and only gives up with `NoRoom` if the ship fits nowhere
+    // at all.
+    pub fn place_ship(
+        &mut self,
+        name: &str,
+        size: usize,
+        rng: &mut impl Rng,
+    ) -> Result<(), PlacementError> {
+        if self.spread {
+            if let Some((rows, cols)) = self.emptiest_quarter(rng) {
+                if self.place_ship_in_quarter(name, size, rows, cols, rng) {
+                    return Ok(());
+                }
+            }
+        }
+        let orientations = self.orientations();
+        for _ in 0..PLACEMENT_ATTEMPTS {
+            let row = rng.gen_range(0..self.size);
+            let col = rng.gen_range(0..self.size);
+            let orientation = orientations[rng.gen_range(0..orientations.len())];
+            if self
+                .place_ship_at(name, row, col, size, orientation)
+                .is_ok()
+            {
+                return Ok(());
+            }
+        }
+        // Random tries can keep missing the last few spots that fit, so check every position before giving up
+        for row in 0..self.size {
+            for col in 0..self.size {
+                for &orientation in orientations {
+                    if self
+                        .place_ship_at(name, row, col, size, orientation)
+                        .is_ok()
+                    {
+                        return Ok(());
+                    }
+                }
+            }
+        }
+        Err(PlacementError::NoRoom)
+    }
+
+    // Helper method to try `PLACEMENT_ATTEMPTS` random positions for a ship lying wholly within a quarter of the
+    // board, given as its rows and columns. Returns whether one of them worked.
+    fn place_ship_in_quarter(
+        &mut self,
+        name: &str,
+        size: usize,
+        rows: Range<usize>,
+        cols: Range<usize>,
+        rng: &mut impl Rng,
+    ) -> bool {
+        let orientations = self.orientations();
+        let rest = size.saturating_sub(1);
+        for _ in 0..PLACEMENT_ATTEMPTS {
+            let orientation = orientations[rng.gen_range(0..orientations.len())];
+            // Leave room for the rest of the ship before the end of the ranges it runs along
+            let shorten = |range: &Range<usize>| range.start..range.end.saturating_sub(rest);
+            let (row_range, col_range) = match orientation {
+                Orientation::Horizontal => (rows.clone(), shorten(&cols)),
+                Orientation::Vertical => (shorten(&rows), cols.clone()),
+                Orientation::DiagonalDownRight => (shorten(&rows), shorten(&cols)),
+                Orientation::DiagonalDownLeft => (shorten(&rows), cols.start + rest..cols.end),
+            };
+            if row_range.is_empty() || col_range.is_empty() {
+                continue;
+            }
+            let row = rng.gen_range(row_range);
+            let col = rng.gen_range(col_range);
+            if self
+                .place_ship_at(name, row, col, size, orientation)
+                .is_ok()
+            {
+                return true;
+            }
+        }
+        false
+    }
+
+    // Helper method to find the quarter of the board with the fewest ship cells, as its rows and columns, picking
+    // at random between quarters that tie. None for a 1x1 board, which can't be split.
+    fn emptiest_quarter(&self, rng: &mut impl Rng) -> Option<(Range<usize>, Range<usize>)> {
+        let middle = self.size.div_ceil(2);
+        if middle == self.size {
+            return None;
+        }
+        let mut counts = [0; 4];
+        for ship in &self.ships {
+            for &(r, c) in &ship.cells {
+                counts[usize::from(r >= middle) * 2 + usize::from(c >= middle)] += 1;
+            }
+        }
+        let fewest = counts.iter().copied().min().unwrap_or(0);
+        let quarters: Vec<usize> = (0..4).filter(|&q| counts[q] == fewest).collect();
+        let quarter = quarters[rng.gen_range(0..quarters.len())];
+        let half = |upper: bool| {
+            if upper {
+                middle..self.size
+            } else {
+                0..middle
+            }
+        };
+        Some((half(quarter >= 2), half(quarter % 2 == 1)))
+    }
+
+    // Method to randomly place a whole fleet on an empty board. With the no-touching rule the first ships can
+    // occasionally leave no room for the rest, so when a ship can't be placed the board is cleared and the
+    // fleet placed again from scratch, up to `FLEET_ATTEMPTS` times.
+    pub fn place_fleet(
+        &mut self,
+        fleet: &FleetConfig,
+        rng: &mut impl Rng,
+    ) -> Result<(), PlacementError> {
+        for _ in 0..FLEET_ATTEMPTS {
+            // Starting over clears the ships but leaves any land where it is
+            self.grid = Grid {
+                land: self.grid.land,
+                ..Grid::new(self.size)
+            };
+            self.ships.clear();
+            if fleet
+                .ships
+                .iter()
+                .all(|(name, size)| self.place_ship(name, *size, rng).is_ok())
+            {
+                return Ok(());
+            }
+        }
+        Err(PlacementError::NoRoom)
+    }
+
+    // Method to place a named ship at a specific location, running right, down or (under the diagonal rule)
+    // diagonally down from it.
+    pub fn place_ship_at(
+        &mut self,
+        name: &str,
+        row: usize,
+        col: usize,
+        size: usize,
+        orientation: Orientation,
+    ) -> Result<(), PlacementError> {
+        if orientation.is_diagonal() && !self.diagonal {
+            return Err(PlacementError::Diagonal);
+        }
+        if !orientation.fits(row, col, size, self.size) {
+            return Err(PlacementError::OutOfBounds);
+        }
+        // Check if the chosen position can accommodate the ship without overlapping (or, if forbidden, touching) another one.
+        if !self.can_place_ship(row, col, size, orientation) {
+            let cells = || ship_cells(row, col, size, orientation);
+            let aground = cells().any(|(r, c)| self.grid.get(r, c) == CellState::Land);
+            let overlaps = cells().any(|(r, c)| self.grid.get(r, c) != CellState::Empty);
+            return Err(if aground {
+                PlacementError::Land
+            } else if overlaps {
+                PlacementError::Overlap
+            } else {
+                PlacementError::Adjacent
+            });
+        }
+
+        let cells: Vec<(usize, usize)> = ship_cells(row, col, size, orientation).collect();
+        for &(r, c) in &cells {
+            self.grid.set(r, c, CellState::Ship);
+        }
+        self.ships.push(Ship {
+            name: name.to_string(),
+            size,
+            orientation,
+            origin: (row, col),
+            cells,
+            hits: 0,
+        });
+        Ok(())
+    }
+
+    // Helper method to check if a ship can be placed at a specified location without conflicts.
+    pub fn can_place_ship(
+        &self,
+        row: usize,
+        col: usize,
+        size: usize,
+        orientation: Orientation,
+    ) -> bool {
+        if orientation.is_diagonal() && !self.diagonal {
+            return false;
+        }
+        // The start has to be on the board too, not just the end along the ship's length
+        if !orientation.fits(row, col, size, self.size) {
+            return false;
+        }
+        if ship_cells(row, col, size, orientation)
+            .any(|(r, c)| self.grid.get(r, c) != CellState::Empty)
+        {
+            return false;
+        }
+        !(self.no_touching && self.touches_ship(row, col, size, orientation))
+    }
+
+    // Helper method to check if any of the eight cells surrounding a ship's segments already holds a ship.
+    fn touches_ship(&self, row: usize, col: usize, size: usize, orientation: Orientation) -> bool {
+        ship_cells(row, col, size, orientation).any(|(r, c)| {
+            let rows = r.saturating_sub(1)..=(r + 1).min(self.size - 1);
+            rows.into_iter().any(|nr| {
+                let cols = c.saturating_sub(1)..=(c + 1).min(self.size - 1);
+                cols.into_iter()
+                    .any(|nc| (nr, nc) != (r, c) && self.grid.ships.contains(nr, nc))
+            })
+        })
+    }
+
+    // Method to check whether any of the eight cells around a cell holds part of a ship, hit or not.
+    pub fn is_near_ship(&self, row: usize, col: usize) -> bool {
+        if !is_valid_coord(row, col, self.size) {
+            return false;
+        }
+        let rows = row.saturating_sub(1)..=(row + 1).min(self.size - 1);
+        rows.into_iter().any(|r| {
+            let cols = col.saturating_sub(1)..=(col + 1).min(self.size - 1);
+            cols.into_iter()
+                .any(|c| (r, c) != (row, col) && self.grid.ships.contains(r, c))
+        })
+    }
+
+    // Method to make a copy of the board as it was before any shots: same size, rules and ships, all afloat.
+    pub fn unfired(&self) -> Board {
+        let mut board = Board::new(self.size);
+        board.no_touching = self.no_touching;
+        board.spread = self.spread;
+        board.diagonal = self.diagonal;
+        board.grid.land = self.grid.land;
+        for ship in &self.ships {
+            for &(r, c) in &ship.cells {
+                board.grid.set(r, c, CellState::Ship);
+            }
+            board.ships.push(Ship {
+                hits: 0,
+                ..ship.clone()
+            });
+        }
+        board
+    }
+
+    // Method to toggle the rule that ships may not touch each other, not even diagonally.
+    pub fn set_no_touching(&mut self, enabled: bool) {
+        self.no_touching = enabled;
+    }
+
+    // Accessor for whether the no-touching rule is on.
+    pub fn no_touching(&self) -> bool {
+        self.no_touching
+    }
+
+    // Method to toggle the rule that ships may also lie diagonally, running down to the right or to the left.
+    pub fn set_diagonal(&mut self, enabled: bool) {
+        self.diagonal = enabled;
+    }
+
+    // Accessor for whether the diagonal rule is on.
+    pub fn diagonal(&self) -> bool {
+        self.diagonal
+    }
+
+    // Method to list the ways a ship may lie on this board: the straight orientations, and the diagonals too
+    // under the diagonal rule.
+    pub fn orientations(&self) -> &'static [Orientation] {
+        if self.diagonal {
+            &Orientation::WITH_DIAGONALS
+        } else {
+            &Orientation::ALL
+        }
+    }
+
+    // Method to toggle spread placement, which steers randomly placed ships towards the emptier parts of the
+    // board instead of anywhere at all.
+    pub fn set_spread(&mut self, enabled: bool) {
+        self.spread = enabled;
+    }
+
+    // Method to turn an open cell into land, where no ship can be placed. Cells off the board, or that hold a
+    // ship or were fired at, are left alone.
+    pub fn raise_land(&mut self, row: usize, col: usize) {
+        if is_valid_coord(row, col, self.size) && self.grid.get(row, col) == CellState::Empty {
+            self.grid.set(row, col, CellState::Land);
+        }
+    }
+
+    // Method to list the cells that are land, row by row.
+    pub fn land(&self) -> Vec<(usize, usize)> {
+        self.grid.land.cells().collect()
+    }
+
+    // Method to scatter up to `count` cells of land across an empty board at random, before any ships are
+    // placed. While the land leaves no room for the fleet, one cell of it at a time sinks back into the sea,
+    // so the fleet always still fits. Returns how many cells became land.
+    pub fn raise_islands(
+        &mut self,
+        count: usize,
+        fleet: &FleetConfig,
+        rng: &mut impl Rng,
+    ) -> usize {
+        let cells: Vec<(usize, usize)> = (0..self.size)
+            .flat_map(|r| (0..self.size).map(move |c| (r, c)))
+            .collect();
+        // The fleet needs open water for every one of its cells, however the land lies
+        let fleet_cells: usize = fleet.ships.iter().map(|&(_, size)| size).sum();
+        let count = count.min(cells.len().saturating_sub(fleet_cells));
+        if count == 0 {
+            return 0;
+        }
+        for &(r, c) in cells.choose_multiple(rng, count) {
+            self.raise_land(r, c);
+        }
+        while self.clone().place_fleet(fleet, rng).is_err() {
+            let Some(&(row, col)) = self.land().choose(rng) else {
+                break;
+            };
+            self.grid.set(row, col, CellState::Empty);
+        }
+        self.grid.land.count()
+    }
+
+    // Method for firing at a specified cell, changing its state based on whether a ship is hit or not. A cell
+    // off the board is answered with `OffBoard` rather than looked up, wherever the coordinates came from.
+    pub fn fire(&mut self, row: usize, col: usize) -> ShotOutcome {
+        if !is_valid_coord(row, col, self.size) {
+            return ShotOutcome::OffBoard;
+        }
+        match self.grid.get(row, col) {
+            CellState::Empty => {
+                self.last_shot = Some((row, col));
+                self.grid.set(row, col, CellState::Miss);
+                self.stats.shots += 1;
+                self.stats.misses += 1;
+                ShotOutcome::Miss
+            }
+            CellState::Ship => {
+                self.last_shot = Some((row, col));
+                self.grid.set(row, col, CellState::Hit);
+                self.stats.shots += 1;
+                self.stats.hits += 1;
+                if let Some(ship) = self
+                    .ships
+                    .iter_mut()
+                    .find(|ship| ship.cells.contains(&(row, col)))
+                {
+                    ship.hits += 1;
+                }
+                match self.check_sunk(row, col) {
+                    Some(ship) => ShotOutcome::Sunk(ship.name.clone()),
+                    None => ShotOutcome::Hit,
+                }
+            }
+            // A shot at land strikes the shore, which isn't counted as a miss
+            CellState::Land => ShotOutcome::Land,
+            // Cells that were already hit or missed are left untouched so the shot can be retaken.
+            _ => ShotOutcome::AlreadyFired,
+        }
+    }
+
+    // Method to fire a torpedo along a lane. It passes over cells that were already fired at and strikes the
+    // first part of a ship that hasn't been hit, which counts as a shot there. The water it crosses is left
+    // unmarked, so a torpedo that misses only uses up the torpedo.
+    pub fn fire_torpedo(&mut self, lane: Lane) -> TorpedoOutcome {
+        if !lane.is_on_board(self.size) {
+            return TorpedoOutcome::Missed;
+        }
+        match lane
+            .cells(self.size)
+            .find(|&(r, c)| self.grid.get(r, c) == CellState::Ship)
+        {
+            Some((row, col)) => TorpedoOutcome::Struck {
+                row,
+                col,
+                outcome: self.fire(row, col),
+            },
+            None => TorpedoOutcome::Missed,
+        }
+    }
+
+    // Method to scan the 3x3 area centred on a cell without firing at it, returning how many unhit ship parts it
+    // holds. At the edges the area is shifted inwards to stay on the board (a board smaller than 3x3 is scanned
+    // whole). Every cell scanned stays revealed for the rest of the game.
+    pub fn scan(&mut self, row: usize, col: usize) -> usize {
+        let start = |index: usize| index.saturating_sub(1).min(self.size.saturating_sub(3));
+        let (top, left) = (start(row), start(col));
+        let mut found = 0;
+        for r in top..(top + 3).min(self.size) {
+            for c in left..(left + 3).min(self.size) {
+                if self.grid.get(r, c) == CellState::Ship {
+                    found += 1;
+                }
+                if !self.revealed.contains(&(r, c)) {
+                    self.revealed.push((r, c));
+                }
+            }
+        }
+        found
+    }
+
+    // Method to sweep a lane with radar, checking whether any unhit ship part lies along it. Nothing is fired at
+    // or revealed, so the sweep only tells whether there's a ship there, not where.
+    pub fn sweep(&self, lane: Lane) -> bool {
+        lane.is_on_board(self.size)
+            && lane
+                .cells(self.size)
+                .any(|(r, c)| self.grid.get(r, c) == CellState::Ship)
+    }
+
+    // Method to check whether a cell has been revealed by a scan.
+    pub fn is_revealed(&self, row: usize, col: usize) -> bool {
+        self.revealed.contains(&(row, col))
+    }
+
+    // Method to take back a shot fired by `fire`, turning a hit back into a ship (afloat again if it had sunk)
+    // and a miss back into open water. Cells that weren't fired at, or are off the board, are left alone. The
+    // shot before it isn't known, so no shot is marked as the latest afterwards.
+    pub fn undo_shot(&mut self, row: usize, col: usize) {
+        if !is_valid_coord(row, col, self.size) {
+            return;
+        }
+        self.last_shot = None;
+        match self.grid.get(row, col) {
+            CellState::Miss => {
+                self.grid.set(row, col, CellState::Empty);
+                self.stats.shots = self.stats.shots.saturating_sub(1);
+                self.stats.misses = self.stats.misses.saturating_sub(1);
+            }
+            CellState::Hit => {
+                self.grid.set(row, col, CellState::Ship);
+                self.stats.shots = self.stats.shots.saturating_sub(1);
+                self.stats.hits = self.stats.hits.saturating_sub(1);
+                if let Some(ship) = self
+                    .ships
+                    .iter_mut()
+                    .find(|ship| ship.cells.contains(&(row, col)))
+                {
+                    ship.hits = ship.hits.saturating_sub(1);
+                }
+            }
+            _ => (),
+        }
+    }
+
+    // Method to mark the result of a shot on a board whose ships aren't known, such as the other side's board
+    // in a network game, where only the reported outcome comes back. A cell off the board is ignored.
+    pub fn record_shot(&mut self, row: usize, col: usize, outcome: &ShotOutcome) {
+        if !is_valid_coord(row, col, self.size) {
+            return;
+        }
+        if !matches!(
+            outcome,
+            ShotOutcome::AlreadyFired | ShotOutcome::OffBoard | ShotOutcome::Land
+        ) {
+            self.last_shot = Some((row, col));
+        }
+        match outcome {
+            ShotOutcome::Miss => {
+                self.grid.set(row, col, CellState::Miss);
+                self.stats.shots += 1;
+                self.stats.misses += 1;
+            }
+            ShotOutcome::Hit | ShotOutcome::Sunk(_) => {
+                self.grid.set(row, col, CellState::Hit);
+                self.stats.shots += 1;
+                self.stats.hits += 1;
+            }
+            ShotOutcome::AlreadyFired | ShotOutcome::OffBoard | ShotOutcome::Land => (),
+        }
+    }
+
+    // Accessor for the width and height of the board.
+    pub fn size(&self) -> usize {
+        self.size
+    }
+
+    // Accessor for the cell most recently fired at, if any shot has been.
+    pub fn last_shot(&self) -> Option<(usize, usize)> {
+        self.last_shot
+    }
+
+    // Accessor for the state of a single cell.
+    pub fn cell(&self, row: usize, col: usize) -> CellState {
+        self.grid.get(row, col)
+    }
+
+    // Method to check whether a cell has already been fired at, whether it was a hit or a miss.
+    pub fn has_been_fired_at(&self, row: usize, col: usize) -> bool {
+        self.grid.hits.contains(row, col) || self.grid.misses.contains(row, col)
+    }
+
+    // Accessor for the tally of shots fired at this board.
+    pub fn stats(&self) -> Stats {
+        self.stats
+    }
+
+    // Accessor for the ships placed on this board.
+    pub fn ships(&self) -> &[Ship] {
+        &self.ships
+    }
+
+    // Method to find the ship occupying a cell, if that ship has now been completely hit.
+    pub fn check_sunk(&self, row: usize, col: usize) -> Option<&Ship> {
+        self.ships
+            .iter()
+            .find(|ship| ship.cells.contains(&(row, col)))
+            .filter(|ship| ship.is_sunk())
+    }
+
+    // Method to list the sizes of the ships that haven't been sunk yet.
+    pub fn remaining_ships(&self) -> Vec<usize> {
+        self.ships
+            .iter()
+            .filter(|ship| !ship.is_sunk())
+            .map(|ship| ship.size)
+            .collect()
+    }
+
+    // Method to count the ships that haven't been sunk yet.
+    pub fn ships_afloat(&self) -> usize {
+        self.ships.iter().filter(|ship| !ship.is_sunk()).count()
+    }
+
+    // Method to determine if all ships have been hit, indicating game over: no ship cell is left unhit.
+    pub fn is_game_over(&self) -> bool {
+        (self.grid.ships & !self.grid.hits).is_empty()
+    }
+
+    // Method to check that a board read from a save file is consistent: the grid matches the board's size,
+    // every ship lies on Ship or Hit cells inside the grid, no other cell claims to hold a ship, and each
+    // ship's hit count matches its hit cells.
+    pub fn validate(&self) -> Result<(), String> {
+        if self.size == 0 || self.size > MAX_BOARD_SIZE {
+            return Err(format!("board size {} is out of range", self.size));
+        }
+        if self.grid.rows != self.size || self.grid.cols != self.size {
+            return Err(format!("the grid is not {0}x{0}", self.size));
+        }
+        let mut ship_cells = vec![vec![false; self.size]; self.size];
+        for ship in &self.ships {
+            if ship.cells.is_empty() {
+                return Err(format!("the {} has no cells", ship.name));
+            }
+            let mut hits = 0;
+            for &(row, col) in &ship.cells {
+                if !is_valid_coord(row, col, self.size) {
+                    return Err(format!("the {} lies outside the grid", ship.name));
+                }
+                if ship_cells[row][col] {
+                    return Err(format!("the {} overlaps another ship", ship.name));
+                }
+                ship_cells[row][col] = true;
+                match self.grid.get(row, col) {
+                    CellState::Ship => {}
+                    CellState::Hit => hits += 1,
+                    _ => {
+                        return Err(format!(
+                            "the {} doesn't match the grid at row {}, column {}",
+                            ship.name, row, col
+                        ))
+                    }
+                }
+            }
+            if hits != ship.hits {
+                return Err(format!(
+                    "the {} records {} hits but the grid shows {}",
+                    ship.name, ship.hits, hits
+                ));
+            }
+        }
+        for (row, col) in self.grid.ships.cells() {
+            if !ship_cells[row][col] {
+                return Err(format!("row {}, column {} has no ship", row, col));
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Default for Board {
+    fn default() -> Self {
+        Self::new(DEFAULT_BOARD_SIZE)
+    }
+}
+
+// A read-only view of a board as the side firing at it sees it: the shots taken so far, which ships have been
+// sunk and the sizes still afloat, but never where the remaining ships are. Computer players only ever get
+// one of these, so they can't peek at the board.
+#[derive(Clone, Copy)]
+pub struct BoardView<'a> {
+    board: &'a Board,
+    reported: Option<ReportedFleet<'a>>,
+}
+
+// What's known of a fleet whose ships aren't on the board: the sizes still afloat and the cells of the ships
+// sunk so far.
+#[derive(Clone, Copy)]
+struct ReportedFleet<'a> {
+    remaining: &'a [usize],
+    sunk: &'a [(usize, usize)],
+}
+
+impl<'a> BoardView<'a> {
+    // Constructor for the view of a board.
+    pub fn new(board: &'a Board) -> Self {
+        BoardView {
+            board,
+            reported: None,
+        }
+    }
+
+    // Constructor for the view of a board filled in with `Board::record_shot`, which holds no ships, so the
+    // sizes still afloat and the cells of the ships sunk so far come from what the other side has reported.
+    pub fn with_reported_fleet(
+        board: &'a Board,
+        remaining: &'a [usize],
+        sunk: &'a [(usize, usize)],
+    ) -> Self {
+        BoardView {
+            board,
+            reported: Some(ReportedFleet { remaining, sunk }),
+        }
+    }
+
+    // Accessor for the width and height of the board.
+    pub fn size(&self) -> usize {
+        self.board.size
+    }
+
+    // Accessor for the cell most recently fired at, if any shot has been.
+    pub fn last_shot(&self) -> Option<(usize, usize)> {
+        self.board.last_shot
+    }
+
+    // Accessor for whether a scan has revealed what's in a cell.
+    pub fn is_revealed(&self, row: usize, col: usize) -> bool {
+        self.board.is_revealed(row, col)
+    }
+
+    // Accessor for a cell as seen from the other side, where a ship nobody has hit looks like open water.
+    pub fn cell(&self, row: usize, col: usize) -> CellState {
+        match self.board.grid.get(row, col) {
+            CellState::Ship => CellState::Empty,
+            state => state,
+        }
+    }
+
+    // Method to check whether a cell has already been fired at.
+    pub fn has_been_fired_at(&self, row: usize, col: usize) -> bool {
+        self.board.has_been_fired_at(row, col)
+    }
+
+    // Method to check whether the hit at a cell belongs to a ship that has been sunk.
+    pub fn is_sunk_at(&self, row: usize, col: usize) -> bool {
+        match self.reported {
+            Some(fleet) => fleet.sunk.contains(&(row, col)),
+            None => self.board.check_sunk(row, col).is_some(),
+        }
+    }
+
+    // Method to list the sizes of the ships that haven't been sunk yet.
+    pub fn remaining_ships(&self) -> Vec<usize> {
+        match self.reported {
+            Some(fleet) => fleet.remaining.to_vec(),
+            None => self.board.remaining_ships(),
+        }
+    }
+
+    // Accessor for whether ships on this board are kept from touching.
+    pub fn no_touching(&self) -> bool {
+        self.board.no_touching
+    }
+
+    // Method to list the ways a ship may lie on this board.
+    pub fn orientations(&self) -> &'static [Orientation] {
+        self.board.orientations()
+    }
+}
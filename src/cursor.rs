@@ -0,0 +1,135 @@
+use crate::board::{BoardView, CellState};
+use crate::style::{Style, Styled};
+use crate::ui::{already_fired_warning, coordinate_label, glyphs};
+use crossterm::cursor::{Hide, MoveTo, Show};
+use crossterm::event::{self, Event, KeyCode, KeyEventKind, KeyModifiers};
+use crossterm::terminal::{self, Clear, ClearType, EnterAlternateScreen, LeaveAlternateScreen};
+use crossterm::{execute, queue};
+use std::io::{self, Write};
+use std::sync::atomic::{AtomicBool, Ordering};
+
+// Whether people aim with a cursor on the board instead of typing coordinates. Off unless `--cursor` is given.
+static ENABLED: AtomicBool = AtomicBool::new(false);
+
+// Function to turn cursor targeting on or off.
+pub fn set_cursor_targeting(enabled: bool) {
+    ENABLED.store(enabled, Ordering::Relaxed);
+}
+
+// Function to check whether cursor targeting is on.
+pub fn cursor_targeting() -> bool {
+    ENABLED.load(Ordering::Relaxed)
+}
+
+// The terminal in raw mode on its alternate screen, for as long as this lives. Dropping it puts the terminal
+// back, which also happens while unwinding from a panic, so the terminal is never left broken.
+struct RawScreen;
+
+impl RawScreen {
+    // Constructor switching the terminal over. The guard exists as soon as raw mode is on, so a failure
+    // after that still switches it back off.
+    fn enter() -> io::Result<Self> {
+        terminal::enable_raw_mode()?;
+        let screen = RawScreen;
+        execute!(io::stdout(), EnterAlternateScreen, Hide)?;
+        Ok(screen)
+    }
+}
+
+impl Drop for RawScreen {
+    fn drop(&mut self) {
+        let _ = execute!(io::stdout(), Show, LeaveAlternateScreen);
+        let _ = terminal::disable_raw_mode();
+    }
+}
+
+// Function to let a person pick a cell on the board they're firing at by moving a cursor with the arrow keys or
+// WASD, starting from their last shot. Enter fires at the cell under the cursor, unless it was already fired
+// at, and Esc gives up so they can type coordinates instead, which returns None. Ctrl-C aborts the game.
+pub fn pick_target(view: &BoardView) -> io::Result<Option<(usize, usize)>> {
+    let last = view.size() - 1;
+    let (mut row, mut col) = view.last_shot().unwrap_or((0, 0));
+    let _screen = RawScreen::enter()?;
+    loop {
+        draw(view, row, col)?;
+        let Event::Key(key) = event::read()? else {
+            continue;
+        };
+        if key.kind != KeyEventKind::Press {
+            continue;
+        }
+        match key.code {
+            KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                return Err(io::Error::new(io::ErrorKind::Interrupted, "interrupted"))
+            }
+            KeyCode::Up | KeyCode::Char('w') | KeyCode::Char('W') => row = row.saturating_sub(1),
+            KeyCode::Down | KeyCode::Char('s') | KeyCode::Char('S') => row = (row + 1).min(last),
+            KeyCode::Left | KeyCode::Char('a') | KeyCode::Char('A') => col = col.saturating_sub(1),
+            KeyCode::Right | KeyCode::Char('d') | KeyCode::Char('D') => col = (col + 1).min(last),
+            KeyCode::Enter if !view.has_been_fired_at(row, col) => return Ok(Some((row, col))),
+            KeyCode::Esc => return Ok(None),
+            _ => (),
+        }
+    }
+}
+
+// Function to redraw the board with the cursor in brackets, which turn into a warning when the cell under them
+// was already fired at. Raw mode doesn't return to the start of the line by itself, so every line ends in
+// "\r\n".
+fn draw(view: &BoardView, row: usize, col: usize) -> io::Result<()> {
+    let glyphs = glyphs();
+    let fired = view.has_been_fired_at(row, col);
+    let mut lines = vec![
+        "Opponent's Board:".styled(Style::Emphasis).to_string(),
+        String::from("   "),
+    ];
+    for c in 0..view.size() {
+        lines[1].push_str(&format!(" {} ", (b'A' + c as u8) as char));
+    }
+    for r in 0..view.size() {
+        let mut line = format!("{:2} ", r);
+        for c in 0..view.size() {
+            let glyph = match view.cell(r, c) {
+                CellState::Hit if view.is_sunk_at(r, c) => glyphs.sunk.styled(Style::Wreck),
+                CellState::Hit => glyphs.hit.styled(Style::Hit),
+                CellState::Miss => glyphs.miss.styled(Style::Miss),
+                CellState::Land => glyphs.land.styled(Style::Land),
+                _ => " ".styled(Style::Miss),
+            };
+            if (r, c) == (row, col) {
+                let bracket = if fired {
+                    Style::Warning
+                } else {
+                    Style::Emphasis
+                };
+                line.push_str(&format!(
+                    "{}{}{}",
+                    "[".styled(bracket),
+                    glyph,
+                    "]".styled(bracket)
+                ));
+            } else {
+                line.push_str(&format!(" {} ", glyph));
+            }
+        }
+        lines.push(line);
+    }
+    lines.push(String::new());
+    if fired {
+        lines.push(
+            already_fired_warning(row, col, view.cell(row, col))
+                .styled(Style::Warning)
+                .to_string(),
+        );
+    } else {
+        lines.push(format!("Target: {}", coordinate_label(row, col)));
+    }
+    lines.push(
+        "Arrow keys or WASD to aim, Enter to fire, Esc to type coordinates instead.".to_string(),
+    );
+
+    let mut stdout = io::stdout().lock();
+    queue!(stdout, MoveTo(0, 0), Clear(ClearType::All))?;
+    write!(stdout, "{}\r\n", lines.join("\r\n"))?;
+    stdout.flush()
+}
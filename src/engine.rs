@@ -0,0 +1,209 @@
+use crate::ai::OpponentAi;
+use crate::board::{is_valid_coord, ship_cells, Board, BoardView, CellState, ShotOutcome};
+use crate::game::GameSettings;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use std::io::{self, BufRead, Write};
+
+// The version of the engine protocol, announced in the first line so a bot can tell what it's talking to.
+pub const ENGINE_PROTOCOL_VERSION: u32 = 1;
+
+// Function to play one game as an engine against an outside bot, reading the bot's lines from `input` and
+// writing the engine's to `output`. The engine plays the computer opponent the settings describe and places its
+// own fleet at random. Everything is one command per line, with rows and columns counted from 0:
+//
+//   engine -> bot   battleship 1           the protocol version
+//   engine -> bot   size 10                the board is 10x10
+//   engine -> bot   ship 5 Carrier         one line per ship in the fleet, its size and then its name
+//   engine -> bot   place                  the bot should place its fleet now
+//   bot -> engine   place                  the bot's fleet is placed
+//
+// Then, starting with the engine, the two take turns firing one shot each:
+//
+//   engine -> bot   fire 3 4               the engine fires at row 3, column 4
+//   bot -> engine   result miss            or "result hit", or "result sunk Carrier" for a sinking shot
+//   bot -> engine   fire 0 0               the bot fires at row 0, column 0
+//   engine -> bot   result hit             in the same form
+//
+// Once a fleet is gone the engine sends "winner engine" or "winner bot" and stops. A line from the bot that
+// can't be used (a command out of turn, a cell off the board or already fired at, a ship reported sunk that
+// isn't in the fleet or was sunk already) is answered with "error <reason>" and the engine waits for the line
+// again. The bot can send "quit" at any point to end the game without a winner.
+pub fn run_engine(
+    settings: &GameSettings,
+    input: &mut impl BufRead,
+    output: &mut impl Write,
+) -> io::Result<()> {
+    let size = settings.size;
+    let mut rng = match settings.seed {
+        Some(seed) => StdRng::seed_from_u64(seed),
+        None => StdRng::from_entropy(),
+    };
+    let mut own_board = Board::new(size);
+    own_board.set_no_touching(settings.no_touching);
+    own_board.set_spread(settings.spread);
+    own_board.set_diagonal(settings.diagonal);
+    own_board
+        .place_fleet(&settings.fleet, &mut rng)
+        .map_err(|error| io::Error::other(error.to_string()))?;
+    let mut ai = OpponentAi::new(settings.difficulty, rng.gen());
+    let mut target_board = Board::new(size);
+    target_board.set_diagonal(settings.diagonal);
+    // The bot's board holds no ships, so what's known of its fleet is kept alongside, from the ships it reports
+    // sunk
+    let mut afloat = settings.fleet.ships.clone();
+    let mut sunk_cells = Vec::new();
+    let fleet_cells: usize = settings.fleet.ships.iter().map(|&(_, size)| size).sum();
+
+    writeln!(output, "battleship {}", ENGINE_PROTOCOL_VERSION)?;
+    writeln!(output, "size {}", size)?;
+    for (name, ship_size) in &settings.fleet.ships {
+        writeln!(output, "ship {} {}", ship_size, name)?;
+    }
+    writeln!(output, "place")?;
+    output.flush()?;
+    loop {
+        match read_command(input)? {
+            Some(line) if line == "place" => break,
+            Some(_) => reply(output, "error expected place")?,
+            None => return Ok(()),
+        }
+    }
+
+    loop {
+        let remaining: Vec<usize> = afloat.iter().map(|&(_, size)| size).collect();
+        let view = BoardView::with_reported_fleet(&target_board, &remaining, &sunk_cells);
+        let (row, col) = ai.choose_target(&view);
+        reply(output, &format!("fire {} {}", row, col))?;
+        let outcome = loop {
+            let Some(line) = read_command(input)? else {
+                return Ok(());
+            };
+            match parse_result(&line) {
+                Some(ShotOutcome::Sunk(name)) if !afloat.iter().any(|(ship, _)| *ship == name) => {
+                    reply(output, "error no ship by that name is afloat")?
+                }
+                Some(outcome) => break outcome,
+                None => reply(output, "error expected result miss, hit or sunk <ship>")?,
+            }
+        };
+        target_board.record_shot(row, col, &outcome);
+        ai.notify((row, col), &outcome);
+        if let ShotOutcome::Sunk(name) = &outcome {
+            if let Some(index) = afloat.iter().position(|(ship, _)| ship == name) {
+                let (_, ship_size) = afloat.remove(index);
+                let cells = sunk_ship_cells(&target_board, &sunk_cells, (row, col), ship_size);
+                sunk_cells.extend(cells);
+            }
+        }
+        if target_board.stats().hits >= fleet_cells {
+            return reply(output, "winner engine");
+        }
+
+        let outcome = loop {
+            let Some(line) = read_command(input)? else {
+                return Ok(());
+            };
+            let (row, col) = match parse_fire(&line) {
+                Some((row, col)) if is_valid_coord(row, col, size) => (row, col),
+                Some(_) => {
+                    reply(output, "error that cell is off the board")?;
+                    continue;
+                }
+                None => {
+                    reply(output, "error expected fire <row> <col>")?;
+                    continue;
+                }
+            };
+            match own_board.fire(row, col) {
+                ShotOutcome::AlreadyFired => reply(output, "error already fired at that cell")?,
+                outcome => break outcome,
+            }
+        };
+        let result = match outcome {
+            ShotOutcome::Hit => "result hit".to_string(),
+            ShotOutcome::Sunk(name) => format!("result sunk {}", name),
+            _ => "result miss".to_string(),
+        };
+        reply(output, &result)?;
+        if own_board.is_game_over() {
+            return reply(output, "winner bot");
+        }
+    }
+}
+
+// Helper function to work out the cells of the ship the bot reports a shot at (row, col) sank: a line of hits
+// through that cell as long as the ship, none of them part of a ship already sunk. Just the cell itself if
+// there's no such line, as when the bot's reports don't add up.
+fn sunk_ship_cells(
+    board: &Board,
+    sunk: &[(usize, usize)],
+    (row, col): (usize, usize),
+    ship_size: usize,
+) -> Vec<(usize, usize)> {
+    let size = board.size();
+    for &orientation in board.orientations() {
+        for start_row in 0..size {
+            for start_col in 0..size {
+                if !orientation.fits(start_row, start_col, ship_size, size) {
+                    continue;
+                }
+                let cells: Vec<_> =
+                    ship_cells(start_row, start_col, ship_size, orientation).collect();
+                if cells.contains(&(row, col))
+                    && cells.iter().all(|&(r, c)| {
+                        board.cell(r, c) == CellState::Hit && !sunk.contains(&(r, c))
+                    })
+                {
+                    return cells;
+                }
+            }
+        }
+    }
+    vec![(row, col)]
+}
+
+// Helper function to read the bot's next line, trimmed, skipping blank lines. None once the bot quits, and an
+// error if its input ends without it quitting.
+fn read_command(input: &mut impl BufRead) -> io::Result<Option<String>> {
+    loop {
+        let mut line = String::new();
+        if input.read_line(&mut line)? == 0 {
+            return Err(io::Error::new(
+                io::ErrorKind::UnexpectedEof,
+                "the bot stopped without quitting",
+            ));
+        }
+        match line.trim() {
+            "" => continue,
+            "quit" => return Ok(None),
+            command => return Ok(Some(command.to_string())),
+        }
+    }
+}
+
+// Helper function to send the bot one line straight away.
+fn reply(output: &mut impl Write, line: &str) -> io::Result<()> {
+    writeln!(output, "{}", line)?;
+    output.flush()
+}
+
+// Function to read a "fire <row> <col>" line.
+fn parse_fire(line: &str) -> Option<(usize, usize)> {
+    match line.split_whitespace().collect::<Vec<_>>()[..] {
+        ["fire", row, col] => Some((row.parse().ok()?, col.parse().ok()?)),
+        _ => None,
+    }
+}
+
+// Function to read a "result miss", "result hit" or "result sunk <ship>" line.
+fn parse_result(line: &str) -> Option<ShotOutcome> {
+    match line.split_whitespace().collect::<Vec<_>>()[..] {
+        ["result", "miss"] => Some(ShotOutcome::Miss),
+        ["result", "hit"] => Some(ShotOutcome::Hit),
+        ["result", "sunk", ref name @ ..] if !name.is_empty() => {
+            Some(ShotOutcome::Sunk(name.join(" ")))
+        }
+        _ => None,
+    }
+}
@@ -0,0 +1,233 @@
+use crate::board::MAX_BOARD_SIZE;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::fs;
+use std::io;
+
+// The standard fleet each side places before the game starts, as (name, size) pairs.
+pub const FLEET: [(&str, usize); 5] = [
+    ("Carrier", 5),
+    ("Battleship", 4),
+    ("Cruiser", 3),
+    ("Submarine", 3),
+    ("Destroyer", 2),
+];
+
+// The rules used when no config file is given: the standard fleet on a 10x10 board.
+const DEFAULT_RULES: &str = include_str!("default_rules.json");
+
+// The named ships each side places before the game starts.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct FleetConfig {
+    pub ships: Vec<(String, usize)>,
+}
+
+// Reasons a fleet file can't be used.
+#[derive(Debug)]
+pub enum FleetError {
+    Io(io::Error),
+    InvalidLine(usize, String),
+    InvalidEntry(String),
+    Empty,
+    Json(serde_json::Error),
+    InvalidSize(usize),
+    EmptyShip(String),
+    DoesNotFit(usize),
+}
+
+impl fmt::Display for FleetError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            FleetError::Io(error) => write!(f, "couldn't read the fleet file: {}", error),
+            FleetError::InvalidLine(line, text) => write!(
+                f,
+                "line {} should be a ship size, optionally after the ship's name, but is '{}'",
+                line, text
+            ),
+            FleetError::InvalidEntry(text) => write!(
+                f,
+                "'{}' should be a ship size, optionally after the ship's name",
+                text
+            ),
+            FleetError::Empty => write!(f, "the fleet file doesn't list any ships"),
+            FleetError::Json(error) => write!(f, "the config file is not valid JSON: {}", error),
+            FleetError::InvalidSize(size) => write!(
+                f,
+                "the board size {} isn't allowed; choose a size from 1 to {}",
+                size, MAX_BOARD_SIZE
+            ),
+            FleetError::EmptyShip(name) => {
+                write!(f, "the {} has a size of 0; every ship needs at least one cell", name)
+            }
+            FleetError::DoesNotFit(size) => write!(
+                f,
+                "the fleet doesn't fit on a {0}x{0} board; every ship must fit in a row and the fleet may cover at most half the board",
+                size
+            ),
+        }
+    }
+}
+
+impl From<io::Error> for FleetError {
+    fn from(error: io::Error) -> Self {
+        FleetError::Io(error)
+    }
+}
+
+impl From<serde_json::Error> for FleetError {
+    fn from(error: serde_json::Error) -> Self {
+        FleetError::Json(error)
+    }
+}
+
+// House rules read from a JSON config file: the board size and the ships each side places, e.g.
+// {"size": 8, "ships": [{"name": "Cruiser", "size": 3}, {"name": "Destroyer", "size": 2}]}.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Rules {
+    pub size: usize,
+    pub ships: Vec<ShipRule>,
+}
+
+// One ship listed in a config file.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ShipRule {
+    pub name: String,
+    pub size: usize,
+}
+
+impl Rules {
+    // Function to read rules from JSON, checking that the board size is allowed and that the fleet has ships
+    // and fits on the board.
+    pub fn parse(text: &str) -> Result<Self, FleetError> {
+        let rules: Rules = serde_json::from_str(text)?;
+        if !(1..=MAX_BOARD_SIZE).contains(&rules.size) {
+            return Err(FleetError::InvalidSize(rules.size));
+        }
+        if rules.ships.is_empty() {
+            return Err(FleetError::Empty);
+        }
+        if let Some(ship) = rules.ships.iter().find(|ship| ship.size == 0) {
+            return Err(FleetError::EmptyShip(ship.name.clone()));
+        }
+        if !rules.fleet().fits(rules.size) {
+            return Err(FleetError::DoesNotFit(rules.size));
+        }
+        Ok(rules)
+    }
+
+    // Function to load rules from a config file in the format `parse` reads.
+    pub fn load(path: &str) -> Result<Self, FleetError> {
+        Self::parse(&fs::read_to_string(path)?)
+    }
+
+    // Method to get the fleet the rules list.
+    pub fn fleet(&self) -> FleetConfig {
+        FleetConfig {
+            ships: self
+                .ships
+                .iter()
+                .map(|ship| (ship.name.clone(), ship.size))
+                .collect(),
+        }
+    }
+}
+
+impl Default for Rules {
+    fn default() -> Self {
+        Self::parse(DEFAULT_RULES).expect("the built-in rules are valid")
+    }
+}
+
+impl FleetConfig {
+    // Constructor for the classic five-ship fleet.
+    pub fn standard() -> Self {
+        FleetConfig {
+            ships: FLEET
+                .iter()
+                .map(|&(name, size)| (name.to_string(), size))
+                .collect(),
+        }
+    }
+
+    // Function to read a fleet from text with one ship per line, written as its name and then its size
+    // (e.g. "Patrol Boat 2"), or just its size. Blank lines and lines starting with '#' are skipped.
+    pub fn parse(text: &str) -> Result<Self, FleetError> {
+        let mut ships = Vec::new();
+        for (index, line) in text.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            ships.push(
+                parse_ship(line)
+                    .ok_or_else(|| FleetError::InvalidLine(index + 1, line.to_string()))?,
+            );
+        }
+        Self::from_ships(ships)
+    }
+
+    // Function to read a fleet from a comma-separated list of ships in the same form as the lines of a fleet
+    // file, e.g. "5,4,3,3,2" or "Carrier 5, Patrol Boat 2".
+    pub fn parse_list(text: &str) -> Result<Self, FleetError> {
+        let ships = text
+            .split(',')
+            .map(str::trim)
+            .map(|entry| {
+                parse_ship(entry).ok_or_else(|| FleetError::InvalidEntry(entry.to_string()))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        Self::from_ships(ships)
+    }
+
+    // Helper function to build a fleet from parsed ships, naming any ship given only as a size after the
+    // first standard ship of that size not yet in the fleet, or as "Ship N" by its position.
+    fn from_ships(ships: Vec<(Option<String>, usize)>) -> Result<Self, FleetError> {
+        if ships.is_empty() {
+            return Err(FleetError::Empty);
+        }
+        let mut named: Vec<(String, usize)> = Vec::new();
+        for (index, (name, size)) in ships.into_iter().enumerate() {
+            let name = name.unwrap_or_else(|| {
+                FLEET
+                    .iter()
+                    .find(|&&(standard, standard_size)| {
+                        standard_size == size && !named.iter().any(|(taken, _)| taken == standard)
+                    })
+                    .map(|&(standard, _)| standard.to_string())
+                    .unwrap_or_else(|| format!("Ship {}", index + 1))
+            });
+            named.push((name, size));
+        }
+        Ok(FleetConfig { ships: named })
+    }
+
+    // Function to load a fleet from a file in the format `parse` reads.
+    pub fn load(path: &str) -> Result<Self, FleetError> {
+        Self::parse(&fs::read_to_string(path)?)
+    }
+
+    // Method to check whether the fleet fits comfortably on a board of the given size: every ship must fit in
+    // a single row, and the fleet may cover at most half of the board so random placement never runs out of
+    // room.
+    pub fn fits(&self, size: usize) -> bool {
+        let total: usize = self.ships.iter().map(|&(_, ship_size)| ship_size).sum();
+        self.ships.iter().all(|&(_, ship_size)| ship_size <= size) && total * 2 <= size * size
+    }
+}
+
+// Function to read one ship written as its name and then its size, or as just its size, in which case there is
+// no name. Sizes must be whole numbers above zero.
+fn parse_ship(text: &str) -> Option<(Option<String>, usize)> {
+    let (name, size) = match text.rsplit_once(char::is_whitespace) {
+        Some((name, size)) => (Some(name.trim().to_string()), size),
+        None => (None, text),
+    };
+    let size = size.parse().ok().filter(|&size| size > 0)?;
+    Some((name, size))
+}
+
+impl Default for FleetConfig {
+    fn default() -> Self {
+        Self::standard()
+    }
+}
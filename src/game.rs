@@ -0,0 +1,1516 @@
+use crate::ai::{suggest_target, Difficulty, OpponentAi};
+use crate::board::{
+    is_valid_coord, Board, BoardView, Lane, PlacementError, ShotOutcome, Stats, TorpedoOutcome,
+};
+use crate::fleet::FleetConfig;
+use crate::lifetime::{load_stats, save_stats};
+use crate::player::{HumanPlayer, Player};
+use crate::replay::{save_replay, Replay, ReplayError, REPLAY_FILE};
+use crate::style::{clear_screen, reset_colors, Style, Styled};
+use crate::ui::{
+    already_fired_warning, ask_yes_no, check_salvo, coordinate_label, display,
+    display_side_by_side, pass_keyboard, print_hud, print_last_turn, print_legend,
+    print_remaining_ships, print_scoreboard, report_opponent_shot, report_player_shot,
+    reveal_boards, wait_to_continue, Arsenal, PlayerAction,
+};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+
+// Weights for the end-of-game score: a bonus for winning and for every ship sunk, less a penalty for every
+// miss and every turn taken.
+pub const WIN_BONUS: i32 = 1000;
+pub const SINK_BONUS: i32 = 100;
+pub const MISS_PENALTY: i32 = 10;
+pub const TURN_PENALTY: i32 = 5;
+
+// Settings that shape a game: the board size, how strong the opponent is, which ships each side gets, whether
+// salvo rules apply, whether a hit earns another shot, whether a miss next to a ship is called close, whether
+// ships may touch each other, whether randomly placed ships are spread across the board, whether ships may lie
+// diagonally, how many cells of each board are land, whether two people share the keyboard instead of playing the
+// computer, an optional seed to replay the exact same game, an optional number of turns after which the game is a
+// draw, how many torpedoes, radar scans and radar sweeps each side gets, and how long to pause between turns
+// (None waits for Enter).
+#[derive(Serialize, Deserialize)]
+pub struct GameSettings {
+    pub size: usize,
+    pub difficulty: Difficulty,
+    #[serde(default)]
+    pub fleet: FleetConfig,
+    pub salvo: bool,
+    #[serde(default)]
+    pub hit_again: bool,
+    #[serde(default)]
+    pub heat: bool,
+    #[serde(default)]
+    pub no_touching: bool,
+    #[serde(default)]
+    pub spread: bool,
+    #[serde(default)]
+    pub diagonal: bool,
+    #[serde(default)]
+    pub land: usize,
+    #[serde(default)]
+    pub hotseat: bool,
+    pub seed: Option<u64>,
+    #[serde(default)]
+    pub max_turns: Option<usize>,
+    #[serde(default)]
+    pub torpedoes: usize,
+    #[serde(default)]
+    pub scans: usize,
+    #[serde(default)]
+    pub sweeps: usize,
+    #[serde(skip)]
+    pub auto_advance: Option<Duration>,
+}
+
+// Which side fired a shot. In hotseat mode the player is Player 1 and the opponent Player 2.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub enum Side {
+    Player,
+    Opponent,
+}
+
+// One shot from the game's history: the turn it was fired on, who fired, where, and what it did. A torpedo
+// also records the lane it ran along; one that missed is kept as a miss at the far end of its lane, though
+// it left no mark there.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Move {
+    #[serde(default)]
+    pub turn: usize,
+    pub side: Side,
+    pub row: usize,
+    pub col: usize,
+    pub outcome: ShotOutcome,
+    #[serde(default)]
+    pub torpedo: Option<Lane>,
+}
+
+impl Move {
+    // Method to check whether the shot changed the board it was fired at, which neither a repeated shot, a shot
+    // off the board nor a torpedo that missed does.
+    pub fn marked_board(&self) -> bool {
+        match self.outcome {
+            ShotOutcome::AlreadyFired | ShotOutcome::OffBoard | ShotOutcome::Land => false,
+            ShotOutcome::Miss => self.torpedo.is_none(),
+            _ => true,
+        }
+    }
+}
+
+// End-of-game figures for one side: its shot counts, how many turns it took, its longest run of hits in a
+// row, the ships it sank, in order, and whether it won.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Summary {
+    pub stats: Stats,
+    pub turns: usize,
+    pub longest_streak: usize,
+    pub sunk: Vec<String>,
+    pub won: bool,
+}
+
+impl Summary {
+    // Method to work out the side's score: `WIN_BONUS` for a win and `SINK_BONUS` per ship sunk, less
+    // `MISS_PENALTY` per miss and `TURN_PENALTY` per turn. It can go below zero.
+    pub fn score(&self) -> i32 {
+        let win = if self.won { WIN_BONUS } else { 0 };
+        win + SINK_BONUS * self.sunk.len() as i32
+            - MISS_PENALTY * self.stats.misses as i32
+            - TURN_PENALTY * self.turns as i32
+    }
+}
+
+// Function to sum up one side's shots from a game's history. Repeated shots at the same cell and shots off the
+// board are skipped, shots at land use up a turn without counting as shots, and whether the side won is left
+// for the caller to fill in.
+pub fn summarize(history: &[Move], side: Side) -> Summary {
+    let mut summary = Summary {
+        stats: Stats::default(),
+        turns: 0,
+        longest_streak: 0,
+        sunk: Vec::new(),
+        won: false,
+    };
+    let mut streak = 0;
+    let mut last_turn = None;
+    for shot in history.iter().filter(|shot| shot.side == side) {
+        if matches!(
+            shot.outcome,
+            ShotOutcome::AlreadyFired | ShotOutcome::OffBoard
+        ) {
+            continue;
+        }
+        if last_turn != Some(shot.turn) {
+            summary.turns += 1;
+            last_turn = Some(shot.turn);
+        }
+        if shot.outcome == ShotOutcome::Land {
+            continue;
+        }
+        summary.stats.shots += 1;
+        match &shot.outcome {
+            ShotOutcome::Miss => {
+                summary.stats.misses += 1;
+                streak = 0;
+            }
+            outcome => {
+                summary.stats.hits += 1;
+                streak += 1;
+                summary.longest_streak = summary.longest_streak.max(streak);
+                if let ShotOutcome::Sunk(name) = outcome {
+                    summary.sunk.push(name.clone());
+                }
+            }
+        }
+    }
+    summary
+}
+
+// What one shot fired through `GameState::player_fire` or `GameState::opponent_take_turn` did: its outcome,
+// whether it used up the side's turn, and whether it ended the game, with the winner (None for a draw).
+#[derive(Clone, Debug, PartialEq)]
+pub struct TurnReport {
+    pub outcome: ShotOutcome,
+    pub turn_over: bool,
+    pub game_over: bool,
+    pub winner: Option<Side>,
+}
+
+// Something that happened in a game, handed to the callback set with `GameState::set_event_callback` so a GUI
+// or sound effects can follow the game without reading what's printed. At the keyboard the game's own messages
+// about shots, scans, sweeps and the end of the game are worded from these events too. The side is the one that
+// owns the ship for ShipPlaced, the one giving up or running out of time for Surrendered and TurnTimedOut, and
+// the one that fired, scanned or swept for the others. ShotFired comes first for every shot, with the lane for
+// a torpedo (whose cell is the last one in the lane if it missed), followed by ShipHit for a hit and by both
+// ShipHit and ShipSunk for the shot that sinks a ship. Under the heat rule a miss right next to a ship is
+// followed by NearMiss.
+#[derive(Clone, Debug, PartialEq)]
+pub enum GameEvent {
+    ShipPlaced {
+        side: Side,
+        name: String,
+        cells: Vec<(usize, usize)>,
+    },
+    ShotFired {
+        side: Side,
+        row: usize,
+        col: usize,
+        outcome: ShotOutcome,
+        torpedo: Option<Lane>,
+    },
+    ShipHit {
+        side: Side,
+        row: usize,
+        col: usize,
+    },
+    ShipSunk {
+        side: Side,
+        name: String,
+    },
+    NearMiss {
+        side: Side,
+        row: usize,
+        col: usize,
+    },
+    Scanned {
+        side: Side,
+        row: usize,
+        col: usize,
+        found: usize,
+    },
+    Swept {
+        side: Side,
+        lane: Lane,
+        detected: bool,
+    },
+    TurnTimedOut {
+        side: Side,
+    },
+    Surrendered {
+        side: Side,
+    },
+    GameOver {
+        winner: Option<Side>,
+    },
+}
+
+// Time spent on a game so far: whatever was played before it was last loaded, plus the time since. Saved as the
+// total, so a loaded game's clock carries on from where it was saved.
+#[derive(Clone, Copy, Serialize, Deserialize)]
+#[serde(from = "Duration", into = "Duration")]
+struct Stopwatch {
+    earlier: Duration,
+    since: Instant,
+}
+
+impl Stopwatch {
+    // Method to read the total time on the clock.
+    fn elapsed(&self) -> Duration {
+        self.earlier + self.since.elapsed()
+    }
+}
+
+impl Default for Stopwatch {
+    fn default() -> Self {
+        Duration::ZERO.into()
+    }
+}
+
+impl From<Duration> for Stopwatch {
+    fn from(earlier: Duration) -> Self {
+        Stopwatch {
+            earlier,
+            since: Instant::now(),
+        }
+    }
+}
+
+impl From<Stopwatch> for Duration {
+    fn from(stopwatch: Stopwatch) -> Self {
+        stopwatch.elapsed()
+    }
+}
+
+// How a turn ended: play carries on, the game is over with a winner (or a draw), or the player asked to leave.
+enum TurnEnd {
+    Continue,
+    GameOver(Option<Side>),
+    Quit,
+}
+
+// A game in progress between the player and the computer opponent, or between two players in hotseat mode, where
+// the opponent's board belongs to Player 2, along with how many games each side has won in the session so far.
+// The whole state can be saved to and loaded from JSON, except for the random number generator, which is reseeded
+// when a game is loaded.
+#[derive(Serialize, Deserialize)]
+pub struct GameState {
+    settings: GameSettings,
+    player_board: Board,
+    opponent_board: Board,
+    opponent_ai: OpponentAi,
+    seed: u64,
+    #[serde(skip, default = "StdRng::from_entropy")]
+    rng: StdRng,
+    turns: usize,
+    player_turn: bool,
+    #[serde(default)]
+    history: Vec<Move>,
+    #[serde(default)]
+    scans_used: [usize; 2],
+    #[serde(default)]
+    sweeps_used: [usize; 2],
+    #[serde(default)]
+    hints_used: [usize; 2],
+    #[serde(default)]
+    session: [usize; 2],
+    #[serde(default)]
+    clock: Stopwatch,
+    #[serde(skip)]
+    human: HumanPlayer,
+    #[serde(skip)]
+    stats_path: Option<PathBuf>,
+    #[serde(skip)]
+    on_event: Option<Box<dyn FnMut(GameEvent)>>,
+    #[serde(skip)]
+    narrate: bool,
+}
+
+// Function to make the two empty boards a game starts with, following the settings' placement rules, and to
+// place the computer's fleet on its board (in hotseat mode Player 2 places their own).
+fn new_boards(settings: &GameSettings, rng: &mut StdRng) -> Result<(Board, Board), PlacementError> {
+    let mut player_board = Board::new(settings.size);
+    let mut opponent_board = Board::new(settings.size);
+    player_board.set_no_touching(settings.no_touching);
+    opponent_board.set_no_touching(settings.no_touching);
+    player_board.set_spread(settings.spread);
+    opponent_board.set_spread(settings.spread);
+    player_board.set_diagonal(settings.diagonal);
+    opponent_board.set_diagonal(settings.diagonal);
+    // Land goes down before any ships, in time for the player to place around it
+    player_board.raise_islands(settings.land, &settings.fleet, rng);
+    opponent_board.raise_islands(settings.land, &settings.fleet, rng);
+    if !settings.hotseat {
+        opponent_board.place_fleet(&settings.fleet, rng)?;
+    }
+    Ok((player_board, opponent_board))
+}
+
+impl GameState {
+    // Constructor for a new game, with empty boards and the computer's fleet placed at random (in hotseat mode
+    // Player 2 places their own). Ship placement and the AI are both driven by one seed, picked at random
+    // unless the settings supply one. Fails if the computer's fleet can't be fitted onto the board.
+    pub fn new(settings: GameSettings) -> Result<Self, PlacementError> {
+        let seed = settings.seed.unwrap_or_else(|| rand::thread_rng().gen());
+        let mut rng = StdRng::seed_from_u64(seed);
+        let (player_board, opponent_board) = new_boards(&settings, &mut rng)?;
+        Ok(GameState {
+            player_board,
+            opponent_board,
+            opponent_ai: OpponentAi::new(settings.difficulty, rng.gen()),
+            settings,
+            seed,
+            rng,
+            turns: 0,
+            player_turn: true,
+            history: Vec::new(),
+            scans_used: [0, 0],
+            sweeps_used: [0, 0],
+            hints_used: [0, 0],
+            session: [0, 0],
+            clock: Stopwatch::default(),
+            human: HumanPlayer,
+            stats_path: None,
+            on_event: None,
+            narrate: false,
+        })
+    }
+
+    // Method to set up a rematch once a game is over: new boards with the computer's fleet placed afresh, a
+    // computer that has forgotten the last game, and no shots, turns or special weapons used. The new game's
+    // seed is drawn from the last one's, so a seeded session plays out the same every time. Only the settings
+    // and the session score carry over.
+    pub fn new_round(&mut self) -> Result<(), PlacementError> {
+        let seed = self.rng.gen();
+        let mut rng = StdRng::seed_from_u64(seed);
+        (self.player_board, self.opponent_board) = new_boards(&self.settings, &mut rng)?;
+        self.opponent_ai = OpponentAi::new(self.settings.difficulty, rng.gen());
+        self.seed = seed;
+        self.rng = rng;
+        self.turns = 0;
+        self.player_turn = true;
+        self.history.clear();
+        self.scans_used = [0, 0];
+        self.sweeps_used = [0, 0];
+        self.hints_used = [0, 0];
+        self.clock = Stopwatch::default();
+        Ok(())
+    }
+
+    // Accessor for the player's own board.
+    pub fn player_board(&self) -> &Board {
+        &self.player_board
+    }
+
+    // Mutable accessor for the player's board, for placing their fleet outside the interactive prompt.
+    pub fn player_board_mut(&mut self) -> &mut Board {
+        &mut self.player_board
+    }
+
+    // Accessor for the computer opponent's board.
+    pub fn opponent_board(&self) -> &Board {
+        &self.opponent_board
+    }
+
+    // Accessor for the number of turns the player has taken.
+    pub fn turns(&self) -> usize {
+        self.turns
+    }
+
+    // Accessor for how long the game has been played, counting time played before it was saved.
+    pub fn elapsed(&self) -> Duration {
+        self.clock.elapsed()
+    }
+
+    // Accessor for every shot fired so far, in order.
+    pub fn history(&self) -> &[Move] {
+        &self.history
+    }
+
+    // Accessor for whether two players share the keyboard instead of playing the computer.
+    pub fn is_hotseat(&self) -> bool {
+        self.settings.hotseat
+    }
+
+    // Accessor for whether it's the player's turn to fire next.
+    pub fn is_player_turn(&self) -> bool {
+        self.player_turn
+    }
+
+    // Method to change how long to pause between turns, since that isn't kept in save files.
+    pub fn set_auto_advance(&mut self, auto_advance: Option<Duration>) {
+        self.settings.auto_advance = auto_advance;
+    }
+
+    // Method to choose the file the game's result is added to once it's over, or None to keep no lifetime
+    // statistics. Hotseat games are never added.
+    pub fn set_stats_path(&mut self, path: Option<PathBuf>) {
+        self.stats_path = path;
+    }
+
+    // Method to have every GameEvent handed to a callback as it happens, replacing any callback set before.
+    // Without one, events go nowhere.
+    pub fn set_event_callback(&mut self, callback: impl FnMut(GameEvent) + 'static) {
+        self.on_event = Some(Box::new(callback));
+    }
+
+    // Helper method to hand an event to the callback, if there is one, after printing what it means while the
+    // game is being played at the keyboard.
+    fn emit(&mut self, event: GameEvent) {
+        if self.narrate {
+            self.narrate_event(&event);
+        }
+        if let Some(callback) = &mut self.on_event {
+            callback(event);
+        }
+    }
+
+    // Helper method to emit the events for one shot a side fired and what it did.
+    fn emit_shot(
+        &mut self,
+        side: Side,
+        (row, col): (usize, usize),
+        outcome: &ShotOutcome,
+        torpedo: Option<Lane>,
+    ) {
+        self.emit(GameEvent::ShotFired {
+            side,
+            row,
+            col,
+            outcome: outcome.clone(),
+            torpedo,
+        });
+        if matches!(outcome, ShotOutcome::Hit | ShotOutcome::Sunk(_)) {
+            self.emit(GameEvent::ShipHit { side, row, col });
+        }
+        if let ShotOutcome::Sunk(name) = outcome {
+            self.emit(GameEvent::ShipSunk {
+                side,
+                name: name.clone(),
+            });
+        }
+    }
+
+    // Helper method to emit a ShipPlaced event for every ship on both boards, once both fleets are placed and
+    // before the first shot.
+    fn emit_placements(&mut self) {
+        for (side, board) in [
+            (Side::Player, self.player_board.clone()),
+            (Side::Opponent, self.opponent_board.clone()),
+        ] {
+            for ship in board.ships() {
+                self.emit(GameEvent::ShipPlaced {
+                    side,
+                    name: ship.name.clone(),
+                    cells: ship.cells.clone(),
+                });
+            }
+        }
+    }
+
+    // Method to print the message for an event at the keyboard: how a shot, scan or sweep went, who ran out of
+    // time or gave up, and who won. Placements, and hits and sinkings (which the shot's own message covers),
+    // print nothing.
+    fn narrate_event(&self, event: &GameEvent) {
+        match event {
+            GameEvent::ShotFired {
+                side,
+                outcome,
+                torpedo,
+                ..
+            } => {
+                if let Some(lane) = torpedo {
+                    println!(
+                        "{} fired a torpedo along {}.",
+                        self.names()[*side as usize],
+                        lane
+                    );
+                    if *outcome == ShotOutcome::Miss {
+                        println!(
+                            "{}",
+                            "It ran the length of the lane without striking a ship."
+                                .styled(Style::Miss)
+                        );
+                        return;
+                    }
+                }
+                // In hotseat mode both people see their shots described as their own
+                if *side == Side::Player || self.settings.hotseat {
+                    report_player_shot(outcome);
+                } else {
+                    report_opponent_shot(outcome);
+                }
+            }
+            GameEvent::NearMiss { .. } => println!(
+                "{}",
+                "Close! That shot landed right next to a ship.".styled(Style::Info)
+            ),
+            GameEvent::Scanned {
+                row, col, found, ..
+            } => {
+                let parts = if *found == 1 { "part" } else { "parts" };
+                println!(
+                    "{}",
+                    format!(
+                        "The scan around {} found {} ship {}.",
+                        coordinate_label(*row, *col),
+                        found,
+                        parts
+                    )
+                    .styled(Style::Info)
+                );
+            }
+            GameEvent::Swept {
+                side,
+                lane,
+                detected,
+            } => {
+                println!(
+                    "{} swept {} with radar.",
+                    self.names()[*side as usize],
+                    lane
+                );
+                let report = if *detected {
+                    format!("Ships detected in {}.", lane)
+                } else {
+                    format!("No ships detected in {}.", lane)
+                };
+                println!("{}", report.styled(Style::Info));
+            }
+            GameEvent::TurnTimedOut { .. } => println!(
+                "{}",
+                "Time's up! The rest of this turn is forfeited.".styled(Style::Warning)
+            ),
+            GameEvent::Surrendered { side } => self.surrender(*side as usize + 1),
+            GameEvent::GameOver { winner: Some(side) } => {
+                // A surrender has already been described, so only a sunk fleet is announced
+                let loser = match side {
+                    Side::Player => &self.opponent_board,
+                    Side::Opponent => &self.player_board,
+                };
+                if loser.is_game_over() {
+                    self.announce_winner(*side);
+                }
+            }
+            GameEvent::GameOver { winner: None } => {
+                if let Some(max_turns) = self.settings.max_turns {
+                    println!(
+                        "{}",
+                        format!(
+                            "The turn limit of {} has been reached. The game is a draw!",
+                            max_turns
+                        )
+                        .styled(Style::Highlight)
+                    );
+                }
+            }
+            GameEvent::ShipPlaced { .. }
+            | GameEvent::ShipHit { .. }
+            | GameEvent::ShipSunk { .. } => (),
+        }
+    }
+
+    // Accessor for how many games each side has won this session, the player's first.
+    pub fn session(&self) -> [usize; 2] {
+        self.session
+    }
+
+    // Method to play games until the player has had enough, starting with the lifetime record against the
+    // computer if there is one. Every game that's played to the end is followed by
+    // the offer of a rematch, which carries the session score over, and turning it down shows the final score.
+    // Fails if the input runs out or can't be read mid-game, leaving the caller to abort it.
+    pub fn play(&mut self) -> io::Result<()> {
+        self.narrate = true;
+        self.show_lifetime();
+        while self.play_round()? {
+            // The game just played is over, so running out of input here counts as a no
+            if !ask_yes_no("Play again? [y/N] ").unwrap_or(false) {
+                println!(
+                    "{}",
+                    format!("Final score: {}", self.session_score()).styled(Style::Highlight)
+                );
+                break;
+            }
+            self.new_round()
+                .map_err(|error| io::Error::other(error.to_string()))?;
+        }
+        Ok(())
+    }
+
+    // Method to play one game: the player places their fleet (unless it was loaded from a save), as does
+    // Player 2 in hotseat mode, then both sides take turns until one fleet is sunk. Returns false if the
+    // player quit before the end.
+    fn play_round(&mut self) -> io::Result<bool> {
+        for (number, side) in [(1, Side::Player), (2, Side::Opponent)] {
+            // Borrowed field by field so the placement can use the game's settings and random number generator
+            let player: &mut dyn Player = if side == Side::Player || self.settings.hotseat {
+                &mut self.human
+            } else {
+                &mut self.opponent_ai
+            };
+            let board = match side {
+                Side::Player => &mut self.player_board,
+                Side::Opponent => &mut self.opponent_board,
+            };
+            if board.ships().is_empty() {
+                if self.settings.hotseat {
+                    pass_keyboard(number)?;
+                }
+                player.place_ships(board, &self.settings.fleet, &mut self.rng)?;
+            }
+        }
+        // The clock starts with the first shot of a new game, not while the ships are being placed
+        if self.history.is_empty() {
+            self.clock = Stopwatch::default();
+            self.emit_placements();
+        }
+
+        // Explain the board symbols once before the first shot
+        clear_screen();
+        print_legend();
+        println!("Type 'help' at the firing prompt to see this again.");
+        wait_to_continue(self.settings.auto_advance)?;
+
+        // Main game loop
+        let winner = loop {
+            match self.take_turn()? {
+                TurnEnd::Continue => (),
+                TurnEnd::GameOver(winner) => break winner,
+                TurnEnd::Quit => {
+                    // Reset the terminal colors in case the game is left mid-way through styled output
+                    reset_colors();
+                    println!("Thanks for playing. Goodbye!");
+                    return Ok(false);
+                }
+            }
+        };
+
+        self.emit(GameEvent::GameOver { winner });
+        if let Some(side) = winner {
+            self.session[side as usize] += 1;
+        }
+        let names = self.names();
+        // Show where every ship was, then the summary for each side. The shot counts come from the boards,
+        // which also cover saves made before moves were recorded.
+        println!();
+        reveal_boards(&self.player_board, &self.opponent_board);
+        let summaries = [
+            Summary {
+                stats: self.opponent_board.stats(),
+                won: winner == Some(Side::Player),
+                ..summarize(&self.history, Side::Player)
+            },
+            Summary {
+                stats: self.player_board.stats(),
+                won: winner == Some(Side::Opponent),
+                ..summarize(&self.history, Side::Opponent)
+            },
+        ];
+        print_scoreboard(names, &summaries, self.turns, self.clock.elapsed());
+        for (name, hints) in names.into_iter().zip(self.hints_used) {
+            if hints > 0 {
+                println!("Hints used by {}: {}", name, hints);
+            }
+        }
+        if !self.settings.hotseat {
+            println!(
+                "Opponent difficulty: {}",
+                self.opponent_ai
+                    .difficulty()
+                    .to_string()
+                    .styled(Style::Emphasis)
+            );
+            self.record_lifetime(winner, &summaries[0]);
+        }
+
+        self.record_replay(REPLAY_FILE);
+
+        // The game is already over, so running out of input here just skips the replay
+        if ask_yes_no("Watch a replay of the game? [y/N] ").unwrap_or(false) {
+            self.replay()?;
+        }
+        Ok(true)
+    }
+
+    // Method to step through the recorded shots from the start of the game, redrawing both boards with every
+    // ship showing after each one. The boards are rebuilt from the placements and shots alone, so a replay
+    // that doesn't match what happened is reported instead of shown.
+    pub fn replay(&self) -> io::Result<()> {
+        match Replay::from_game(self).play_back(self.settings.auto_advance) {
+            Ok(()) => Ok(()),
+            Err(ReplayError::Io(error)) => Err(error),
+            Err(error) => {
+                println!(
+                    "{}",
+                    format!("Could not replay the game: {}.", error).styled(Style::Warning)
+                );
+                Ok(())
+            }
+        }
+    }
+
+    // Helper method to show the lifetime record as a session against the computer starts, once there's a game in
+    // it. A stats file that can't be read is left for `record_lifetime` to start afresh.
+    fn show_lifetime(&self) {
+        if self.settings.hotseat {
+            return;
+        }
+        let stats = self
+            .stats_path
+            .as_deref()
+            .and_then(|path| load_stats(path).ok());
+        if let Some(stats) = stats.filter(|stats| stats.games > 0) {
+            println!("{}", stats.to_string().styled(Style::Info));
+        }
+    }
+
+    // Helper method to add the finished game to the lifetime statistics, if there's a file for them, and show the
+    // new totals. A stats file that can't be read is replaced with a warning instead of stopping the game.
+    fn record_lifetime(&self, winner: Option<Side>, summary: &Summary) {
+        let Some(path) = &self.stats_path else {
+            return;
+        };
+        let mut stats = load_stats(path).unwrap_or_else(|error| {
+            println!(
+                "{}",
+                format!("Starting your lifetime stats afresh: {}.", error).styled(Style::Warning)
+            );
+            Default::default()
+        });
+        stats.record(winner, summary.stats.shots, summary.score());
+        if let Err(error) = save_stats(path, &stats) {
+            println!(
+                "{}",
+                format!("Could not save your lifetime stats: {}.", error).styled(Style::Warning)
+            );
+        }
+        println!("{}", stats.to_string().styled(Style::Info));
+    }
+
+    // Method to write a replay of the game so far to a file and say how that went.
+    fn record_replay(&self, path: &str) {
+        match save_replay(path, &Replay::from_game(self)) {
+            Ok(()) => println!(
+                "{}",
+                format!("Replay saved to {}.", path).styled(Style::Info)
+            ),
+            Err(error) => println!(
+                "{}",
+                format!("Could not save the replay: {}.", error).styled(Style::Warning)
+            ),
+        }
+    }
+
+    // Method to run the turn of whichever side is up, returning how it ended. People get the boards shown
+    // first (after the keyboard has been handed over in hotseat mode), while the computer just fires. Saving
+    // keeps the turn going, and loading replaces the whole game and hands control back to the main loop.
+    fn take_turn(&mut self) -> io::Result<TurnEnd> {
+        let side = if self.player_turn {
+            Side::Player
+        } else {
+            Side::Opponent
+        };
+        let current = if self.player_turn { 1 } else { 2 };
+        let human = self.parts(side).0.is_human();
+        if human {
+            if self.settings.hotseat {
+                pass_keyboard(current)?;
+            }
+            self.show_turn(current)?;
+        }
+
+        // One shot normally, or one per surviving ship in salvo mode, plus one more for every hit under the
+        // hit-again rule
+        let salvo = self.settings.salvo;
+        let hit_again = self.settings.hit_again;
+        let heat = self.settings.heat;
+        let mut shots = if salvo {
+            self.boards().0.ships_afloat()
+        } else {
+            1
+        };
+        let mut fired = 0;
+        let mut sank_ship = false;
+        while fired < shots && !self.boards().1.is_game_over() {
+            let arsenal = Arsenal {
+                // A torpedo can only open a turn
+                torpedoes: if fired == 0 {
+                    self.torpedoes_left(side)
+                } else {
+                    0
+                },
+                scans: self.scans_left(side),
+                sweeps: self.sweeps_left(side),
+            };
+            let (player, _, target) = self.parts(side);
+            match player.choose_shots(&BoardView::new(target), shots - fired, arsenal)? {
+                PlayerAction::Fire(targets) => {
+                    // Re-prompt on a cell that was already fired at instead of wasting the shot, and for a salvo
+                    // with any bad cell in it, ask for the whole salvo again
+                    if let [(row, col)] = targets[..] {
+                        if human && target.has_been_fired_at(row, col) {
+                            println!(
+                                "{}",
+                                already_fired_warning(row, col, target.cell(row, col))
+                                    .styled(Style::Warning)
+                            );
+                            continue;
+                        }
+                    } else if let Err(error) = check_salvo(&targets, &BoardView::new(target)) {
+                        if human {
+                            let warning =
+                                format!("That salvo can't be fired, as some cells are {}.", error);
+                            println!("{}", warning.styled(Style::Warning));
+                            println!("Enter the whole salvo again.");
+                            continue;
+                        }
+                    }
+                    if fired == 0 && self.player_turn {
+                        self.turns += 1;
+                    }
+                    for (row, col) in targets {
+                        if salvo {
+                            print!("{}: ", coordinate_label(row, col));
+                        }
+                        let result = self.fire_shot(side, row, col);
+                        let (player, _, target) = self.parts(side);
+                        player.report_shot((row, col), &result);
+                        // Under the heat rule a miss says whether it was close, though not to which ship
+                        if heat && result == ShotOutcome::Miss && target.is_near_ship(row, col) {
+                            self.emit(GameEvent::NearMiss { side, row, col });
+                        }
+                        sank_ship |= matches!(result, ShotOutcome::Sunk(_));
+                        if hit_again && matches!(result, ShotOutcome::Hit | ShotOutcome::Sunk(_)) {
+                            shots += 1;
+                        }
+                        fired += 1;
+                        if self.boards().1.is_game_over() {
+                            break;
+                        }
+                    }
+                    if hit_again && human && fired < shots && !self.boards().1.is_game_over() {
+                        self.offer_extra_shot(current)?;
+                    }
+                }
+                PlayerAction::Torpedo(_) if fired > 0 => println!(
+                    "{}",
+                    "A torpedo can only be fired at the start of a turn.".styled(Style::Warning)
+                ),
+                PlayerAction::Torpedo(_) if arsenal.torpedoes == 0 => {
+                    println!("{}", "You have no torpedoes left.".styled(Style::Warning))
+                }
+                PlayerAction::Torpedo(lane) => {
+                    if self.player_turn {
+                        self.turns += 1;
+                    }
+                    let turn = self.turns;
+                    let (player, _, target) = self.parts(side);
+                    let (row, col, result) = match target.fire_torpedo(lane) {
+                        TorpedoOutcome::Struck { row, col, outcome } => {
+                            player.report_shot((row, col), &outcome);
+                            (row, col, outcome)
+                        }
+                        TorpedoOutcome::Missed => {
+                            let (row, col) = lane.cells(target.size()).last().unwrap_or((0, 0));
+                            (row, col, ShotOutcome::Miss)
+                        }
+                    };
+                    self.emit_shot(side, (row, col), &result, Some(lane));
+                    sank_ship |= matches!(result, ShotOutcome::Sunk(_));
+                    // The torpedo takes the place of the turn's shots, though under the hit-again rule a hit
+                    // still earns another
+                    fired = shots;
+                    if hit_again && matches!(result, ShotOutcome::Hit | ShotOutcome::Sunk(_)) {
+                        shots += 1;
+                    }
+                    self.history.push(Move {
+                        turn,
+                        side,
+                        row,
+                        col,
+                        outcome: result,
+                        torpedo: Some(lane),
+                    });
+                    if hit_again && human && fired < shots && !self.boards().1.is_game_over() {
+                        self.offer_extra_shot(current)?;
+                    }
+                }
+                PlayerAction::Scan(..) if arsenal.scans == 0 => {
+                    println!("{}", "You have no scans left.".styled(Style::Warning))
+                }
+                // A scan doesn't use up a shot, and what it shows stays on the board from then on
+                PlayerAction::Scan(row, col) => {
+                    self.scans_used[side as usize] += 1;
+                    let found = self.parts(side).2.scan(row, col);
+                    if human {
+                        self.show_turn(current)?;
+                    }
+                    self.emit(GameEvent::Scanned {
+                        side,
+                        row,
+                        col,
+                        found,
+                    });
+                }
+                PlayerAction::Sweep(_) if arsenal.sweeps == 0 => println!(
+                    "{}",
+                    "You have no radar sweeps left.".styled(Style::Warning)
+                ),
+                // A sweep takes the place of one shot, and only says whether there's a ship along the lane
+                PlayerAction::Sweep(lane) => {
+                    if fired == 0 && self.player_turn {
+                        self.turns += 1;
+                    }
+                    self.sweeps_used[side as usize] += 1;
+                    let detected = self.parts(side).2.sweep(lane);
+                    self.emit(GameEvent::Swept {
+                        side,
+                        lane,
+                        detected,
+                    });
+                    fired += 1;
+                }
+                PlayerAction::Save(path) => match save_game(&path, self) {
+                    Ok(()) => {
+                        println!("{}", format!("Game saved to {}.", path).styled(Style::Info))
+                    }
+                    Err(error) => println!(
+                        "{}",
+                        format!("Could not save the game: {}.", error).styled(Style::Warning)
+                    ),
+                },
+                PlayerAction::Record(path) => self.record_replay(&path),
+                // Worked out from what the player can see of the board, so it gives nothing away
+                PlayerAction::Hint => {
+                    let (row, col) = suggest_target(&BoardView::new(self.boards().1));
+                    self.hints_used[side as usize] += 1;
+                    println!(
+                        "{}",
+                        format!("Hint: try {}.", coordinate_label(row, col)).styled(Style::Info)
+                    );
+                }
+                PlayerAction::Reveal => {
+                    println!("{}", "The board you're firing at:".styled(Style::Emphasis));
+                    display(self.boards().1, false);
+                }
+                // Only between turns, so a salvo is never left half taken back
+                PlayerAction::Undo if fired > 0 => println!(
+                    "{}",
+                    "Finish this turn's shots before undoing.".styled(Style::Warning)
+                ),
+                PlayerAction::Undo if self.settings.hotseat => println!(
+                    "{}",
+                    "Undo isn't available in hotseat mode, as it would take back the other player's turn."
+                        .styled(Style::Warning)
+                ),
+                PlayerAction::Undo => {
+                    if self.undo_turn(side) {
+                        // A ship the undone reply sank is afloat again, and gets its salvo shot back
+                        if salvo {
+                            shots = self.boards().0.ships_afloat();
+                        }
+                        self.show_turn(current)?;
+                        println!(
+                            "{}",
+                            "Took back your last turn and the reply to it.".styled(Style::Info)
+                        );
+                    } else {
+                        println!("{}", "There's nothing to undo yet.".styled(Style::Warning));
+                    }
+                }
+                // Running out of time forfeits whatever shots are left in the turn
+                PlayerAction::TimedOut => {
+                    if fired == 0 && self.player_turn {
+                        self.turns += 1;
+                    }
+                    self.emit(GameEvent::TurnTimedOut { side });
+                    fired = shots;
+                }
+                PlayerAction::Quit => return Ok(TurnEnd::Quit),
+                PlayerAction::Surrender => {
+                    self.emit(GameEvent::Surrendered { side });
+                    let other = match side {
+                        Side::Player => Side::Opponent,
+                        Side::Opponent => Side::Player,
+                    };
+                    return Ok(TurnEnd::GameOver(Some(other)));
+                }
+                PlayerAction::Load(path) => match load_game(&path) {
+                    Ok(mut game) => {
+                        game.set_auto_advance(self.settings.auto_advance);
+                        game.on_event = self.on_event.take();
+                        game.narrate = self.narrate;
+                        game.stats_path = self.stats_path.take();
+                        *self = game;
+                        return Ok(TurnEnd::Continue);
+                    }
+                    Err(error) => println!(
+                        "{}",
+                        format!("Could not load the game: {}.", error).styled(Style::Warning)
+                    ),
+                },
+            }
+        }
+        let game_over = self.boards().1.is_game_over();
+        if sank_ship && !game_over && human {
+            print_remaining_ships(self.boards().1);
+        }
+        wait_to_continue(self.settings.auto_advance)?;
+        self.player_turn = !self.player_turn;
+
+        // Check if all of the other side's ships have been sunk
+        if game_over {
+            return Ok(TurnEnd::GameOver(Some(side)));
+        }
+        // Once both sides have had their last turn, a game that hit the turn limit is a draw
+        if self.turn_limit_reached() {
+            return Ok(TurnEnd::GameOver(None));
+        }
+        Ok(TurnEnd::Continue)
+    }
+
+    // Helper method to fire one shot for a side at the other side's board and record it in the history as
+    // part of the current turn.
+    fn fire_shot(&mut self, side: Side, row: usize, col: usize) -> ShotOutcome {
+        let turn = self.turns;
+        let outcome = self.parts(side).2.fire(row, col);
+        self.history.push(Move {
+            turn,
+            side,
+            row,
+            col,
+            outcome: outcome.clone(),
+            torpedo: None,
+        });
+        self.emit_shot(side, (row, col), &outcome, None);
+        outcome
+    }
+
+    // Helper method to check whether the game has run into its turn limit, which makes it a draw once both
+    // sides have had their last turn.
+    fn turn_limit_reached(&self) -> bool {
+        self.settings
+            .max_turns
+            .is_some_and(|max_turns| self.player_turn && self.turns >= max_turns)
+    }
+
+    // Method to check whether the game is over: either fleet has been sunk, or the turn limit has been reached.
+    pub fn is_over(&self) -> bool {
+        self.player_board.is_game_over()
+            || self.opponent_board.is_game_over()
+            || self.turn_limit_reached()
+    }
+
+    // Method to fire one of the player's shots without any prompts or output, for driving a game from code
+    // once both fleets are placed. The same rules as at the keyboard decide when the turn passes to the
+    // opponent: after one shot, one per surviving ship in salvo mode, or a miss under the hit-again rule. A
+    // cell that was already fired at, or one off the board, doesn't use up the shot. None if it isn't the
+    // player's turn or the game is over.
+    pub fn player_fire(&mut self, row: usize, col: usize) -> Option<TurnReport> {
+        self.step(Side::Player, row, col)
+    }
+
+    // Method to have the computer fire one shot of its turn without any output, the counterpart of
+    // `player_fire`. The computer only fires plain shots this way, never torpedoes. None if it isn't the
+    // computer's turn (including in hotseat mode, where a person has that side) or the game is over.
+    pub fn opponent_take_turn(&mut self) -> Option<TurnReport> {
+        if self.settings.hotseat {
+            return None;
+        }
+        let (row, col) = self
+            .opponent_ai
+            .choose_target(&BoardView::new(&self.player_board));
+        let report = self.step(Side::Opponent, row, col)?;
+        self.opponent_ai.notify((row, col), &report.outcome);
+        Some(report)
+    }
+
+    // Helper method to fire one shot for a side whose turn it is, counting the turn from its first shot and
+    // handing the turn over once the side has fired all the shots it gets.
+    fn step(&mut self, side: Side, row: usize, col: usize) -> Option<TurnReport> {
+        if self.player_turn != (side == Side::Player) || self.is_over() {
+            return None;
+        }
+        let target = self.boards().1;
+        if !is_valid_coord(row, col, target.size()) || target.has_been_fired_at(row, col) {
+            let outcome = if is_valid_coord(row, col, target.size()) {
+                ShotOutcome::AlreadyFired
+            } else {
+                ShotOutcome::OffBoard
+            };
+            return Some(TurnReport {
+                outcome,
+                turn_over: false,
+                game_over: false,
+                winner: None,
+            });
+        }
+        if self.history.is_empty() {
+            self.emit_placements();
+        }
+        let started = self.history.last().is_some_and(|shot| shot.side == side);
+        if side == Side::Player && !started {
+            self.turns += 1;
+        }
+        let outcome = self.fire_shot(side, row, col);
+        if self.settings.heat
+            && outcome == ShotOutcome::Miss
+            && self.boards().1.is_near_ship(row, col)
+        {
+            self.emit(GameEvent::NearMiss { side, row, col });
+        }
+
+        let fired = self.last_turn(side);
+        let hits = fired
+            .iter()
+            .filter(|shot| matches!(shot.outcome, ShotOutcome::Hit | ShotOutcome::Sunk(_)))
+            .count();
+        let mut shots = if self.settings.salvo {
+            self.boards().0.ships_afloat()
+        } else {
+            1
+        };
+        if self.settings.hit_again {
+            shots += hits;
+        }
+        let won = self.boards().1.is_game_over();
+        let turn_over = won || fired.len() >= shots;
+        if turn_over {
+            self.player_turn = !self.player_turn;
+        }
+        let game_over = won || self.turn_limit_reached();
+        let winner = won.then_some(side);
+        if game_over {
+            self.emit(GameEvent::GameOver { winner });
+        }
+        Some(TurnReport {
+            outcome,
+            turn_over,
+            game_over,
+            winner,
+        })
+    }
+
+    // Method to clear the screen and show a person whose turn it is (or the opponent's difficulty), the game's
+    // seed, their own board and the board they're firing at.
+    fn show_turn(&self, current: usize) -> io::Result<()> {
+        clear_screen();
+        // The count goes up with the player's first shot, so their turn isn't counted until they have fired
+        let started = self
+            .history
+            .last()
+            .is_some_and(|shot| shot.side == Side::Player);
+        let turn = if self.player_turn && !started {
+            self.turns + 1
+        } else {
+            self.turns
+        };
+        let turn = match self.settings.max_turns {
+            Some(max_turns) => format!("Turn {} of {}", turn, max_turns),
+            None => format!("Turn {}", turn),
+        };
+        if self.settings.hotseat {
+            println!(
+                "{}   Seed: {}",
+                format!("Player {}'s turn", current).styled(Style::Emphasis),
+                self.seed
+            );
+        } else {
+            println!(
+                "Opponent difficulty: {}   Seed: {}",
+                self.opponent_ai
+                    .difficulty()
+                    .to_string()
+                    .styled(Style::Emphasis),
+                self.seed
+            );
+        }
+        // Each side's shots are tallied on the board they were fired at
+        let stats = [self.opponent_board.stats(), self.player_board.stats()];
+        print_hud(&turn, self.names(), stats, self.clock.elapsed());
+        // Once a game of the session has been won, the score so far is kept in sight
+        if self.session.iter().sum::<usize>() > 0 {
+            println!("Session score: {}", self.session_score());
+        }
+        let (own, target) = self.boards();
+        display_side_by_side(own, target);
+        print_remaining_ships(target);
+        let side = if self.player_turn {
+            Side::Player
+        } else {
+            Side::Opponent
+        };
+        if self.settings.torpedoes > 0 {
+            println!(
+                "{} {}",
+                "Torpedoes left:".styled(Style::Emphasis),
+                self.torpedoes_left(side)
+            );
+        }
+        if self.settings.scans > 0 {
+            println!(
+                "{} {}",
+                "Scans left:".styled(Style::Emphasis),
+                self.scans_left(side)
+            );
+        }
+        if self.settings.sweeps > 0 {
+            println!(
+                "{} {}",
+                "Radar sweeps left:".styled(Style::Emphasis),
+                self.sweeps_left(side)
+            );
+        }
+
+        // The previous results go below the boards, the current player's first, so they survive the clear
+        let (side, other) = if self.player_turn {
+            (Side::Player, Side::Opponent)
+        } else {
+            (Side::Opponent, Side::Player)
+        };
+        let names = self.names();
+        print_last_turn(names[side as usize], self.last_turn(side));
+        print_last_turn(names[other as usize], self.last_turn(other));
+        Ok(())
+    }
+
+    // Method to show a person the board again before the extra shot a hit earned them, with the hit listed
+    // under it.
+    fn offer_extra_shot(&self, current: usize) -> io::Result<()> {
+        wait_to_continue(self.settings.auto_advance)?;
+        self.show_turn(current)?;
+        println!("{}", "It's a hit, so fire again!".styled(Style::Success));
+        Ok(())
+    }
+
+    // Method to count the torpedoes a side has yet to fire. Only the history is consulted, so undoing a turn
+    // hands back a torpedo fired in it.
+    pub fn torpedoes_left(&self, side: Side) -> usize {
+        let fired = self
+            .history
+            .iter()
+            .filter(|shot| shot.side == side && shot.torpedo.is_some())
+            .count();
+        self.settings.torpedoes.saturating_sub(fired)
+    }
+
+    // Method to count the radar scans a side has yet to use. Undoing a turn doesn't hand back a scan, since what
+    // it showed can't be unseen.
+    pub fn scans_left(&self, side: Side) -> usize {
+        self.settings
+            .scans
+            .saturating_sub(self.scans_used[side as usize])
+    }
+
+    // Accessor for how many hints a side has asked for this game.
+    pub fn hints_used(&self, side: Side) -> usize {
+        self.hints_used[side as usize]
+    }
+
+    // Method to count the radar sweeps a side has yet to use. Like scans, undoing a turn doesn't hand one back.
+    pub fn sweeps_left(&self, side: Side) -> usize {
+        self.settings
+            .sweeps
+            .saturating_sub(self.sweeps_used[side as usize])
+    }
+
+    // Method to take back a side's last turn along with the other side's reply to it, so it is that side's
+    // turn again with the boards as they were. Returns false if the side hasn't fired yet, and always in hotseat
+    // mode, where the reply is the other person's turn.
+    pub fn undo_turn(&mut self, side: Side) -> bool {
+        if self.settings.hotseat || !self.history.iter().any(|shot| shot.side == side) {
+            return false;
+        }
+        // The reply first (if there was one), then the side's own turn
+        while let Some(last) = self.history.last() {
+            let (run_side, run_turn) = (last.side, last.turn);
+            while let Some(shot) = self.history.last() {
+                if shot.side != run_side || shot.turn != run_turn {
+                    break;
+                }
+                let board = match shot.side {
+                    Side::Player => &mut self.opponent_board,
+                    Side::Opponent => &mut self.player_board,
+                };
+                // A shot at a cell already fired at, or a torpedo that missed, didn't change the board
+                if shot.marked_board() {
+                    board.undo_shot(shot.row, shot.col);
+                }
+                self.history.pop();
+            }
+            // Set from the turn itself rather than counted down, since a turn spent on a sweep has no shots
+            if run_side == Side::Player {
+                self.turns = run_turn.saturating_sub(1);
+            }
+            if run_side == side {
+                break;
+            }
+        }
+        self.player_turn = side == Side::Player;
+
+        // Let the computer relearn from the shots it still has
+        self.opponent_ai.forget();
+        for shot in &self.history {
+            if shot.side == Side::Opponent && shot.marked_board() {
+                self.opponent_ai.notify((shot.row, shot.col), &shot.outcome);
+            }
+        }
+        true
+    }
+
+    // Helper method to get the shots from a side's most recent turn.
+    fn last_turn(&self, side: Side) -> &[Move] {
+        let Some(end) = self.history.iter().rposition(|shot| shot.side == side) else {
+            return &[];
+        };
+        let turn = self.history[end].turn;
+        let start = self.history[..end]
+            .iter()
+            .rposition(|shot| shot.side != side || shot.turn != turn)
+            .map_or(0, |index| index + 1);
+        &self.history[start..=end]
+    }
+
+    // Helper method to get what to call the two sides: the player and the opponent, or Player 1 and Player 2
+    // in hotseat mode.
+    fn names(&self) -> [&'static str; 2] {
+        if self.settings.hotseat {
+            ["Player 1", "Player 2"]
+        } else {
+            ["You", "Opponent"]
+        }
+    }
+
+    // Helper method to describe the session score, like "You 2 - 1 Opponent".
+    fn session_score(&self) -> String {
+        let names = self.names();
+        format!(
+            "{} {} - {} {}",
+            names[0], self.session[0], self.session[1], names[1]
+        )
+    }
+
+    // Method to announce the end of the game once the given side has sunk the other side's whole fleet.
+    fn announce_winner(&self, side: Side) {
+        if self.settings.hotseat {
+            let winner = if side == Side::Player { 1 } else { 2 };
+            println!(
+                "{}",
+                format!(
+                    "Player {} wins! All of Player {}'s ships have been sunk!",
+                    winner,
+                    3 - winner
+                )
+                .styled(Style::Success)
+            );
+        } else if side == Side::Player {
+            println!(
+                "{}",
+                "Congratulations! You sank all of your opponent's ships!".styled(Style::Success)
+            );
+        } else {
+            println!(
+                "{}",
+                "Oh no! All of your ships have been sunk!".styled(Style::Warning)
+            );
+        }
+    }
+
+    // Method to describe the given player giving up the game to the other side.
+    fn surrender(&self, current: usize) {
+        if self.settings.hotseat {
+            println!(
+                "{}",
+                format!(
+                    "Player {} surrenders. Player {} wins!",
+                    current,
+                    3 - current
+                )
+                .styled(Style::Warning)
+            );
+        } else {
+            println!(
+                "{}",
+                "You surrendered. Your opponent wins!".styled(Style::Warning)
+            );
+        }
+    }
+
+    // Helper method to get the board of the player whose turn it is and the board they fire at.
+    fn boards(&self) -> (&Board, &Board) {
+        if self.player_turn {
+            (&self.player_board, &self.opponent_board)
+        } else {
+            (&self.opponent_board, &self.player_board)
+        }
+    }
+
+    // Helper method to split the game into the given side's player, that side's own board and the board it
+    // fires at. In hotseat mode both sides are played by people; otherwise the opponent is the computer.
+    fn parts(&mut self, side: Side) -> (&mut dyn Player, &mut Board, &mut Board) {
+        let player: &mut dyn Player = if side == Side::Player || self.settings.hotseat {
+            &mut self.human
+        } else {
+            &mut self.opponent_ai
+        };
+        match side {
+            Side::Player => (player, &mut self.player_board, &mut self.opponent_board),
+            Side::Opponent => (player, &mut self.opponent_board, &mut self.player_board),
+        }
+    }
+
+    // Method to check that a loaded game is self-consistent: both boards must be valid and the same size.
+    fn validate(&self) -> Result<(), String> {
+        self.player_board
+            .validate()
+            .map_err(|error| format!("player board: {}", error))?;
+        self.opponent_board
+            .validate()
+            .map_err(|error| format!("opponent board: {}", error))?;
+        if self.player_board.size() != self.opponent_board.size() {
+            return Err("the two boards have different sizes".to_string());
+        }
+        Ok(())
+    }
+}
+
+// Reasons saving or loading a game can fail.
+#[derive(Debug)]
+pub enum SaveError {
+    Io(io::Error),
+    Json(serde_json::Error),
+    Invalid(String),
+}
+
+impl fmt::Display for SaveError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SaveError::Io(error) => write!(f, "couldn't access the save file: {}", error),
+            SaveError::Json(error) => write!(f, "the save file is not a valid game: {}", error),
+            SaveError::Invalid(reason) => write!(f, "the saved game is inconsistent: {}", reason),
+        }
+    }
+}
+
+impl From<io::Error> for SaveError {
+    fn from(error: io::Error) -> Self {
+        SaveError::Io(error)
+    }
+}
+
+impl From<serde_json::Error> for SaveError {
+    fn from(error: serde_json::Error) -> Self {
+        SaveError::Json(error)
+    }
+}
+
+// Function to write a game to a JSON file.
+pub fn save_game(path: &str, game: &GameState) -> Result<(), SaveError> {
+    let json = serde_json::to_string_pretty(game)?;
+    fs::write(path, json)?;
+    Ok(())
+}
+
+// Function to read a game back from a JSON file written by `save_game`, rejecting files whose boards don't
+// add up.
+pub fn load_game(path: &str) -> Result<GameState, SaveError> {
+    let json = fs::read_to_string(path)?;
+    let game: GameState = serde_json::from_str(&json)?;
+    game.validate().map_err(SaveError::Invalid)?;
+    Ok(game)
+}
@@ -0,0 +1,46 @@
+pub mod ai;
+pub mod bitboard;
+pub mod board;
+pub mod cursor;
+pub mod engine;
+pub mod fleet;
+pub mod game;
+pub mod lifetime;
+pub mod net;
+pub mod player;
+pub mod replay;
+pub mod sim;
+pub mod style;
+pub mod ui;
+
+pub use ai::{
+    heatmap, suggest_target, torpedo_lane, DensityStrategy, Difficulty, HuntTargetStrategy,
+    MonteCarloStrategy, OpponentAi, RandomStrategy, Strategy,
+};
+pub use bitboard::Bitboard;
+pub use board::{
+    is_valid_coord, Board, BoardView, CellState, Lane, Orientation, PlacementError, Ship,
+    ShotOutcome, Stats, TorpedoOutcome, DEFAULT_BOARD_SIZE, MAX_BOARD_SIZE,
+};
+pub use engine::{run_engine, ENGINE_PROTOCOL_VERSION};
+pub use fleet::{FleetConfig, FleetError, Rules, ShipRule, FLEET};
+pub use game::{
+    load_game, save_game, summarize, GameEvent, GameSettings, GameState, Move, SaveError, Side,
+    Summary, TurnReport, MISS_PENALTY, SINK_BONUS, TURN_PENALTY, WIN_BONUS,
+};
+pub use lifetime::{load_stats, save_stats, stats_path, LifetimeStats, StatsError, STATS_ENV};
+pub use net::{
+    canonical_fleet, commitment, play_online, sha256_hex, spectate, verify_reveal, CheatError,
+    Connection, Message, NetError, PROTOCOL_VERSION,
+};
+pub use player::{HumanPlayer, Player};
+pub use replay::{
+    load_replay, placements, save_replay, Placement, Replay, ReplayError, REPLAY_VERSION,
+};
+pub use sim::{run_game, simulate, GameResult, SimulationReport};
+pub use ui::{
+    already_fired_warning, check_salvo, choose_hotseat, coordinate_label, format_elapsed,
+    locale_is_utf8, parse_coordinates, parse_lane, parse_salvo, parse_sweep, read_input, render,
+    set_ascii, set_debug, set_time_limit, time_limit, Arsenal, CellGlyphs, ParseError,
+    PlayerAction, RenderOptions, SalvoError, ASCII_GLYPHS, UNICODE_GLYPHS,
+};
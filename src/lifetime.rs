@@ -0,0 +1,135 @@
+use crate::game::Side;
+use serde::{Deserialize, Serialize};
+use std::env;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+// Environment variable that points the lifetime statistics at a different file.
+pub const STATS_ENV: &str = "BATTLESHIP_STATS";
+
+// Totals kept across sessions for the games played against the computer: games played (draws included), wins,
+// losses, every shot fired, the fewest shots any win has taken and the best score of any game.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct LifetimeStats {
+    #[serde(default)]
+    pub games: usize,
+    #[serde(default)]
+    pub wins: usize,
+    #[serde(default)]
+    pub losses: usize,
+    #[serde(default)]
+    pub shots: usize,
+    #[serde(default)]
+    pub best_game: Option<usize>,
+    #[serde(default)]
+    pub best_score: Option<i32>,
+}
+
+impl LifetimeStats {
+    // Method to add a finished game from the player's side: who won (None for a draw), how many shots the
+    // player fired and the player's score.
+    pub fn record(&mut self, winner: Option<Side>, shots: usize, score: i32) {
+        self.games += 1;
+        self.shots += shots;
+        self.best_score = Some(self.best_score.map_or(score, |best| best.max(score)));
+        match winner {
+            Some(Side::Player) => {
+                self.wins += 1;
+                self.best_game = Some(self.best_game.map_or(shots, |best| best.min(shots)));
+            }
+            Some(Side::Opponent) => self.losses += 1,
+            None => (),
+        }
+    }
+}
+
+impl fmt::Display for LifetimeStats {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "Lifetime: {} wins / {} losses in {} games, ",
+            self.wins, self.losses, self.games
+        )?;
+        match self.best_game {
+            Some(shots) => write!(f, "best game: {} shots", shots)?,
+            None => write!(f, "no wins yet")?,
+        }
+        match self.best_score {
+            Some(score) => write!(f, ", best score: {}", score),
+            None => Ok(()),
+        }
+    }
+}
+
+// Reasons the lifetime statistics can't be read or written.
+#[derive(Debug)]
+pub enum StatsError {
+    Io(io::Error),
+    Json(serde_json::Error),
+}
+
+impl fmt::Display for StatsError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            StatsError::Io(error) => write!(f, "couldn't access the stats file: {}", error),
+            StatsError::Json(error) => write!(f, "the stats file is corrupt: {}", error),
+        }
+    }
+}
+
+impl From<io::Error> for StatsError {
+    fn from(error: io::Error) -> Self {
+        StatsError::Io(error)
+    }
+}
+
+impl From<serde_json::Error> for StatsError {
+    fn from(error: serde_json::Error) -> Self {
+        StatsError::Json(error)
+    }
+}
+
+// Function to find the file the lifetime statistics are kept in: whatever `BATTLESHIP_STATS` names, or else
+// battle_ship/stats.json in the user's data directory (%APPDATA% on Windows, ~/Library/Application Support on
+// macOS, and $XDG_DATA_HOME or ~/.local/share elsewhere). None if there's no such directory to be found.
+pub fn stats_path() -> Option<PathBuf> {
+    let var = |name: &str| env::var_os(name).filter(|value| !value.is_empty());
+    if let Some(path) = var(STATS_ENV) {
+        return Some(PathBuf::from(path));
+    }
+    let data_dir = if cfg!(windows) {
+        var("APPDATA").map(PathBuf::from)
+    } else if cfg!(target_os = "macos") {
+        var("HOME").map(|home| PathBuf::from(home).join("Library/Application Support"))
+    } else {
+        var("XDG_DATA_HOME")
+            .map(PathBuf::from)
+            .or_else(|| var("HOME").map(|home| PathBuf::from(home).join(".local/share")))
+    };
+    data_dir.map(|dir| dir.join("battle_ship").join("stats.json"))
+}
+
+// Function to read the lifetime statistics, starting from nothing if the file doesn't exist yet.
+pub fn load_stats(path: &Path) -> Result<LifetimeStats, StatsError> {
+    match fs::read_to_string(path) {
+        Ok(json) => {
+            let mut stats: LifetimeStats = serde_json::from_str(&json)?;
+            // Files from before games were counted still know of the games that were won or lost
+            stats.games = stats.games.max(stats.wins + stats.losses);
+            Ok(stats)
+        }
+        Err(error) if error.kind() == io::ErrorKind::NotFound => Ok(LifetimeStats::default()),
+        Err(error) => Err(error.into()),
+    }
+}
+
+// Function to write the lifetime statistics, creating the directory they go in if need be.
+pub fn save_stats(path: &Path, stats: &LifetimeStats) -> Result<(), StatsError> {
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    fs::write(path, serde_json::to_string_pretty(stats)?)?;
+    Ok(())
+}
@@ -1,247 +1,470 @@
-use colored::Colorize;
-use rand::Rng;
-use std::io::{self, Write};
+use battle_ship::cursor::set_cursor_targeting;
+use battle_ship::style::{self, Style, Styled};
+use battle_ship::{
+    choose_hotseat, load_game, load_replay, load_stats, locale_is_utf8, play_online, run_engine,
+    save_stats, set_ascii, set_debug, set_time_limit, simulate, spectate, stats_path, Connection,
+    Difficulty, FleetConfig, GameSettings, GameState, LifetimeStats, NetError, ReplayError, Rules,
+    MAX_BOARD_SIZE,
+};
+use std::env;
+use std::io::{self, IsTerminal};
+use std::path::Path;
+use std::process;
+use std::time::Duration;
 
-// define size of game board as const
-const BOARD_SIZE: usize = 10;
+// How long `--fast` pauses between turns unless `--fast-delay` says otherwise.
+const DEFAULT_FAST_DELAY: Duration = Duration::from_millis(800);
 
-struct Board {
-    grid: [[CellState; BOARD_SIZE]; BOARD_SIZE],
-    ships: Vec<(usize, usize)>,
+// How many games `simulate` plays unless `--games` says otherwise.
+const DEFAULT_SIMULATED_GAMES: usize = 1000;
+
+// What `--help` prints: every command-line option.
+const USAGE: &str = "\
+Usage: battle_ship [OPTIONS] [SIZE]
+
+Rules:
+  --size N, N             Play on an NxN board
+  --difficulty LEVEL      easy, normal, hard or expert
+  --salvo                 Fire one shot per surviving ship each turn
+  --hit-again             Fire again after every hit
+  --heat                  Say when a miss lands next to a ship
+  --no-touching           Keep ships from touching
+  --spread                Spread randomly placed ships across the board
+  --diagonal              Let ships lie diagonally
+  --archipelago N         Turn N cells of each board into land
+  --torpedoes N           Give each side N torpedoes that run along a row or column
+  --scans N               Give each side N radar scans that reveal a 3x3 area
+  --sweeps N              Give each side N radar sweeps of a whole row or column
+  --max-turns N           Call the game a draw after N turns
+  --time-limit SECONDS    Forfeit the rest of a turn when no shot is entered in time
+  --fleet PATH|SIZES      Play with the ships in a file, or sizes like 5,4,3,3,2
+  --config PATH           Read the board size and fleet from a JSON file of house rules
+  --seed N                Replay a game from its seed
+
+Modes:
+  --hotseat               Two players sharing the keyboard
+  --vs-computer           Play the computer (with neither, a menu asks)
+  --load PATH             Resume a saved game
+  replay PATH             Watch a recorded game
+  --host PORT             Host a network game
+  --connect HOST:PORT     Join a network game
+  --spectate HOST:PORT    Watch a hosted network game
+  --engine                Play a bot over the line protocol on stdin and stdout
+  simulate, --simulate N  Pit two computer players against each other without a display
+  --games N               How many games to simulate
+  --p1, --p2 STRATEGY     random, hunt, density or montecarlo
+  --stats, --reset-stats  Show or clear the lifetime stats against the computer
+
+Display:
+  --fast                  Skip the Enter prompts between turns
+  --fast-delay MS         How long --fast pauses between turns
+  --no-color              Print plain text
+  --ascii                 Draw the boards without Unicode
+  --cursor                Aim with the arrow keys
+  --debug                 Allow the reveal command
+  --help, -h              Show this help
+";
+
+// What `--stats` and `--reset-stats` say when there's no data directory to keep the stats in.
+const NO_STATS_DIR: &str =
+    "There's nowhere to keep lifetime stats. Set BATTLESHIP_STATS to a file path.";
+
+// What the command line asked for: a new game, a saved game to resume, a replay to watch, a network game to
+// host on a port, join at an address or spectate at an address, a game against a bot over the engine
+// protocol, a batch of computer-vs-computer games to total up, or the lifetime statistics to show or clear.
+enum Start {
+    New,
+    Load(String),
+    Replay(String),
+    Host(u16),
+    Connect(String),
+    Spectate(String),
+    Engine,
+    Simulate {
+        players: [Difficulty; 2],
+        games: usize,
+    },
+    ShowStats,
+    ResetStats,
+}
+
+fn main() {
+    let (settings, start) = parse_args();
+    let auto_advance = settings.auto_advance;
+    let mut game = match start {
+        Start::Simulate { players, games } => run_simulation(players, games, &settings),
+        Start::Replay(path) => watch_replay(&path, auto_advance),
+        Start::ShowStats => show_stats(),
+        Start::ResetStats => reset_stats(),
+        Start::Host(port) => {
+            println!("Waiting for another player to connect on port {}...", port);
+            finish_online(
+                Connection::host(port)
+                    .and_then(|mut connection| play_online(&mut connection, &settings, true)),
+            )
+        }
+        Start::Connect(address) => finish_online(
+            Connection::connect(&address)
+                .and_then(|mut connection| play_online(&mut connection, &settings, false)),
+        ),
+        Start::Engine => play_engine(&settings),
+        Start::Spectate(address) => finish_online(
+            Connection::connect(&address).and_then(|mut connection| spectate(&mut connection)),
+        ),
+        Start::Load(path) => load_game(&path).unwrap_or_else(|error| {
+            exit_with_error(&format!(
+                "Could not load the game from {}: {}.",
+                path, error
+            ))
+        }),
+        Start::New => GameState::new(settings).unwrap_or_else(|error| {
+            exit_with_error(&format!(
+                "Could not place the computer's fleet: {}. Try a larger board.",
+                error
+            ))
+        }),
+    };
+    game.set_auto_advance(auto_advance);
+    game.set_stats_path(stats_path());
+    // Running out of input (Ctrl-D at a prompt, or a closed pipe) can't be recovered from, so quit with a
+    // nonzero exit code instead of leaving the game waiting forever.
+    if let Err(error) = game.play() {
+        let message = match error.kind() {
+            io::ErrorKind::UnexpectedEof => "Game aborted.".to_string(),
+            _ => format!("Game aborted: {}.", error),
+        };
+        exit_with_error(&message);
+    }
 }
 
-#[derive(Clone, Copy, PartialEq)]
-enum CellState {
-    Empty,
-    Ship,
-    Hit,
-    Miss,
+// Function to play back a replay file turn by turn and quit, with an error if it can't be read or doesn't
+// match what the game engine makes of its shots.
+fn watch_replay(path: &str, auto_advance: Option<Duration>) -> ! {
+    let replay = load_replay(path).unwrap_or_else(|error| {
+        exit_with_error(&format!(
+            "Could not load the replay from {}: {}.",
+            path, error
+        ))
+    });
+    match replay.play_back(auto_advance) {
+        Ok(()) => process::exit(0),
+        Err(ReplayError::Io(_)) => exit_with_error("Replay stopped."),
+        Err(error) => exit_with_error(&format!("Could not replay {}: {}.", path, error)),
+    }
 }
 
-// Implement methods for the Board struct.
-impl Board {
-    // Constructor for Board, initializes the grid with all cells empty and no ships.
-    fn new() -> Self {
-        Board {
-            grid: [[CellState::Empty; BOARD_SIZE]; BOARD_SIZE],
-            ships: Vec::new(),
+// Function to play a batch of computer-vs-computer games with nothing drawn, print the totals and quit.
+fn run_simulation(players: [Difficulty; 2], games: usize, settings: &GameSettings) -> ! {
+    match simulate(players, games, settings) {
+        Ok(report) => {
+            report.print([&players[0].to_string(), &players[1].to_string()]);
+            process::exit(0)
         }
+        Err(error) => exit_with_error(&format!("Could not place a fleet: {}.", error)),
     }
+}
 
-    // Method to randomly place a ship of given size on the board, ensuring it doesn't overlap or go out of bounds.
-    fn place_ship(&mut self, size: usize) {
-        let mut rng = rand::thread_rng();
-        loop {
-            let row = rng.gen_range(0..BOARD_SIZE);
-            let col = rng.gen_range(0..BOARD_SIZE);
-            let direction = rng.gen::<bool>();
-            // Check if the chosen position can accommodate the ship without overlapping or going out of bounds.
-            if self.can_place_ship(row, col, size, direction) {
-                for i in 0..size {
-                    let (r, c) = if direction {
-                        (row, col + i)
-                    } else {
-                        (row + i, col)
-                    };
-                    self.grid[r][c] = CellState::Ship;
-                    self.ships.push((r, c));
-                }
-                break;
-            }
+// Function to print the lifetime statistics and quit. A stats file that can't be read is shown as empty, with
+// a warning, just as a game would start it afresh.
+fn show_stats() -> ! {
+    let path = stats_path().unwrap_or_else(|| exit_with_error(NO_STATS_DIR));
+    let stats = load_stats(&path).unwrap_or_else(|error| {
+        println!(
+            "{}",
+            format!("Ignoring the lifetime stats: {}.", error).styled(Style::Warning)
+        );
+        LifetimeStats::default()
+    });
+    println!("{}", stats);
+    println!("Shots fired: {}", stats.shots);
+    process::exit(0)
+}
+
+// Function to clear the lifetime statistics and quit.
+fn reset_stats() -> ! {
+    let path = stats_path().unwrap_or_else(|| exit_with_error(NO_STATS_DIR));
+    match save_stats(&path, &LifetimeStats::default()) {
+        Ok(()) => {
+            println!("Lifetime stats reset.");
+            process::exit(0)
         }
+        Err(error) => exit_with_error(&format!("Could not reset the lifetime stats: {}.", error)),
+    }
+}
+
+// Function to play one game against a bot over the engine protocol on stdin and stdout, then quit.
+fn play_engine(settings: &GameSettings) -> ! {
+    match run_engine(settings, &mut io::stdin().lock(), &mut io::stdout().lock()) {
+        Ok(()) => process::exit(0),
+        Err(error) => exit_with_error(&format!("Engine stopped: {}.", error)),
+    }
+}
+
+// Function to quit once a network game is over, with an error if it ended early.
+fn finish_online(result: Result<(), NetError>) -> ! {
+    match result {
+        Ok(()) => process::exit(0),
+        Err(error) => exit_with_error(&format!("Network game ended: {}.", error)),
     }
+}
 
-    // Helper method to check if a ship can be placed at a specified location without conflicts.
-    fn can_place_ship(&self, row: usize, col: usize, size: usize, direction: bool) -> bool {
-        if direction {
-            if col + size > BOARD_SIZE {
-                return false;
+// Function to read the settings from the command line, as listed in USAGE.
+fn parse_args() -> (GameSettings, Start) {
+    // Without a config file the built-in rules give the board size and fleet
+    let rules = Rules::default();
+    let mut options = GameSettings {
+        size: rules.size,
+        difficulty: Difficulty::Normal,
+        fleet: rules.fleet(),
+        salvo: false,
+        hit_again: false,
+        heat: false,
+        no_touching: false,
+        spread: false,
+        diagonal: false,
+        land: 0,
+        hotseat: false,
+        seed: None,
+        max_turns: None,
+        torpedoes: 0,
+        scans: 0,
+        sweeps: 0,
+        auto_advance: None,
+    };
+    let mut start = Start::New;
+    let mut players = [Difficulty::Normal; 2];
+    let mut games = DEFAULT_SIMULATED_GAMES;
+    let mut simulating = false;
+    let mut hotseat = None;
+    let mut ascii = !locale_is_utf8();
+    let mut time_limit = None;
+    let mut args = env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--salvo" => options.salvo = true,
+            "--hit-again" => options.hit_again = true,
+            "--heat" => options.heat = true,
+            "--no-color" => style::set_enabled(false),
+            "--ascii" => ascii = true,
+            "--debug" => set_debug(true),
+            "--cursor" => set_cursor_targeting(true),
+            "--no-touching" => options.no_touching = true,
+            "--spread" => options.spread = true,
+            "--diagonal" => options.diagonal = true,
+            "--hotseat" => hotseat = Some(true),
+            "--vs-computer" => hotseat = Some(false),
+            "--fleet" => {
+                let value = args.next().unwrap_or_default();
+                // A fleet file if one exists at that path, otherwise a list like "5,4,3,3,2"
+                let fleet = if Path::new(&value).is_file() {
+                    FleetConfig::load(&value)
+                } else {
+                    FleetConfig::parse_list(&value)
+                };
+                options.fleet = fleet.unwrap_or_else(|error| {
+                    exit_with_error(&format!("Could not use the fleet '{}': {}.", value, error))
+                });
             }
-            for i in 0..size {
-                if self.grid[row][col + i] != CellState::Empty {
-                    return false;
-                }
+            "--config" => {
+                let path = args.next().unwrap_or_default();
+                let rules = Rules::load(&path).unwrap_or_else(|error| {
+                    exit_with_error(&format!(
+                        "Could not use the config file '{}': {}.",
+                        path, error
+                    ))
+                });
+                options.size = rules.size;
+                options.fleet = rules.fleet();
             }
-        } else {
-            if row + size > BOARD_SIZE {
-                return false;
+            "--load" => match args.next() {
+                Some(path) => start = Start::Load(path),
+                None => exit_with_error("--load needs the path of a saved game."),
+            },
+            "--host" => {
+                let value = args.next().unwrap_or_default();
+                start = Start::Host(value.parse().unwrap_or_else(|_| {
+                    exit_with_error(&format!(
+                        "Invalid port '{}'. Give a port number to host on.",
+                        value
+                    ))
+                }));
             }
-            for i in 0..size {
-                if self.grid[row + i][col] != CellState::Empty {
-                    return false;
-                }
+            "--connect" => match args.next() {
+                Some(address) => start = Start::Connect(address),
+                None => exit_with_error("--connect needs the host:port of a hosted game."),
+            },
+            "--engine" => start = Start::Engine,
+            "--spectate" => match args.next() {
+                Some(address) => start = Start::Spectate(address),
+                None => exit_with_error("--spectate needs the host:port of a hosted game."),
+            },
+            "simulate" => simulating = true,
+            "--help" | "-h" => {
+                print!("{}", USAGE);
+                process::exit(0);
             }
-        }
-        true
-    }
-
-    // Method for firing at a specified cell, changing its state based on whether a ship is hit or not.
-    fn fire(&mut self, row: usize, col: usize) -> CellState {
-        match self.grid[row][col] {
-            CellState::Empty => {
-                self.grid[row][col] = CellState::Miss;
-                CellState::Miss
+            "--stats" => start = Start::ShowStats,
+            "--reset-stats" => start = Start::ResetStats,
+            "--simulate" | "--games" => {
+                simulating |= arg == "--simulate";
+                let value = args.next().unwrap_or_default();
+                games = value.parse().unwrap_or_else(|_| {
+                    exit_with_error(&format!(
+                        "Invalid number of games '{}'. Give a whole number.",
+                        value
+                    ))
+                });
             }
-            CellState::Ship => {
-                self.grid[row][col] = CellState::Hit;
-                CellState::Hit
+            "--p1" | "--p2" => {
+                let value = args.next().unwrap_or_default();
+                let player = if arg == "--p1" { 0 } else { 1 };
+                players[player] = value.parse().unwrap_or_else(|_| {
+                    exit_with_error(&format!(
+                        "Invalid strategy '{}'. Choose random, hunt, density or montecarlo.",
+                        value
+                    ))
+                });
             }
-            _ => CellState::Miss,
-        }
-    }
-
-    // Method to display the game board, optionally hiding the ships (for the opponent's view).
-    fn display(&self, hide_ships: bool) {
-        print!("   ");
-        for i in 0..BOARD_SIZE {
-            print!(" {} ", i);
-        }
-        println!();
-        for (i, row) in self.grid.iter().enumerate() {
-            print!("{:2} ", i);
-            for cell in row {
-                match cell {
-                    CellState::Empty => {
-                        if hide_ships {
-                            print!("   ");
-                        } else {
-                            print!(" □ "); // □ Water
-                        }
-                    }
-                    CellState::Ship => {
-                        if hide_ships {
-                            print!("   ");
-                        } else {
-                            print!(" ■ ");
-                        }
-                    }
-                    CellState::Hit => print!(" {} ", "●".red()),
-                    CellState::Miss => print!(" {} ", "·".cyan()),
+            "replay" => match args.next() {
+                Some(path) => start = Start::Replay(path),
+                None => exit_with_error("replay needs the path of a replay file."),
+            },
+            "--fast" => {
+                options.auto_advance = options.auto_advance.or(Some(DEFAULT_FAST_DELAY));
+            }
+            "--fast-delay" => {
+                let value = args.next().unwrap_or_default();
+                let millis = value.parse().unwrap_or_else(|_| {
+                    exit_with_error(&format!(
+                        "Invalid delay '{}'. Give the pause in milliseconds.",
+                        value
+                    ))
+                });
+                options.auto_advance = Some(Duration::from_millis(millis));
+            }
+            "--seed" => {
+                let value = args.next().unwrap_or_default();
+                options.seed = Some(value.parse().unwrap_or_else(|_| {
+                    exit_with_error(&format!(
+                        "Invalid seed '{}'. The seed must be a whole number.",
+                        value
+                    ))
+                }));
+            }
+            "--max-turns" => {
+                let value = args.next().unwrap_or_default();
+                options.max_turns = match value.parse() {
+                    Ok(turns) if turns > 0 => Some(turns),
+                    _ => exit_with_error(&format!(
+                        "Invalid turn limit '{}'. Give a whole number of turns above zero.",
+                        value
+                    )),
+                };
+            }
+            "--torpedoes" => {
+                let value = args.next().unwrap_or_default();
+                options.torpedoes = value.parse().unwrap_or_else(|_| {
+                    exit_with_error(&format!(
+                        "Invalid number of torpedoes '{}'. Give a whole number.",
+                        value
+                    ))
+                });
+            }
+            "--scans" => {
+                let value = args.next().unwrap_or_default();
+                options.scans = value.parse().unwrap_or_else(|_| {
+                    exit_with_error(&format!(
+                        "Invalid number of scans '{}'. Give a whole number.",
+                        value
+                    ))
+                });
+            }
+            "--time-limit" => {
+                let value = args.next().unwrap_or_default();
+                match value.parse() {
+                    Ok(seconds) if seconds > 0 => time_limit = Some(seconds),
+                    _ => exit_with_error(&format!(
+                        "Invalid time limit '{}'. Give a whole number of seconds above zero.",
+                        value
+                    )),
                 }
             }
-            println!();
+            "--archipelago" => {
+                let value = args.next().unwrap_or_default();
+                options.land = value.parse().unwrap_or_else(|_| {
+                    exit_with_error(&format!(
+                        "Invalid number of land cells '{}'. Give a whole number.",
+                        value
+                    ))
+                });
+            }
+            "--sweeps" => {
+                let value = args.next().unwrap_or_default();
+                options.sweeps = value.parse().unwrap_or_else(|_| {
+                    exit_with_error(&format!(
+                        "Invalid number of radar sweeps '{}'. Give a whole number.",
+                        value
+                    ))
+                });
+            }
+            "--size" => options.size = parse_board_size(&args.next().unwrap_or_default()),
+            "--difficulty" => {
+                let value = args.next().unwrap_or_default();
+                options.difficulty = value.parse().unwrap_or_else(|_| {
+                    exit_with_error(&format!(
+                        "Invalid difficulty '{}'. Choose easy, normal, hard or expert.",
+                        value
+                    ))
+                });
+            }
+            _ => options.size = parse_board_size(&arg),
         }
     }
-
-    // Method to determine if all ships have been hit, indicating game over.
-    fn is_game_over(&self) -> bool {
-        self.ships
-            .iter()
-            .all(|&(r, c)| self.grid[r][c] == CellState::Hit)
+    if simulating {
+        start = Start::Simulate { players, games };
     }
-}
-fn main() {
-    let mut player_board: Board = Board::new();
-    let mut opponent_board: Board = Board::new();
-
-    player_board.place_ship(5);
-    player_board.place_ship(4);
-    player_board.place_ship(3);
-    player_board.place_ship(3);
-    player_board.place_ship(2);
-
-    opponent_board.place_ship(5);
-    opponent_board.place_ship(4);
-    opponent_board.place_ship(3);
-    opponent_board.place_ship(3);
-    opponent_board.place_ship(2);
-
-    // Main game loop
-    loop {
-        // Clear the screen for a fresh display of the game board each turn
-        print!("\x1b[2J\x1b[1;1H");
-
-        // Display the player's board and the opponent's board
-        println!("{}", "Your Board:".bold());
-        player_board.display(false);
-        println!("{}", "Opponent's Board:".bold());
-        opponent_board.display(true);
-        // Player's turn: prompt for input and process the firing result
-        let (player_row, player_col) = get_player_input();
-        let result = opponent_board.fire(player_row, player_col);
-        match result {
-            CellState::Miss => println!("{}", "You missed!".cyan()),
-            CellState::Hit => println!("{}", "You hit a ship!".red()),
-            _ => (),
-        }
-        println!("Press Enter to continue...");
-        io::stdin()
-            .read_line(&mut String::new())
-            .expect("Failed to read line");
-
-        // Check if all opponent ships have been sunk
-        if opponent_board.is_game_over() {
-            println!(
-                "{}",
-                "Congratulations! You sank all of your opponent's ships!"
-                    .bold()
-                    .green()
-            );
-            break;
-        }
-
-        // Opponent's turn: simulate opponent move (could be AI-controlled in future enhancements)
-        let (opponent_row, opponent_col) = generate_opponent_move();
-        let result = player_board.fire(opponent_row, opponent_col);
-        match result {
-            CellState::Miss => println!("{}", "Opponent missed!".cyan()),
-            CellState::Hit => println!("{}", "Opponent hit one of your ships!".red()),
-            _ => (),
-        }
-        println!("Press Enter to continue...");
-        io::stdin()
-            .read_line(&mut String::new())
-            .expect("Failed to read line");
-
-        // Check if all player ships have been sunk
-        if player_board.is_game_over() {
-            println!(
-                "{}",
-                "Oh no! All of your ships have been sunk!".bold().red()
-            );
-            break;
-        }
+    // Without a mode flag, ask which mode to play, unless the input is piped in
+    if hotseat.is_none() && matches!(start, Start::New) && io::stdin().is_terminal() {
+        hotseat = Some(choose_hotseat().unwrap_or_else(|_| exit_with_error("Game aborted.")));
+    }
+    options.hotseat = hotseat.unwrap_or(false);
+    set_ascii(ascii);
+    set_time_limit(time_limit);
+    // Bots speaking the engine protocol only play the plain rules, as network games do
+    let online = matches!(start, Start::Host(_) | Start::Connect(_) | Start::Engine);
+    let specials = options.torpedoes > 0 || options.scans > 0 || options.sweeps > 0;
+    let modes =
+        options.salvo || options.hit_again || options.heat || options.hotseat || options.land > 0;
+    if online && (modes || specials || time_limit.is_some()) {
+        exit_with_error(
+            "Salvo, hit-again, heat, archipelago and hotseat modes, torpedoes, scans, sweeps and time limits can't be used in a network game or against a bot.",
+        );
+    }
+    if options.salvo && options.hit_again {
+        exit_with_error("Salvo and hit-again rules can't be combined.");
     }
+    if (matches!(start, Start::New) || online || simulating) && !options.fleet.fits(options.size) {
+        exit_with_error(&format!(
+            "The fleet doesn't fit on a {0}x{0} board. Choose a larger board or a smaller fleet.",
+            options.size
+        ));
+    }
+    (options, start)
 }
 
-// Function to get player input for firing
-fn get_player_input() -> (usize, usize) {
-    loop {
-        print!("{}", "Enter coordinates to fire (row, col): ".bold());
-        io::stdout().flush().unwrap();
-        let mut input = String::new();
-        io::stdin()
-            .read_line(&mut input)
-            .expect("Failed to read line");
-        let coordinates: Option<Vec<usize>> = input
-            .trim()
-            .split(',')
-            .map(|s| s.trim().parse().ok())
-            .collect();
-
-        if let Some(coordinates) = coordinates {
-            if coordinates.len() == 2 && coordinates[0] < BOARD_SIZE && coordinates[1] < BOARD_SIZE
-            {
-                return (coordinates[0], coordinates[1]);
-            } else {
-                print_error_message();
-            }
-        } else {
-            print_error_message();
-        }
+// Function to validate a requested board size. Whether the fleet fits is checked once all options are read.
+fn parse_board_size(arg: &str) -> usize {
+    match arg.parse::<usize>() {
+        Ok(size) if (1..=MAX_BOARD_SIZE).contains(&size) => size,
+        _ => exit_with_error(&format!(
+            "Invalid board size '{}'. Choose a size from 1 to {}.",
+            arg, MAX_BOARD_SIZE
+        )),
     }
 }
-fn print_error_message() {
-    println!(
-        "{}",
-        "Invalid input. Please enter row and column numbers separated by a comma."
-            .bold()
-            .red()
-    );
-}
 
-// Function to generate a random move for the opponent
-fn generate_opponent_move() -> (usize, usize) {
-    let mut rng = rand::thread_rng();
-    (rng.gen_range(0..BOARD_SIZE), rng.gen_range(0..BOARD_SIZE))
+// Function to print a startup error and quit.
+fn exit_with_error(message: &str) -> ! {
+    eprintln!("{}", message.styled(Style::Warning));
+    process::exit(1);
 }
@@ -0,0 +1,827 @@
+use crate::board::{is_valid_coord, Board, ShotOutcome, MAX_BOARD_SIZE};
+use crate::fleet::FleetConfig;
+use crate::game::{GameSettings, Move, Side};
+use crate::replay::{placements, Placement};
+use crate::style::{clear_screen, reset_colors, Style, Styled};
+use crate::ui::{
+    already_fired_warning, coordinate_label, describe_move, describe_outcome, display_side_by_side,
+    get_player_input, glyphs, place_player_fleet, report_opponent_shot, report_player_shot,
+    show_boards, wait_to_continue, Arsenal, PlayerAction, ASCII_GLYPHS,
+};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::fmt;
+use std::io::{self, BufRead, BufReader, Write};
+use std::mem;
+use std::net::{TcpListener, TcpStream};
+use std::sync::mpsc::{self, Sender};
+use std::sync::{Arc, Mutex};
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+
+// The message format spoken by this version of the game. Both sides must agree on it before the game starts.
+pub const PROTOCOL_VERSION: u32 = 2;
+
+// One message between the two players in a network game, sent as a single line of JSON. Each side opens with
+// a Hello, places its ships and sends a Commit to them. After that the side whose turn it is sends Fire, the
+// other side answers with the Result, and a side whose fleet is gone (or who gives up) sends GameOver. Once
+// the game is over both sides send a Reveal so the other can check every result it was given. A spectator
+// opens with Spectate instead, and from then on only listens: the host sends it a Snapshot of the shots so far
+// once the game has started, a Shot for every shot after that, and both fleets in Ended when the game is over.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub enum Message {
+    Hello {
+        version: u32,
+        size: usize,
+        fleet: FleetConfig,
+    },
+    Fire {
+        row: usize,
+        col: usize,
+    },
+    Result {
+        outcome: ShotOutcome,
+    },
+    GameOver,
+    Commit {
+        hash: String,
+    },
+    Reveal {
+        fleet: Vec<Placement>,
+        salt: String,
+    },
+    Spectate {
+        version: u32,
+    },
+    Snapshot {
+        size: usize,
+        moves: Vec<Move>,
+    },
+    Shot {
+        shot: Move,
+    },
+    Ended {
+        host_fleet: Vec<Placement>,
+        guest_fleet: Vec<Placement>,
+    },
+}
+
+// Reasons a network game can't start or has to end early.
+#[derive(Debug)]
+pub enum NetError {
+    Io(io::Error),
+    Input(io::Error),
+    Disconnected,
+    Malformed(String),
+    VersionMismatch(u32),
+    Mismatch(String),
+    Unexpected(String),
+}
+
+impl fmt::Display for NetError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            NetError::Io(error) => write!(f, "network error: {}", error),
+            NetError::Input(error) => write!(f, "couldn't read your input: {}", error),
+            NetError::Disconnected => write!(f, "the other player disconnected"),
+            NetError::Malformed(text) => {
+                write!(f, "the other player sent a message that makes no sense: '{}'", text)
+            }
+            NetError::VersionMismatch(version) => write!(
+                f,
+                "the other player's game speaks protocol version {}, but this one speaks version {}",
+                version, PROTOCOL_VERSION
+            ),
+            NetError::Mismatch(reason) => write!(f, "the two games don't match: {}", reason),
+            NetError::Unexpected(message) => {
+                write!(f, "the other player sent {} out of turn", message)
+            }
+        }
+    }
+}
+
+impl From<io::Error> for NetError {
+    fn from(error: io::Error) -> Self {
+        match error.kind() {
+            io::ErrorKind::BrokenPipe
+            | io::ErrorKind::ConnectionReset
+            | io::ErrorKind::ConnectionAborted
+            | io::ErrorKind::UnexpectedEof => NetError::Disconnected,
+            _ => NetError::Io(error),
+        }
+    }
+}
+
+// Ways a revealed board can fail to back up the results the other player reported during the game.
+#[derive(Clone, Debug, PartialEq)]
+pub enum CheatError {
+    Commitment,
+    Board(String),
+    Result {
+        row: usize,
+        col: usize,
+        reported: ShotOutcome,
+        actual: ShotOutcome,
+    },
+}
+
+impl fmt::Display for CheatError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            CheatError::Commitment => {
+                write!(f, "the board they revealed isn't the one they committed to")
+            }
+            CheatError::Board(reason) => {
+                write!(f, "the board they revealed is illegal: {}", reason)
+            }
+            CheatError::Result {
+                row,
+                col,
+                reported,
+                actual,
+            } => write!(
+                f,
+                "they reported '{}' at {}, but their board gives '{}'",
+                describe_outcome(reported),
+                coordinate_label(*row, *col),
+                describe_outcome(actual)
+            ),
+        }
+    }
+}
+
+// Function to write a fleet's placements the same way whatever order the ships were placed in: sorted by
+// where they start, as JSON.
+pub fn canonical_fleet(fleet: &[Placement]) -> String {
+    let mut sorted = fleet.to_vec();
+    sorted.sort_by(|a, b| (a.row, a.col, &a.name).cmp(&(b.row, b.col, &b.name)));
+    serde_json::to_string(&sorted).expect("placements always serialize")
+}
+
+// Function to work out the SHA-256 digest of some bytes as 64 lowercase hex digits.
+pub fn sha256_hex(data: &[u8]) -> String {
+    Sha256::digest(data)
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect()
+}
+
+// Function to work out the hash a side commits to before the first shot: SHA-256 of the salt and the
+// canonical placements, as hex. The salt keeps the other side from guessing the board by trying placements.
+pub fn commitment(fleet: &[Placement], salt: &str) -> String {
+    sha256_hex(format!("{}:{}", salt, canonical_fleet(fleet)).as_bytes())
+}
+
+// Function to check the board the other side revealed at the end of a game: it must hash to what they
+// committed to, be a legal placement of the agreed fleet under the game's rules, and give exactly the results
+// they reported for every shot fired at it, in order.
+pub fn verify_reveal(
+    hash: &str,
+    fleet: &[Placement],
+    salt: &str,
+    settings: &GameSettings,
+    shots: &[((usize, usize), ShotOutcome)],
+) -> Result<(), CheatError> {
+    if commitment(fleet, salt) != hash {
+        return Err(CheatError::Commitment);
+    }
+    let mut board = Board::new(settings.size);
+    board.set_no_touching(settings.no_touching);
+    board.set_diagonal(settings.diagonal);
+    for ship in fleet {
+        board
+            .place_ship_at(&ship.name, ship.row, ship.col, ship.size, ship.orientation)
+            .map_err(|error| {
+                CheatError::Board(format!(
+                    "the {} can't go at {}: {}",
+                    ship.name,
+                    coordinate_label(ship.row, ship.col),
+                    error
+                ))
+            })?;
+    }
+    let mut revealed: Vec<(&str, usize)> = fleet
+        .iter()
+        .map(|ship| (ship.name.as_str(), ship.size))
+        .collect();
+    let mut agreed: Vec<(&str, usize)> = settings
+        .fleet
+        .ships
+        .iter()
+        .map(|(name, size)| (name.as_str(), *size))
+        .collect();
+    revealed.sort();
+    agreed.sort();
+    if revealed != agreed {
+        return Err(CheatError::Board(
+            "it doesn't hold the agreed fleet".to_string(),
+        ));
+    }
+    for ((row, col), reported) in shots {
+        let actual = board.fire(*row, *col);
+        if actual != *reported {
+            return Err(CheatError::Result {
+                row: *row,
+                col: *col,
+                reported: reported.clone(),
+                actual,
+            });
+        }
+    }
+    Ok(())
+}
+
+// How long the host waits on a spectator that isn't reading before giving up on it.
+const SPECTATOR_TIMEOUT: Duration = Duration::from_secs(5);
+
+// How long the host waits for someone who has just connected to say whether they're the opponent or a
+// spectator before dropping them.
+const GREETING_TIMEOUT: Duration = Duration::from_secs(5);
+
+// One spectator of a hosted game: the channel its messages are queued on and the thread writing them out.
+struct Viewer {
+    sender: Sender<Message>,
+    writer: JoinHandle<()>,
+}
+
+// Everyone watching a hosted game, along with what a spectator joining late needs to catch up: the board size
+// once the game has started, every shot so far and the final fleets once it's over. Each spectator has its own
+// thread writing to its socket, so one that is slow or has gone away never holds up the game.
+#[derive(Default)]
+struct Audience {
+    size: Option<usize>,
+    moves: Vec<Move>,
+    ended: Option<Message>,
+    viewers: Vec<Viewer>,
+}
+
+impl Audience {
+    // Method to start writing to a new spectator, beginning with a snapshot if the game is already under way.
+    fn join(&mut self, stream: TcpStream) {
+        let (sender, receiver) = mpsc::channel();
+        let _ = stream.set_write_timeout(Some(SPECTATOR_TIMEOUT));
+        let writer = thread::spawn(move || {
+            let mut stream = stream;
+            for message in receiver {
+                if write_message(&mut stream, &message).is_err() {
+                    break;
+                }
+            }
+        });
+        if let Some(size) = self.size {
+            let _ = sender.send(Message::Snapshot {
+                size,
+                moves: self.moves.clone(),
+            });
+        }
+        if let Some(ended) = &self.ended {
+            let _ = sender.send(ended.clone());
+        }
+        self.viewers.push(Viewer { sender, writer });
+    }
+
+    // Method to queue a message for every spectator, remembering what a late one will need and dropping any
+    // whose writer has given up.
+    fn broadcast(&mut self, message: Message) {
+        match &message {
+            Message::Snapshot { size, moves } => {
+                self.size = Some(*size);
+                self.moves = moves.clone();
+            }
+            Message::Shot { shot } => self.moves.push(shot.clone()),
+            Message::Ended { .. } => self.ended = Some(message.clone()),
+            _ => (),
+        }
+        self.viewers
+            .retain(|viewer| viewer.sender.send(message.clone()).is_ok());
+    }
+}
+
+// Function to write one message as a line of JSON.
+fn write_message(writer: &mut TcpStream, message: &Message) -> io::Result<()> {
+    let line = serde_json::to_string(message)?;
+    writeln!(writer, "{}", line)?;
+    writer.flush()
+}
+
+// Function to keep accepting spectators on a hosted game's port for as long as the game lasts. Each one is
+// heard out on its own thread, so a connection that never says anything doesn't keep others waiting.
+fn accept_spectators(listener: TcpListener, audience: Arc<Mutex<Audience>>) {
+    for stream in listener.incoming().flatten() {
+        let audience = Arc::clone(&audience);
+        thread::spawn(move || {
+            let Ok(mut connection) = Connection::new(stream) else {
+                return;
+            };
+            match connection.receive_greeting() {
+                Ok(Message::Spectate { version }) if version == PROTOCOL_VERSION => {
+                    if let (Ok(writer), Ok(mut audience)) =
+                        (connection.writer.try_clone(), audience.lock())
+                    {
+                        audience.join(writer);
+                    }
+                }
+                _ => (),
+            }
+        });
+    }
+}
+
+// A connection to the other player, reading and writing one message per line. The host's end also keeps
+// the spectators watching the game.
+pub struct Connection {
+    reader: BufReader<TcpStream>,
+    writer: TcpStream,
+    pending: Option<Message>,
+    audience: Option<Arc<Mutex<Audience>>>,
+}
+
+impl Connection {
+    // Constructor wrapping an open TCP stream.
+    pub fn new(stream: TcpStream) -> Result<Self, NetError> {
+        let writer = stream.try_clone()?;
+        Ok(Connection {
+            reader: BufReader::new(stream),
+            writer,
+            pending: None,
+            audience: None,
+        })
+    }
+
+    // Function to wait on a port until the other player connects.
+    pub fn host(port: u16) -> Result<Self, NetError> {
+        Self::accept(TcpListener::bind(("0.0.0.0", port))?)
+    }
+
+    // Function to wait on a listener until the other player connects, taking in anyone who comes to spectate
+    // in the meantime. The listener then carries on taking spectators in the background.
+    // Anyone who says nothing in time, or whose first message is neither a Spectate nor a Hello, is dropped.
+    pub fn accept(listener: TcpListener) -> Result<Self, NetError> {
+        let audience = Arc::new(Mutex::new(Audience::default()));
+        loop {
+            let (stream, _) = listener.accept()?;
+            let Ok(mut connection) = Self::new(stream) else {
+                continue;
+            };
+            let first = match connection.receive_greeting() {
+                Ok(Message::Spectate { version }) if version == PROTOCOL_VERSION => {
+                    if let (Ok(writer), Ok(mut audience)) =
+                        (connection.writer.try_clone(), audience.lock())
+                    {
+                        audience.join(writer);
+                    }
+                    continue;
+                }
+                Ok(first @ Message::Hello { .. }) => first,
+                _ => continue,
+            };
+            connection.pending = Some(first);
+            let spectators = Arc::clone(&audience);
+            thread::spawn(move || accept_spectators(listener, spectators));
+            connection.audience = Some(audience);
+            return Ok(connection);
+        }
+    }
+
+    // Function to connect to a player hosting a game at "host:port".
+    pub fn connect(address: &str) -> Result<Self, NetError> {
+        Self::new(TcpStream::connect(address)?)
+    }
+
+    // Method to send one message.
+    pub fn send(&mut self, message: &Message) -> Result<(), NetError> {
+        write_message(&mut self.writer, message)?;
+        Ok(())
+    }
+
+    // Method to pass a message on to everyone spectating, which does nothing unless this is the host's end.
+    // A Snapshot marks the start of the game, and is what spectators who were already waiting are sent first.
+    pub fn broadcast(&mut self, message: Message) {
+        if let Some(Ok(mut audience)) = self.audience.as_ref().map(|audience| audience.lock()) {
+            audience.broadcast(message);
+        }
+    }
+
+    // Method to wait for the next message. A closed connection is reported as a disconnect and a line that
+    // isn't a message as malformed.
+    pub fn receive(&mut self) -> Result<Message, NetError> {
+        if let Some(message) = self.pending.take() {
+            return Ok(message);
+        }
+        let mut line = String::new();
+        if self.reader.read_line(&mut line)? == 0 {
+            return Err(NetError::Disconnected);
+        }
+        serde_json::from_str(&line).map_err(|_| NetError::Malformed(line.trim().to_string()))
+    }
+
+    // Method to wait a short while for the first message from someone who has just connected, after which
+    // reads wait as long as they need to again.
+    fn receive_greeting(&mut self) -> Result<Message, NetError> {
+        self.reader
+            .get_ref()
+            .set_read_timeout(Some(GREETING_TIMEOUT))?;
+        let first = self.receive()?;
+        self.reader.get_ref().set_read_timeout(None)?;
+        Ok(first)
+    }
+
+    // Method to exchange Hellos and check that both games speak the same protocol and use the same board
+    // size and fleet.
+    pub fn handshake(&mut self, size: usize, fleet: &FleetConfig) -> Result<(), NetError> {
+        self.send(&Message::Hello {
+            version: PROTOCOL_VERSION,
+            size,
+            fleet: fleet.clone(),
+        })?;
+        match self.receive()? {
+            Message::Hello { version, .. } if version != PROTOCOL_VERSION => {
+                Err(NetError::VersionMismatch(version))
+            }
+            Message::Hello {
+                size: their_size, ..
+            } if their_size != size => Err(NetError::Mismatch(format!(
+                "the other player is using a {0}x{0} board, not {1}x{1}",
+                their_size, size
+            ))),
+            Message::Hello {
+                fleet: their_fleet, ..
+            } if their_fleet != *fleet => Err(NetError::Mismatch(
+                "the other player is using a different fleet".to_string(),
+            )),
+            Message::Hello { .. } => Ok(()),
+            other => Err(NetError::Unexpected(describe_message(&other))),
+        }
+    }
+}
+
+// Closing the host's end lets every spectator's writer finish sending what it has queued, which the timeout on
+// their sockets keeps from taking long.
+impl Drop for Connection {
+    fn drop(&mut self) {
+        let Some(audience) = self.audience.take() else {
+            return;
+        };
+        let viewers = match audience.lock() {
+            Ok(mut audience) => mem::take(&mut audience.viewers),
+            Err(_) => return,
+        };
+        for Viewer { sender, writer } in viewers {
+            drop(sender);
+            let _ = writer.join();
+        }
+    }
+}
+
+// Function to name a message for error reports.
+fn describe_message(message: &Message) -> String {
+    match message {
+        Message::Hello { .. } => "a greeting".to_string(),
+        Message::Fire { .. } => "a shot".to_string(),
+        Message::Result { .. } => "a shot result".to_string(),
+        Message::GameOver => "the end of the game".to_string(),
+        Message::Commit { .. } => "a board commitment".to_string(),
+        Message::Reveal { .. } => "a revealed board".to_string(),
+        Message::Spectate { .. } => "a request to spectate".to_string(),
+        Message::Snapshot { .. } => "a snapshot of the game".to_string(),
+        Message::Shot { .. } => "a spectated shot".to_string(),
+        Message::Ended { .. } => "the final boards".to_string(),
+    }
+}
+
+// Function to play a game against another player over a connection. Each side places its own ships locally
+// and only shots and their results cross the wire, so the other side's board is filled in from the reported
+// results. Before the first shot each side commits to its board with a salted hash, and once the game is over
+// both boards are revealed and every reported result is checked against them. The host fires first. The game
+// ends with an error if the connection drops or the other side sends something unexpected.
+pub fn play_online(
+    connection: &mut Connection,
+    settings: &GameSettings,
+    hosting: bool,
+) -> Result<(), NetError> {
+    let size = settings.size;
+    connection.handshake(size, &settings.fleet)?;
+
+    let mut rng = match settings.seed {
+        Some(seed) => StdRng::seed_from_u64(seed),
+        None => StdRng::from_entropy(),
+    };
+    let mut own_board = Board::new(size);
+    own_board.set_no_touching(settings.no_touching);
+    own_board.set_spread(settings.spread);
+    own_board.set_diagonal(settings.diagonal);
+    place_player_fleet(&mut own_board, &settings.fleet, &mut rng).map_err(NetError::Input)?;
+    let mut target_board = Board::new(size);
+    let fleet_cells: usize = settings.fleet.ships.iter().map(|&(_, size)| size).sum();
+
+    // The salt is never seeded, so a seeded game doesn't give the board away
+    let salt = format!("{:032x}", StdRng::from_entropy().gen::<u128>());
+    let own_fleet = placements(&own_board);
+    connection.send(&Message::Commit {
+        hash: commitment(&own_fleet, &salt),
+    })?;
+    let their_hash = match connection.receive()? {
+        Message::Commit { hash } => hash,
+        other => return Err(NetError::Unexpected(describe_message(&other))),
+    };
+    let mut shots = Vec::new();
+    connection.broadcast(Message::Snapshot {
+        size,
+        moves: Vec::new(),
+    });
+
+    // Spectators see the host as the player and the guest as the opponent, and a turn as the host's shot
+    // and the guest's reply
+    let (mine, theirs) = if hosting {
+        (Side::Player, Side::Opponent)
+    } else {
+        (Side::Opponent, Side::Player)
+    };
+    let mut turn = 0;
+    let mut my_turn = hosting;
+    'game: loop {
+        if my_turn == hosting {
+            turn += 1;
+        }
+        if my_turn {
+            clear_screen();
+            println!("{}", "Your turn".styled(Style::Emphasis));
+            display_side_by_side(&own_board, &target_board);
+            let (row, col) = loop {
+                match get_player_input(size, 1, Arsenal::default()).map_err(NetError::Input)? {
+                    PlayerAction::Fire(targets) => {
+                        let (row, col) = targets[0];
+                        if target_board.has_been_fired_at(row, col) {
+                            println!(
+                                "{}",
+                                already_fired_warning(row, col, target_board.cell(row, col))
+                                    .styled(Style::Warning)
+                            );
+                            continue;
+                        }
+                        break (row, col);
+                    }
+                    PlayerAction::Quit | PlayerAction::Surrender => {
+                        connection.send(&Message::GameOver)?;
+                        reset_colors();
+                        println!(
+                            "{}",
+                            "You left the game. Your opponent wins.".styled(Style::Warning)
+                        );
+                        break 'game;
+                    }
+                    PlayerAction::Torpedo(_) | PlayerAction::Scan(..) | PlayerAction::Sweep(_) => {
+                        println!(
+                            "{}",
+                            "Torpedoes, scans and sweeps aren't available in a network game."
+                                .styled(Style::Warning)
+                        )
+                    }
+                    // Turns aren't timed in network games, so there's nothing to forfeit
+                    PlayerAction::TimedOut => (),
+                    PlayerAction::Save(_)
+                    | PlayerAction::Load(_)
+                    | PlayerAction::Record(_)
+                    | PlayerAction::Undo
+                    | PlayerAction::Hint
+                    | PlayerAction::Reveal => {
+                        println!(
+                            "{}",
+                            "Saving, loading, undo, hints and replays aren't available in a network game."
+                                .styled(Style::Warning)
+                        )
+                    }
+                }
+            };
+            connection.send(&Message::Fire { row, col })?;
+            match connection.receive()? {
+                Message::Result { outcome } => {
+                    target_board.record_shot(row, col, &outcome);
+                    report_player_shot(&outcome);
+                    connection.broadcast(Message::Shot {
+                        shot: Move {
+                            turn,
+                            side: mine,
+                            row,
+                            col,
+                            outcome: outcome.clone(),
+                            torpedo: None,
+                        },
+                    });
+                    shots.push(((row, col), outcome));
+                }
+                other => return Err(NetError::Unexpected(describe_message(&other))),
+            }
+        } else {
+            println!("Waiting for the other player to fire...");
+            match connection.receive()? {
+                Message::Fire { row, col } => {
+                    if !is_valid_coord(row, col, size) {
+                        return Err(NetError::Malformed(format!(
+                            "a shot at row {}, column {}",
+                            row, col
+                        )));
+                    }
+                    let outcome = own_board.fire(row, col);
+                    connection.send(&Message::Result {
+                        outcome: outcome.clone(),
+                    })?;
+                    report_opponent_shot(&outcome);
+                    connection.broadcast(Message::Shot {
+                        shot: Move {
+                            turn,
+                            side: theirs,
+                            row,
+                            col,
+                            outcome,
+                            torpedo: None,
+                        },
+                    });
+                    if own_board.is_game_over() {
+                        connection.send(&Message::GameOver)?;
+                        println!(
+                            "{}",
+                            "Oh no! All of your ships have been sunk!".styled(Style::Warning)
+                        );
+                        break;
+                    }
+                    wait_to_continue(settings.auto_advance).map_err(NetError::Input)?;
+                }
+                Message::GameOver => {
+                    if target_board.stats().hits == fleet_cells {
+                        println!(
+                            "{}",
+                            "Congratulations! You sank all of your opponent's ships!"
+                                .styled(Style::Success)
+                        );
+                    } else {
+                        println!(
+                            "{}",
+                            "Your opponent left the game. You win!".styled(Style::Success)
+                        );
+                    }
+                    break;
+                }
+                other => return Err(NetError::Unexpected(describe_message(&other))),
+            }
+        }
+        my_turn = !my_turn;
+    }
+
+    connection.send(&Message::Reveal {
+        fleet: own_fleet.clone(),
+        salt,
+    })?;
+    let their_fleet = match connection.receive() {
+        Ok(Message::Reveal { fleet, salt }) => {
+            match verify_reveal(&their_hash, &fleet, &salt, settings, &shots) {
+                Ok(()) => println!(
+                    "{}",
+                    "Your opponent's board matches every result they reported.".styled(Style::Info)
+                ),
+                Err(error) => {
+                    let dash = if *glyphs() == ASCII_GLYPHS {
+                        "-"
+                    } else {
+                        "—"
+                    };
+                    println!(
+                        "{}",
+                        format!(
+                            "Opponent's results don't match their committed board {} they cheated: {}.",
+                            dash, error
+                        )
+                        .styled(Style::Warning)
+                    );
+                }
+            }
+            fleet
+        }
+        Ok(other) => return Err(NetError::Unexpected(describe_message(&other))),
+        Err(NetError::Disconnected) => {
+            println!(
+                "{}",
+                "Your opponent left without revealing their board, so their results can't be checked."
+                    .styled(Style::Warning)
+            );
+            Vec::new()
+        }
+        Err(error) => return Err(error),
+    };
+    let (host_fleet, guest_fleet) = if hosting {
+        (own_fleet, their_fleet)
+    } else {
+        (their_fleet, own_fleet)
+    };
+    connection.broadcast(Message::Ended {
+        host_fleet,
+        guest_fleet,
+    });
+    Ok(())
+}
+
+// Function to watch a hosted game as a spectator: both boards are drawn from the shots as they're fired, with
+// no ships showing, and the fleets the two players revealed are shown once the game is over. A fleet the
+// guest never revealed stays hidden.
+pub fn spectate(connection: &mut Connection) -> Result<(), NetError> {
+    connection.send(&Message::Spectate {
+        version: PROTOCOL_VERSION,
+    })?;
+    println!("Waiting for the game to start...");
+    let (size, mut moves) = match connection.receive()? {
+        Message::Snapshot { size, moves } if (1..=MAX_BOARD_SIZE).contains(&size) => (size, moves),
+        Message::Snapshot { size, .. } => {
+            return Err(NetError::Malformed(format!("a {0}x{0} board", size)))
+        }
+        other => return Err(NetError::Unexpected(describe_message(&other))),
+    };
+    let mut boards = [Board::new(size), Board::new(size)];
+    for shot in &moves {
+        record_move(&mut boards, shot)?;
+    }
+    show_spectated(&boards, moves.last());
+    loop {
+        match connection.receive()? {
+            Message::Shot { shot } => {
+                record_move(&mut boards, &shot)?;
+                show_spectated(&boards, Some(&shot));
+                moves.push(shot);
+            }
+            Message::Ended {
+                host_fleet,
+                guest_fleet,
+            } => {
+                // Both boards are rebuilt from the revealed fleets and every shot played again on them
+                let mut revealed = [Board::new(size), Board::new(size)];
+                for (board, fleet) in revealed.iter_mut().zip([host_fleet, guest_fleet]) {
+                    board.set_diagonal(true);
+                    for ship in fleet {
+                        board
+                            .place_ship_at(
+                                &ship.name,
+                                ship.row,
+                                ship.col,
+                                ship.size,
+                                ship.orientation,
+                            )
+                            .map_err(|error| {
+                                NetError::Malformed(format!("a fleet where {}", error))
+                            })?;
+                    }
+                }
+                for shot in &moves {
+                    let board = match shot.side {
+                        Side::Player => &mut revealed[1],
+                        Side::Opponent => &mut revealed[0],
+                    };
+                    board.fire(shot.row, shot.col);
+                }
+                clear_screen();
+                println!(
+                    "{}",
+                    "The game is over. Here's where every ship was:".styled(Style::Emphasis)
+                );
+                show_boards(
+                    ["Host's Board:", "Guest's Board:"],
+                    &revealed[0],
+                    &revealed[1],
+                );
+                return Ok(());
+            }
+            other => return Err(NetError::Unexpected(describe_message(&other))),
+        }
+    }
+}
+
+// Function to mark one spectated shot on the board it was fired at: the guest's for the host's shots, and the
+// host's for the guest's.
+fn record_move(boards: &mut [Board; 2], shot: &Move) -> Result<(), NetError> {
+    if !is_valid_coord(shot.row, shot.col, boards[0].size()) {
+        return Err(NetError::Malformed(format!(
+            "a shot at row {}, column {}",
+            shot.row, shot.col
+        )));
+    }
+    let board = match shot.side {
+        Side::Player => &mut boards[1],
+        Side::Opponent => &mut boards[0],
+    };
+    board.record_shot(shot.row, shot.col, &shot.outcome);
+    Ok(())
+}
+
+// Function to redraw both boards for a spectator, with what the last shot did underneath.
+fn show_spectated(boards: &[Board; 2], last: Option<&Move>) {
+    clear_screen();
+    println!("{}", "Spectating".styled(Style::Emphasis));
+    show_boards(["Host's Board:", "Guest's Board:"], &boards[0], &boards[1]);
+    if let Some(shot) = last {
+        let name = match shot.side {
+            Side::Player => "The host",
+            Side::Opponent => "The guest",
+        };
+        println!("{} fired {}", name, describe_move(shot));
+    }
+}
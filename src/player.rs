@@ -0,0 +1,113 @@
+use crate::ai::{torpedo_lane, OpponentAi};
+use crate::board::{Board, BoardView, ShotOutcome};
+use crate::cursor::{cursor_targeting, pick_target};
+use crate::fleet::FleetConfig;
+use crate::ui::{get_player_input, place_player_fleet, time_limit, Arsenal, PlayerAction};
+use rand::rngs::StdRng;
+use std::io::{self, IsTerminal};
+
+// One side of a game: something that places a fleet and then picks where to fire each turn. The game loop
+// only talks to players through this trait, so a person at the keyboard and a computer opponent (or any
+// smarter one added later) are interchangeable. Players only ever see the board they fire at through a
+// `BoardView`, which hides the ships.
+pub trait Player {
+    // Whether a person is playing this side, so the game shows them the boards and hands over the keyboard.
+    fn is_human(&self) -> bool;
+
+    // Method to place the whole fleet on the player's own board.
+    fn place_ships(
+        &mut self,
+        board: &mut Board,
+        fleet: &FleetConfig,
+        rng: &mut StdRng,
+    ) -> io::Result<()>;
+
+    // Method to decide what to do with up to `shots` shots at the board in the view, or whether to use one of
+    // the special weapons left in its arsenal instead. Returning fewer targets than `shots` is fine; the game asks
+    // again for the rest once they have been fired.
+    fn choose_shots(
+        &mut self,
+        view: &BoardView,
+        shots: usize,
+        arsenal: Arsenal,
+    ) -> io::Result<PlayerAction>;
+
+    // Method to tell the player how their shot at `target` went. The message a person reads comes from the
+    // game's ShotFired event instead.
+    fn report_shot(&mut self, target: (usize, usize), outcome: &ShotOutcome);
+}
+
+// A person playing at the keyboard.
+#[derive(Default)]
+pub struct HumanPlayer;
+
+impl Player for HumanPlayer {
+    fn is_human(&self) -> bool {
+        true
+    }
+
+    fn place_ships(
+        &mut self,
+        board: &mut Board,
+        fleet: &FleetConfig,
+        rng: &mut StdRng,
+    ) -> io::Result<()> {
+        place_player_fleet(board, fleet, rng)
+    }
+
+    // With cursor targeting on, one shot is aimed at a time on the board, unless the player presses Esc to
+    // type instead (and always when input is piped in or turns are timed).
+    fn choose_shots(
+        &mut self,
+        view: &BoardView,
+        shots: usize,
+        arsenal: Arsenal,
+    ) -> io::Result<PlayerAction> {
+        if cursor_targeting() && time_limit().is_none() && io::stdin().is_terminal() {
+            if let Some(target) = pick_target(view)? {
+                return Ok(PlayerAction::Fire(vec![target]));
+            }
+        }
+        get_player_input(view.size(), shots, arsenal)
+    }
+
+    fn report_shot(&mut self, _target: (usize, usize), _outcome: &ShotOutcome) {}
+}
+
+// The computer opponent fires one shot at a time, so in salvo mode it sees the result of each shot before
+// choosing the next.
+impl Player for OpponentAi {
+    fn is_human(&self) -> bool {
+        false
+    }
+
+    fn place_ships(
+        &mut self,
+        board: &mut Board,
+        fleet: &FleetConfig,
+        rng: &mut StdRng,
+    ) -> io::Result<()> {
+        board
+            .place_fleet(fleet, rng)
+            .map_err(|error| io::Error::other(error.to_string()))
+    }
+
+    fn choose_shots(
+        &mut self,
+        view: &BoardView,
+        _shots: usize,
+        arsenal: Arsenal,
+    ) -> io::Result<PlayerAction> {
+        // The computer never scans or sweeps, but spends its torpedoes when `torpedo_lane` finds a lane worth it
+        if arsenal.torpedoes > 0 {
+            if let Some(lane) = torpedo_lane(view) {
+                return Ok(PlayerAction::Torpedo(lane));
+            }
+        }
+        Ok(PlayerAction::Fire(vec![self.choose_target(view)]))
+    }
+
+    fn report_shot(&mut self, target: (usize, usize), outcome: &ShotOutcome) {
+        self.notify(target, outcome);
+    }
+}
@@ -0,0 +1,271 @@
+use crate::board::{is_valid_coord, Board, Orientation, ShotOutcome, TorpedoOutcome};
+use crate::game::{GameState, Move, Side};
+use crate::style::{clear_screen, Style, Styled};
+use crate::ui::{
+    coordinate_label, describe_move, describe_outcome, reveal_boards, wait_to_continue,
+};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::fs;
+use std::io;
+use std::time::Duration;
+
+// The replay format written by this version of the game. Bump it whenever the format changes in a way older
+// versions can't read, and keep new fields optional so older replays still load.
+pub const REPLAY_VERSION: u32 = 2;
+
+// Default file a replay is written to at the end of a game, or by the record command when no path is given.
+pub const REPLAY_FILE: &str = "battleship_replay.json";
+
+// Where a ship was placed at the start of the game.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Placement {
+    pub name: String,
+    pub size: usize,
+    pub row: usize,
+    pub col: usize,
+    pub orientation: Orientation,
+}
+
+// Everything needed to watch a finished game again: the board size and rules, which cells of each board are
+// land, where both sides placed their ships, and every shot in order. The boards are rebuilt from these alone, so playing a replay back also
+// checks that the recorded outcomes still come out the same.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Replay {
+    pub version: u32,
+    pub size: usize,
+    #[serde(default)]
+    pub no_touching: bool,
+    #[serde(default)]
+    pub diagonal: bool,
+    #[serde(default)]
+    pub hotseat: bool,
+    #[serde(default)]
+    pub player_land: Vec<(usize, usize)>,
+    #[serde(default)]
+    pub opponent_land: Vec<(usize, usize)>,
+    pub player_fleet: Vec<Placement>,
+    pub opponent_fleet: Vec<Placement>,
+    pub moves: Vec<Move>,
+}
+
+// Reasons a replay can't be written, read or played back.
+#[derive(Debug)]
+pub enum ReplayError {
+    Io(io::Error),
+    Json(serde_json::Error),
+    UnsupportedVersion(u32),
+    Inconsistent(String),
+}
+
+impl fmt::Display for ReplayError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ReplayError::Io(error) => write!(f, "couldn't access the replay file: {}", error),
+            ReplayError::Json(error) => {
+                write!(f, "the replay file is not a valid replay: {}", error)
+            }
+            ReplayError::UnsupportedVersion(version) => write!(
+                f,
+                "the replay uses format version {}, but only versions up to {} can be read",
+                version, REPLAY_VERSION
+            ),
+            ReplayError::Inconsistent(reason) => {
+                write!(f, "the replay doesn't add up: {}", reason)
+            }
+        }
+    }
+}
+
+impl From<io::Error> for ReplayError {
+    fn from(error: io::Error) -> Self {
+        ReplayError::Io(error)
+    }
+}
+
+impl From<serde_json::Error> for ReplayError {
+    fn from(error: serde_json::Error) -> Self {
+        ReplayError::Json(error)
+    }
+}
+
+impl Replay {
+    // Constructor for a replay of a game so far, taken from its ship placements and shot history.
+    pub fn from_game(game: &GameState) -> Self {
+        Replay {
+            version: REPLAY_VERSION,
+            size: game.player_board().size(),
+            no_touching: game.player_board().no_touching(),
+            diagonal: game.player_board().diagonal(),
+            hotseat: game.is_hotseat(),
+            player_land: game.player_board().land(),
+            opponent_land: game.opponent_board().land(),
+            player_fleet: placements(game.player_board()),
+            opponent_fleet: placements(game.opponent_board()),
+            moves: game.history().to_vec(),
+        }
+    }
+
+    // Method to rebuild both boards as they were before the first shot, failing if a placement is invalid.
+    pub fn boards(&self) -> Result<(Board, Board), ReplayError> {
+        Ok((
+            self.build_board(&self.player_land, &self.player_fleet, "player")?,
+            self.build_board(&self.opponent_land, &self.opponent_fleet, "opponent")?,
+        ))
+    }
+
+    // Method to replay every shot without drawing anything, returning the final boards. Fails at the first
+    // shot that is off the board or whose outcome differs from the one recorded.
+    pub fn check(&self) -> Result<(Board, Board), ReplayError> {
+        let (mut player_board, mut opponent_board) = self.boards()?;
+        for (index, shot) in self.moves.iter().enumerate() {
+            apply_move(index, shot, &mut player_board, &mut opponent_board)?;
+        }
+        Ok((player_board, opponent_board))
+    }
+
+    // Method to step through the game one shot at a time, redrawing both boards with every ship showing and
+    // waiting for Enter (or the given pause) after each one.
+    pub fn play_back(&self, auto_advance: Option<Duration>) -> Result<(), ReplayError> {
+        let (mut player_board, mut opponent_board) = self.boards()?;
+        let names = if self.hotseat {
+            ["Player 1", "Player 2"]
+        } else {
+            ["You", "Opponent"]
+        };
+        for (index, shot) in self.moves.iter().enumerate() {
+            apply_move(index, shot, &mut player_board, &mut opponent_board)?;
+            let name = match shot.side {
+                Side::Player => names[0],
+                Side::Opponent => names[1],
+            };
+            clear_screen();
+            println!(
+                "{}",
+                format!(
+                    "Replay: turn {}, shot {} of {}",
+                    shot.turn,
+                    index + 1,
+                    self.moves.len()
+                )
+                .styled(Style::Emphasis)
+            );
+            reveal_boards(&player_board, &opponent_board);
+            println!("{} fired {}", name, describe_move(shot));
+            wait_to_continue(auto_advance)?;
+        }
+        Ok(())
+    }
+
+    // Helper method to build one side's board from its recorded land and placements.
+    fn build_board(
+        &self,
+        land: &[(usize, usize)],
+        fleet: &[Placement],
+        side: &str,
+    ) -> Result<Board, ReplayError> {
+        let mut board = Board::new(self.size);
+        board.set_no_touching(self.no_touching);
+        board.set_diagonal(self.diagonal);
+        for &(row, col) in land {
+            board.raise_land(row, col);
+        }
+        for ship in fleet {
+            board
+                .place_ship_at(&ship.name, ship.row, ship.col, ship.size, ship.orientation)
+                .map_err(|error| {
+                    ReplayError::Inconsistent(format!(
+                        "the {} {} can't be placed at {}: {}",
+                        side,
+                        ship.name,
+                        coordinate_label(ship.row, ship.col),
+                        error
+                    ))
+                })?;
+        }
+        Ok(board)
+    }
+}
+
+// Function to list where each of a board's ships was placed.
+pub fn placements(board: &Board) -> Vec<Placement> {
+    board
+        .ships()
+        .iter()
+        .map(|ship| Placement {
+            name: ship.name.clone(),
+            size: ship.size,
+            row: ship.origin.0,
+            col: ship.origin.1,
+            orientation: ship.orientation,
+        })
+        .collect()
+}
+
+// Function to fire one recorded shot (or torpedo) at the board it was aimed at and check that it does what was
+// recorded.
+fn apply_move(
+    index: usize,
+    shot: &Move,
+    player_board: &mut Board,
+    opponent_board: &mut Board,
+) -> Result<(), ReplayError> {
+    let target = match shot.side {
+        Side::Player => opponent_board,
+        Side::Opponent => player_board,
+    };
+    let lane_off_board = shot
+        .torpedo
+        .is_some_and(|lane| !lane.is_on_board(target.size()));
+    if lane_off_board || !is_valid_coord(shot.row, shot.col, target.size()) {
+        return Err(ReplayError::Inconsistent(format!(
+            "shot {} is off the board",
+            index + 1
+        )));
+    }
+    let outcome = match shot.torpedo {
+        Some(lane) => match target.fire_torpedo(lane) {
+            TorpedoOutcome::Struck { row, col, outcome } if (row, col) == (shot.row, shot.col) => {
+                outcome
+            }
+            TorpedoOutcome::Struck { row, col, .. } => {
+                return Err(ReplayError::Inconsistent(format!(
+                    "the torpedo in shot {} was recorded at {} but now strikes {}",
+                    index + 1,
+                    coordinate_label(shot.row, shot.col),
+                    coordinate_label(row, col)
+                )))
+            }
+            TorpedoOutcome::Missed => ShotOutcome::Miss,
+        },
+        None => target.fire(shot.row, shot.col),
+    };
+    if outcome != shot.outcome {
+        return Err(ReplayError::Inconsistent(format!(
+            "shot {} at {} was recorded as \"{}\" but now comes out as \"{}\"",
+            index + 1,
+            coordinate_label(shot.row, shot.col),
+            describe_outcome(&shot.outcome),
+            describe_outcome(&outcome)
+        )));
+    }
+    Ok(())
+}
+
+// Function to write a replay to a JSON file.
+pub fn save_replay(path: &str, replay: &Replay) -> Result<(), ReplayError> {
+    let json = serde_json::to_string_pretty(replay)?;
+    fs::write(path, json)?;
+    Ok(())
+}
+
+// Function to read a replay back from a JSON file written by `save_replay`, rejecting replays from a newer
+// version of the format.
+pub fn load_replay(path: &str) -> Result<Replay, ReplayError> {
+    let json = fs::read_to_string(path)?;
+    let replay: Replay = serde_json::from_str(&json)?;
+    if replay.version > REPLAY_VERSION {
+        return Err(ReplayError::UnsupportedVersion(replay.version));
+    }
+    Ok(replay)
+}
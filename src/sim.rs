@@ -0,0 +1,190 @@
+use crate::ai::{
+    DensityStrategy, Difficulty, HuntTargetStrategy, MonteCarloStrategy, RandomStrategy, Strategy,
+};
+use crate::board::{Board, BoardView, PlacementError, ShotOutcome};
+use crate::game::{GameSettings, Side};
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+
+// How many shots each bar of the shot-count distribution covers.
+const BUCKET_SIZE: usize = 10;
+
+// How one headless game ended: who won, how many shots each side fired and how many turns it took.
+#[derive(Clone, Debug, PartialEq)]
+pub struct GameResult {
+    pub winner: Side,
+    pub shots: [usize; 2],
+    pub turns: usize,
+}
+
+// Totals over a batch of headless games: wins per side, and how many shots each win took, both summed and
+// counted in buckets of `BUCKET_SIZE` shots.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct SimulationReport {
+    pub games: usize,
+    pub wins: [usize; 2],
+    pub winning_shots: [usize; 2],
+    pub distribution: Vec<usize>,
+}
+
+impl SimulationReport {
+    // Method to add one game's result to the totals.
+    pub fn record(&mut self, result: &GameResult) {
+        let winner = result.winner as usize;
+        let shots = result.shots[winner];
+        self.games += 1;
+        self.wins[winner] += 1;
+        self.winning_shots[winner] += shots;
+        let bucket = shots / BUCKET_SIZE;
+        if self.distribution.len() <= bucket {
+            self.distribution.resize(bucket + 1, 0);
+        }
+        self.distribution[bucket] += 1;
+    }
+
+    // Method to work out the percentage of games a side won, or 0 before any games.
+    pub fn win_rate(&self, side: Side) -> f64 {
+        if self.games == 0 {
+            0.0
+        } else {
+            self.wins[side as usize] as f64 / self.games as f64 * 100.0
+        }
+    }
+
+    // Method to work out how many shots a side needed on average in the games it won, or 0 if it won none.
+    pub fn average_shots_to_win(&self, side: Side) -> f64 {
+        let side = side as usize;
+        if self.wins[side] == 0 {
+            0.0
+        } else {
+            self.winning_shots[side] as f64 / self.wins[side] as f64
+        }
+    }
+
+    // Method to print the win rates, the average shots to win and a bar per bucket of the shot-count
+    // distribution, scaled so the tallest bar is 40 characters wide.
+    pub fn print(&self, names: [&str; 2]) {
+        println!("Games played: {}", self.games);
+        for (side, name) in [Side::Player, Side::Opponent].into_iter().zip(names) {
+            println!(
+                "{:<8} won {:5.1}% of games, averaging {:.1} shots per win",
+                name,
+                self.win_rate(side),
+                self.average_shots_to_win(side)
+            );
+        }
+        println!("Shots needed by the winner:");
+        let tallest = self.distribution.iter().copied().max().unwrap_or(0).max(1);
+        for (bucket, &count) in self.distribution.iter().enumerate() {
+            if count == 0 {
+                continue;
+            }
+            println!(
+                "  {:3}-{:<3} {:6} {}",
+                bucket * BUCKET_SIZE,
+                bucket * BUCKET_SIZE + BUCKET_SIZE - 1,
+                count,
+                "#".repeat((count * 40).div_ceil(tallest))
+            );
+        }
+    }
+}
+
+// Function to build a fresh strategy for a difficulty, with nothing learned yet.
+fn new_strategy(difficulty: Difficulty) -> Box<dyn Strategy> {
+    match difficulty {
+        Difficulty::Easy => Box::new(RandomStrategy),
+        Difficulty::Normal => Box::new(HuntTargetStrategy::default()),
+        Difficulty::Hard => Box::new(DensityStrategy),
+        Difficulty::Expert => Box::new(MonteCarloStrategy),
+    }
+}
+
+// Function to play one game between two strategies with nothing drawn and no input read. Both fleets are placed
+// at random (spread out if the settings ask) with the settings' board size, fleet, land and rules, and the first
+// strategy fires first. In salvo mode each side fires one shot per ship it has afloat, and under the hit-again
+// rule every hit earns another shot. Torpedoes aren't used. Fails if a fleet can't be fitted onto the board.
+pub fn run_game(
+    first: &mut dyn Strategy,
+    second: &mut dyn Strategy,
+    settings: &GameSettings,
+    rng: &mut StdRng,
+) -> Result<GameResult, PlacementError> {
+    let mut boards = [Board::new(settings.size), Board::new(settings.size)];
+    for board in &mut boards {
+        board.set_no_touching(settings.no_touching);
+        board.set_spread(settings.spread);
+        board.set_diagonal(settings.diagonal);
+        board.raise_islands(settings.land, &settings.fleet, rng);
+        board.place_fleet(&settings.fleet, rng)?;
+    }
+    let mut shots = [0, 0];
+    let mut turns = 0;
+    let mut current = 0;
+    loop {
+        if current == 0 {
+            turns += 1;
+        }
+        let mut allowed = if settings.salvo {
+            boards[current].ships_afloat()
+        } else {
+            1
+        };
+        let strategy: &mut dyn Strategy = if current == 0 {
+            &mut *first
+        } else {
+            &mut *second
+        };
+        let target = &mut boards[1 - current];
+        let mut fired = 0;
+        while fired < allowed {
+            let (row, col) = strategy.choose_target(&BoardView::new(target), rng);
+            let outcome = target.fire(row, col);
+            strategy.notify((row, col), &outcome);
+            fired += 1;
+            if settings.hit_again && matches!(outcome, ShotOutcome::Hit | ShotOutcome::Sunk(_)) {
+                allowed += 1;
+            }
+            shots[current] += 1;
+            if target.is_game_over() {
+                let winner = if current == 0 {
+                    Side::Player
+                } else {
+                    Side::Opponent
+                };
+                return Ok(GameResult {
+                    winner,
+                    shots,
+                    turns,
+                });
+            }
+        }
+        current = 1 - current;
+    }
+}
+
+// Function to play a batch of headless games between the strategies for two difficulties and total up the
+// results. Every game starts both strategies afresh, and one seed (from the settings, or picked at random)
+// drives all the placements and shots, so the same seed gives the same report.
+pub fn simulate(
+    players: [Difficulty; 2],
+    games: usize,
+    settings: &GameSettings,
+) -> Result<SimulationReport, PlacementError> {
+    let mut rng = match settings.seed {
+        Some(seed) => StdRng::seed_from_u64(seed),
+        None => StdRng::from_entropy(),
+    };
+    let mut report = SimulationReport::default();
+    for _ in 0..games {
+        let mut first = new_strategy(players[0]);
+        let mut second = new_strategy(players[1]);
+        report.record(&run_game(
+            first.as_mut(),
+            second.as_mut(),
+            settings,
+            &mut rng,
+        )?);
+    }
+    Ok(report)
+}
@@ -0,0 +1,68 @@
+use colored::{control, ColoredString, Colorize};
+
+// The kinds of styled text the game prints. Every colored print goes through one of these, so styling is
+// switched on or off in one place.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Style {
+    Emphasis,
+    Warning,
+    Success,
+    Highlight,
+    Info,
+    Hit,
+    Miss,
+    Sunk,
+    Wreck,
+    Latest,
+    Revealed,
+    Land,
+}
+
+// Trait for applying a `Style` to text.
+pub trait Styled {
+    fn styled(&self, style: Style) -> ColoredString;
+}
+
+impl Styled for str {
+    fn styled(&self, style: Style) -> ColoredString {
+        match style {
+            Style::Emphasis => self.bold(),
+            Style::Warning => self.bold().red(),
+            Style::Success => self.bold().green(),
+            Style::Highlight => self.bold().yellow(),
+            Style::Info => self.green(),
+            Style::Hit => self.red(),
+            Style::Miss => self.cyan(),
+            Style::Sunk => self.strikethrough().red(),
+            Style::Wreck => self.red().reversed(),
+            Style::Latest => self.black().on_yellow(),
+            Style::Revealed => self.magenta(),
+            Style::Land => self.yellow(),
+        }
+    }
+}
+
+// Function to turn colors and other terminal escapes off (or back on). Without a call to this they are on only
+// when stdout is a terminal and the NO_COLOR environment variable isn't set.
+pub fn set_enabled(enabled: bool) {
+    control::set_override(enabled);
+}
+
+// Function to check whether colors and terminal escapes are being written.
+pub fn enabled() -> bool {
+    control::SHOULD_COLORIZE.should_colorize()
+}
+
+// Function to clear the terminal and move the cursor to the top left, when escapes are enabled.
+pub fn clear_screen() {
+    if enabled() {
+        print!("\x1b[2J\x1b[1;1H");
+    }
+}
+
+// Function to reset the terminal's colors, when escapes are enabled.
+pub fn reset_colors() {
+    if enabled() {
+        print!("\x1b[0m");
+    }
+}
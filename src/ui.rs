@@ -0,0 +1,1148 @@
+use crate::board::{
+    is_valid_coord, Board, BoardView, CellState, Lane, Orientation, ShotOutcome, Stats,
+};
+use crate::fleet::FleetConfig;
+use crate::game::{Move, Summary};
+use crate::replay::REPLAY_FILE;
+use crate::style::{self, clear_screen, Style, Styled};
+use rand::Rng;
+use std::env;
+use std::fmt;
+use std::io::{self, BufRead, Write};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError};
+use std::sync::{Mutex, OnceLock};
+use std::thread;
+use std::time::{Duration, Instant};
+
+// Reasons a typed coordinate can't be turned into a cell on the board. Off-board rows and columns keep the
+// text that was typed, so even numbers too big to store can be named.
+#[derive(Clone, Debug, PartialEq)]
+pub enum ParseError {
+    InvalidFormat,
+    WrongCount(usize),
+    NotANumber(String),
+    RowOffBoard(String),
+    ColumnOffBoard(String),
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ParseError::InvalidFormat => write!(f, "unrecognised coordinate"),
+            ParseError::WrongCount(0) => write!(f, "no coordinate was entered"),
+            ParseError::WrongCount(1) => {
+                write!(
+                    f,
+                    "only one value was entered, but a coordinate needs a row and a column"
+                )
+            }
+            ParseError::WrongCount(count) => write!(
+                f,
+                "{} values were entered, but a coordinate is just a row and a column",
+                count
+            ),
+            ParseError::NotANumber(text) => write!(f, "'{}' isn't a row or column number", text),
+            ParseError::RowOffBoard(row) => write!(f, "row {} is off the board", row),
+            ParseError::ColumnOffBoard(col) => write!(f, "column {} is off the board", col),
+        }
+    }
+}
+
+// Function to parse either "row, col" or A1-style input (column letter followed by row number) into (row, col).
+// Empty values, such as after a trailing comma, are ignored.
+pub fn parse_coordinates(input: &str, board_size: usize) -> Result<(usize, usize), ParseError> {
+    let input = input.trim();
+    if input.contains(',') {
+        let values: Vec<&str> = input
+            .split(',')
+            .map(str::trim)
+            .filter(|value| !value.is_empty())
+            .collect();
+        if values.len() != 2 {
+            return Err(ParseError::WrongCount(values.len()));
+        }
+        let row = parse_index(values[0], board_size, ParseError::RowOffBoard)?;
+        let col = parse_index(values[1], board_size, ParseError::ColumnOffBoard)?;
+        return Ok((row, col));
+    }
+
+    let Some(letter) = input.chars().next() else {
+        return Err(ParseError::WrongCount(0));
+    };
+    if !letter.is_ascii_alphabetic() {
+        // A lone number is half a coordinate; anything else isn't one at all
+        return if input.chars().all(|c| c.is_ascii_digit()) {
+            Err(ParseError::WrongCount(1))
+        } else {
+            Err(ParseError::InvalidFormat)
+        };
+    }
+    let letter = letter.to_ascii_uppercase();
+    let row = input[1..].trim();
+    if row.is_empty() {
+        return Err(ParseError::WrongCount(1));
+    }
+    let col = (letter as u8 - b'A') as usize;
+    if col >= board_size {
+        return Err(ParseError::ColumnOffBoard(letter.to_string()));
+    }
+    Ok((parse_index(row, board_size, ParseError::RowOffBoard)?, col))
+}
+
+// Helper function to read a row or column number. Text that isn't a whole number fails as not a number, and
+// numbers past the edge of the board (including ones too big to store) with the given off-board error.
+fn parse_index(
+    text: &str,
+    board_size: usize,
+    off_board: fn(String) -> ParseError,
+) -> Result<usize, ParseError> {
+    if !text.chars().all(|c| c.is_ascii_digit()) {
+        return Err(ParseError::NotANumber(text.to_string()));
+    }
+    match text.parse::<usize>() {
+        Ok(index) if is_valid_coord(index, 0, board_size) => Ok(index),
+        _ => Err(off_board(text.to_string())),
+    }
+}
+
+// Function to parse a salvo of several coordinates, separated by semicolons (e.g. "3, 7; C4") or, when
+// every coordinate uses letters, by spaces (e.g. "A1 B2 C3").
+pub fn parse_salvo(input: &str, board_size: usize) -> Result<Vec<(usize, usize)>, ParseError> {
+    let parts: Vec<&str> = if input.contains(';') || input.contains(',') {
+        input.split(';').collect()
+    } else {
+        input.split_whitespace().collect()
+    };
+    parts
+        .into_iter()
+        .filter(|part| !part.trim().is_empty())
+        .map(|part| parse_coordinates(part, board_size))
+        .collect()
+}
+
+// Why a salvo can't be fired as entered: the cells it names more than once, and the cells in it that were
+// already fired at, each listed once in the order they were typed.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SalvoError {
+    pub duplicates: Vec<(usize, usize)>,
+    pub already_fired: Vec<(usize, usize)>,
+}
+
+impl fmt::Display for SalvoError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let list = |cells: &[(usize, usize)]| {
+            let labels: Vec<String> = cells
+                .iter()
+                .map(|&(row, col)| coordinate_label(row, col))
+                .collect();
+            labels.join(", ")
+        };
+        let mut problems = Vec::new();
+        if !self.duplicates.is_empty() {
+            problems.push(format!("listed more than once: {}", list(&self.duplicates)));
+        }
+        if !self.already_fired.is_empty() {
+            problems.push(format!("already fired at: {}", list(&self.already_fired)));
+        }
+        write!(f, "{}", problems.join("; "))
+    }
+}
+
+// Function to check a whole salvo before any of it is fired, so it either goes off in full or not at all.
+// Every cell must be named once and must not have been fired at already.
+pub fn check_salvo(targets: &[(usize, usize)], view: &BoardView) -> Result<(), SalvoError> {
+    let mut error = SalvoError {
+        duplicates: Vec::new(),
+        already_fired: Vec::new(),
+    };
+    for (i, &(row, col)) in targets.iter().enumerate() {
+        if targets[..i].contains(&(row, col)) && !error.duplicates.contains(&(row, col)) {
+            error.duplicates.push((row, col));
+        }
+        if view.has_been_fired_at(row, col) && !error.already_fired.contains(&(row, col)) {
+            error.already_fired.push((row, col));
+        }
+    }
+    if error.duplicates.is_empty() && error.already_fired.is_empty() {
+        Ok(())
+    } else {
+        Err(error)
+    }
+}
+
+// Function to format a cell as letter + row, matching the labels on the board (e.g. (4, 2) is "C4").
+pub fn coordinate_label(row: usize, col: usize) -> String {
+    format!("{}{}", (b'A' + col as u8) as char, row)
+}
+
+// The symbol drawn for each kind of cell. Every glyph is one column wide, so both sets line up the same way.
+#[derive(PartialEq)]
+pub struct CellGlyphs {
+    pub water: &'static str,
+    pub ship: &'static str,
+    pub hit: &'static str,
+    pub sunk: &'static str,
+    pub miss: &'static str,
+    pub land: &'static str,
+}
+
+// Glyphs for terminals that can show Unicode.
+pub const UNICODE_GLYPHS: CellGlyphs = CellGlyphs {
+    water: "□",
+    ship: "■",
+    hit: "●",
+    sunk: "✖",
+    miss: "·",
+    land: "▲",
+};
+
+// Plain ASCII glyphs for terminals that can't.
+pub const ASCII_GLYPHS: CellGlyphs = CellGlyphs {
+    water: "~",
+    ship: "#",
+    hit: "X",
+    sunk: "*",
+    miss: "o",
+    land: "^",
+};
+
+// Whether boards are drawn with `ASCII_GLYPHS`, chosen once at startup.
+static ASCII: AtomicBool = AtomicBool::new(false);
+
+// Function to switch the board display between ASCII and Unicode glyphs.
+pub fn set_ascii(enabled: bool) {
+    ASCII.store(enabled, Ordering::Relaxed);
+}
+
+// Whether the debugging commands, such as reveal, are accepted at the firing prompt. Off unless `--debug` is given.
+static DEBUG: AtomicBool = AtomicBool::new(false);
+
+// Function to turn the debugging commands on or off.
+pub fn set_debug(enabled: bool) {
+    DEBUG.store(enabled, Ordering::Relaxed);
+}
+
+// How many seconds a person has to answer the firing prompt, or 0 for no limit. Off unless `--time-limit` is
+// given.
+static TIME_LIMIT: AtomicU64 = AtomicU64::new(0);
+
+// Function to set how many seconds a person has to answer the firing prompt, with None for no limit.
+pub fn set_time_limit(seconds: Option<u64>) {
+    TIME_LIMIT.store(seconds.unwrap_or(0), Ordering::Relaxed);
+}
+
+// Function to check how long a person has to answer the firing prompt, if there's a limit at all.
+pub fn time_limit() -> Option<Duration> {
+    match TIME_LIMIT.load(Ordering::Relaxed) {
+        0 => None,
+        seconds => Some(Duration::from_secs(seconds)),
+    }
+}
+
+// Function to guess whether the terminal can show Unicode from the locale, the same way C programs do: the first
+// of LC_ALL, LC_CTYPE and LANG that is set must name a UTF-8 encoding. With none of them set the locale is
+// unknown, so Unicode is assumed.
+pub fn locale_is_utf8() -> bool {
+    ["LC_ALL", "LC_CTYPE", "LANG"]
+        .iter()
+        .filter_map(|name| env::var(name).ok())
+        .find(|value| !value.is_empty())
+        .is_none_or(|value| {
+            let value = value.to_lowercase();
+            value.contains("utf-8") || value.contains("utf8")
+        })
+}
+
+// Function to get the glyph set chosen at startup.
+pub fn glyphs() -> &'static CellGlyphs {
+    if ASCII.load(Ordering::Relaxed) {
+        &ASCII_GLYPHS
+    } else {
+        &UNICODE_GLYPHS
+    }
+}
+
+// How to draw a board: whether its ships are hidden (for the opponent's view), whether to color it (colors
+// still only show while styling is enabled), and which glyphs to draw the cells with.
+#[derive(Clone, Copy)]
+pub struct RenderOptions {
+    pub hide_ships: bool,
+    pub color: bool,
+    pub glyphs: &'static CellGlyphs,
+}
+
+impl RenderOptions {
+    // Constructor for drawing a board the way the game was started: in color unless styling is off, and with
+    // the glyph set chosen at startup.
+    pub fn current(hide_ships: bool) -> Self {
+        RenderOptions {
+            hide_ships,
+            color: style::enabled(),
+            glyphs: glyphs(),
+        }
+    }
+}
+
+// Function to render a game board as text, one line per row under a line of column letters, each ending in a
+// newline.
+pub fn render(board: &Board, options: &RenderOptions) -> String {
+    render_rows(board, options)
+        .into_iter()
+        .map(|row| row + "\n")
+        .collect()
+}
+
+// Function to render a game board as lines of text, without line endings.
+fn render_rows(board: &Board, options: &RenderOptions) -> Vec<String> {
+    let hide_ships = options.hide_ships;
+    let glyphs = options.glyphs;
+    let mut rows = Vec::with_capacity(board.size() + 1);
+    let mut header = String::from("   ");
+    for i in 0..board.size() {
+        header.push_str(&format!(" {} ", (b'A' + i as u8) as char));
+    }
+    rows.push(header);
+    for i in 0..board.size() {
+        let mut line = format!("{:2} ", i);
+        for j in 0..board.size() {
+            let latest = board.last_shot() == Some((i, j));
+            // Cells a scan has revealed show what's in them even on a hidden board, set apart in their own style
+            let revealed = hide_ships && board.is_revealed(i, j);
+            match board.cell(i, j) {
+                CellState::Empty if revealed => line.push_str(&format!(
+                    " {} ",
+                    paint(glyphs.water, Style::Revealed, options)
+                )),
+                CellState::Ship if revealed => line.push_str(&format!(
+                    " {} ",
+                    paint(glyphs.ship, Style::Revealed, options)
+                )),
+                CellState::Empty => {
+                    if hide_ships {
+                        line.push_str("   ");
+                    } else {
+                        line.push_str(&format!(" {} ", glyphs.water));
+                    }
+                }
+                CellState::Ship => {
+                    if hide_ships {
+                        line.push_str("   ");
+                    } else {
+                        line.push_str(&format!(" {} ", glyphs.ship));
+                    }
+                }
+                // Hits on a ship that has gone down are drawn as wreckage, apart from ships still afloat
+                CellState::Hit if board.check_sunk(i, j).is_some() => {
+                    line.push_str(&render_shot(glyphs.sunk, Style::Wreck, latest, options))
+                }
+                CellState::Hit => {
+                    line.push_str(&render_shot(glyphs.hit, Style::Hit, latest, options))
+                }
+                CellState::Miss => {
+                    line.push_str(&render_shot(glyphs.miss, Style::Miss, latest, options))
+                }
+                // Land is known to both sides, so it shows on hidden boards too
+                CellState::Land => {
+                    line.push_str(&format!(" {} ", paint(glyphs.land, Style::Land, options)))
+                }
+            }
+        }
+        rows.push(line);
+    }
+    rows
+}
+
+// Function to render a cell that was fired at, picking out the latest shot at the board with a highlight, or
+// with brackets around it when the board is drawn in ASCII or without colors.
+fn render_shot(glyph: &str, kind: Style, latest: bool, options: &RenderOptions) -> String {
+    if !latest {
+        format!(" {} ", paint(glyph, kind, options))
+    } else if *options.glyphs == ASCII_GLYPHS || !options.color {
+        format!("[{}]", paint(glyph, kind, options))
+    } else {
+        format!(" {} ", paint(glyph, Style::Latest, options))
+    }
+}
+
+// Function to style a glyph on a board, or leave it plain when the board is drawn without colors.
+fn paint(glyph: &str, kind: Style, options: &RenderOptions) -> String {
+    if options.color {
+        glyph.styled(kind).to_string()
+    } else {
+        glyph.to_string()
+    }
+}
+
+// Function to print a key to the board symbols, matching the ones `render_rows` draws.
+pub fn print_legend() {
+    let glyphs = glyphs();
+    println!("{}", "Legend:".styled(Style::Emphasis));
+    println!("  {}  Water", glyphs.water);
+    println!("  {}  Your ship", glyphs.ship);
+    println!("  {}  Hit", glyphs.hit.styled(Style::Hit));
+    println!("  {}  Sunk ship", glyphs.sunk.styled(Style::Wreck));
+    println!("  {}  Miss", glyphs.miss.styled(Style::Miss));
+    println!("  {}  Land", glyphs.land.styled(Style::Land));
+    println!(
+        "  {}  Revealed by a scan, water or ship (opponent's board)",
+        glyphs.ship.styled(Style::Revealed)
+    );
+    println!("     Not fired at yet (opponent's board)");
+}
+
+// Function to render the fleet status panel shown under a board: one line per ship with its size and whether
+// it is still afloat, padded to `width` columns. Only names, sizes and sinks are shown, so the panel never
+// gives away where the opponent's remaining ships are.
+fn render_fleet_status(board: &Board, width: usize) -> Vec<String> {
+    const STATUS_COLUMN: usize = 16;
+    board
+        .ships()
+        .iter()
+        .map(|ship| {
+            let label = format!("{} ({})", ship.name, ship.size);
+            let gap = " ".repeat(STATUS_COLUMN.saturating_sub(label.len()).max(1));
+            let status = if ship.is_sunk() { "sunk" } else { "afloat" };
+            let padding = " ".repeat(width.saturating_sub(label.len() + gap.len() + status.len()));
+            if ship.is_sunk() {
+                format!(
+                    "{}{}{}{}",
+                    label.styled(Style::Sunk),
+                    gap,
+                    status.styled(Style::Hit),
+                    padding
+                )
+            } else {
+                format!("{}{}{}{}", label, gap, status, padding)
+            }
+        })
+        .collect()
+}
+
+// Function to print a one-line key to the board symbols, shown under the boards every turn.
+fn print_legend_line() {
+    let glyphs = glyphs();
+    println!(
+        "{} water   {} ship   {} hit   {} sunk   {} miss",
+        glyphs.water,
+        glyphs.ship,
+        glyphs.hit.styled(Style::Hit),
+        glyphs.sunk.styled(Style::Wreck),
+        glyphs.miss.styled(Style::Miss)
+    );
+}
+
+// Function to work out how many terminal columns a rendered board takes up, ignoring color codes.
+fn board_width(board: &Board) -> usize {
+    3 + 3 * board.size()
+}
+
+// Function to display a game board, optionally hiding the ships (for the opponent's view).
+pub fn display(board: &Board, hide_ships: bool) {
+    print!("{}", render(board, &RenderOptions::current(hide_ships)));
+}
+
+// Function to display both boards next to each other, falling back to stacking them on narrow terminals.
+pub fn display_side_by_side(player: &Board, opponent: &Board) {
+    show_side_by_side(BOARD_TITLES, player, opponent, true);
+}
+
+// Function to display both boards next to each other with every ship showing, once the game is over.
+pub fn reveal_boards(player: &Board, opponent: &Board) {
+    show_side_by_side(BOARD_TITLES, player, opponent, false);
+}
+
+// Function to display two boards next to each other under the given titles, showing whatever ships are on them.
+pub fn show_boards(titles: [&str; 2], left: &Board, right: &Board) {
+    show_side_by_side(titles, left, right, false);
+}
+
+// Titles over the player's board and the one they're firing at.
+const BOARD_TITLES: [&str; 2] = ["Your Board:", "Opponent's Board:"];
+
+// Helper function to lay out the two boards, optionally hiding the opponent's ships.
+fn show_side_by_side(titles: [&str; 2], player: &Board, opponent: &Board, hide_opponent: bool) {
+    const GUTTER: usize = 6;
+    let width = board_width(player);
+    let terminal_width = env::var("COLUMNS")
+        .ok()
+        .and_then(|columns| columns.parse().ok())
+        .unwrap_or(80);
+    if width + GUTTER + board_width(opponent) > terminal_width {
+        println!("{}", titles[0].styled(Style::Emphasis));
+        display(player, false);
+        for line in render_fleet_status(player, 0) {
+            println!("{}", line);
+        }
+        println!("{}", titles[1].styled(Style::Emphasis));
+        display(opponent, hide_opponent);
+        for line in render_fleet_status(opponent, 0) {
+            println!("{}", line);
+        }
+        print_legend_line();
+        return;
+    }
+
+    println!(
+        "{}{}{}",
+        titles[0].styled(Style::Emphasis),
+        " ".repeat((width + GUTTER).saturating_sub(titles[0].len())),
+        titles[1].styled(Style::Emphasis)
+    );
+    // Every rendered row is exactly board_width columns wide, so a fixed gutter keeps the colored rows aligned.
+    for (left, right) in render_rows(player, &RenderOptions::current(false))
+        .into_iter()
+        .zip(render_rows(
+            opponent,
+            &RenderOptions::current(hide_opponent),
+        ))
+    {
+        println!("{}{}{}", left, " ".repeat(GUTTER), right);
+    }
+
+    // The fleet panels line up the same way, one ship per line under each board.
+    println!();
+    let left = render_fleet_status(player, width);
+    let right = render_fleet_status(opponent, 0);
+    for i in 0..left.len().max(right.len()) {
+        let blank = " ".repeat(width);
+        println!(
+            "{}{}{}",
+            left.get(i).unwrap_or(&blank),
+            " ".repeat(GUTTER),
+            right.get(i).map(String::as_str).unwrap_or("")
+        );
+    }
+    print_legend_line();
+}
+
+// Function to print the sizes of the opponent's ships that are still afloat, beneath their board.
+pub fn print_remaining_ships(opponent: &Board) {
+    let sizes: Vec<String> = opponent
+        .remaining_ships()
+        .iter()
+        .map(|size| size.to_string())
+        .collect();
+    println!(
+        "{} {}",
+        "Ships remaining:".styled(Style::Emphasis),
+        sizes.join(", ")
+    );
+}
+
+// Function to let the player position each ship of their fleet, with shortcuts for random placement.
+pub fn place_player_fleet(
+    board: &mut Board,
+    fleet: &FleetConfig,
+    rng: &mut impl Rng,
+) -> io::Result<()> {
+    let mut randomize_rest = false;
+    for (name, size) in &fleet.ships {
+        let (name, size) = (name.as_str(), *size);
+        if randomize_rest {
+            if board.place_ship(name, size, rng).is_ok() {
+                continue;
+            }
+            // Fall back to asking, so the error below gets shown and the player can pick a spot themselves
+            randomize_rest = false;
+        }
+        // Diagonal ships are only offered when the rules allow them
+        let directions = if board.diagonal() { "H/V/DR/DL" } else { "H/V" };
+        loop {
+            clear_screen();
+            println!("{}", "Your Board:".styled(Style::Emphasis));
+            display(board, false);
+            print!(
+                "{}",
+                format!(
+                    "Place your {} ({} cells) as coordinate + {} (e.g. C4 H), 'random', or 'random all': ",
+                    name, size, directions
+                )
+                .styled(Style::Emphasis)
+            );
+            io::stdout().flush()?;
+            let input = read_line()?;
+
+            let placed = match input.trim().to_lowercase().as_str() {
+                "random" | "r" => board.place_ship(name, size, rng),
+                "random all" | "all" => {
+                    randomize_rest = true;
+                    board.place_ship(name, size, rng)
+                }
+                _ => {
+                    let Some((row, col, orientation)) = parse_placement(&input, board.size())
+                    else {
+                        println!(
+                            "{}",
+                            format!(
+                                "Invalid input. Enter a coordinate followed by {}, for example C4 H.",
+                                directions
+                            )
+                            .styled(Style::Warning)
+                        );
+                        println!("Press Enter to try again...");
+                        read_line()?;
+                        continue;
+                    };
+                    board.place_ship_at(name, row, col, size, orientation)
+                }
+            };
+            match placed {
+                Ok(()) => break,
+                Err(error) => {
+                    randomize_rest = false;
+                    println!(
+                        "{}",
+                        format!("Can't place your {} there: {}.", name, error)
+                            .styled(Style::Warning)
+                    );
+                    println!("Press Enter to try again...");
+                    read_line()?;
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+// Function to parse a placement like "C4 H", "3, 7 v" or "B2 DR" into (row, col, orientation). DR runs down
+// and to the right, DL down and to the left.
+fn parse_placement(input: &str, board_size: usize) -> Option<(usize, usize, Orientation)> {
+    let (coordinates, orientation) = input.trim().rsplit_once(char::is_whitespace)?;
+    let orientation = match orientation.to_ascii_uppercase().as_str() {
+        "H" => Orientation::Horizontal,
+        "V" => Orientation::Vertical,
+        "DR" => Orientation::DiagonalDownRight,
+        "DL" => Orientation::DiagonalDownLeft,
+        _ => return None,
+    };
+    let (row, col) = parse_coordinates(coordinates, board_size).ok()?;
+    Some((row, col, orientation))
+}
+
+// Function to parse where to aim a torpedo, like "row 4", "column C" or "col c", into a lane on the board.
+pub fn parse_lane(input: &str, board_size: usize) -> Option<Lane> {
+    let mut words = input.split_whitespace();
+    let kind = words.next()?.to_lowercase();
+    let index = words.next()?;
+    if words.next().is_some() {
+        return None;
+    }
+    let lane = match kind.as_str() {
+        "row" => Lane::Row(index.parse().ok()?),
+        "column" | "col" => {
+            let mut letters = index.chars();
+            let letter = letters.next()?.to_ascii_uppercase();
+            if !letter.is_ascii_uppercase() || letters.next().is_some() {
+                return None;
+            }
+            Lane::Column((letter as u8 - b'A') as usize)
+        }
+        _ => return None,
+    };
+    lane.is_on_board(board_size).then_some(lane)
+}
+
+// Function to parse where to aim a radar sweep: the short form "R3" for row 3 or "CC" for column C, or any of
+// the forms `parse_lane` takes.
+pub fn parse_sweep(input: &str, board_size: usize) -> Option<Lane> {
+    let input = input.trim().to_ascii_uppercase();
+    let digits = |rest: &&str| !rest.is_empty() && rest.bytes().all(|b| b.is_ascii_digit());
+    let lane = if let Some(row) = input.strip_prefix('R').filter(digits) {
+        Lane::Row(row.parse().ok()?)
+    } else if let Some(col) = input.strip_prefix('C').filter(|col| col.len() == 1) {
+        let letter = col.chars().next()?;
+        if !letter.is_ascii_uppercase() {
+            return None;
+        }
+        Lane::Column((letter as u8 - b'A') as usize)
+    } else {
+        return parse_lane(&input, board_size);
+    };
+    lane.is_on_board(board_size).then_some(lane)
+}
+
+// Function to read one line of input. Running out of input (Ctrl-D, or a closed pipe) is reported as an
+// UnexpectedEof error rather than an empty line, so callers can't end up re-prompting forever.
+pub fn read_input(reader: &mut impl BufRead) -> io::Result<String> {
+    let mut input = String::new();
+    if reader.read_line(&mut input)? == 0 {
+        return Err(io::Error::new(
+            io::ErrorKind::UnexpectedEof,
+            "no more input",
+        ));
+    }
+    Ok(input)
+}
+
+// Lines of stdin read by a background thread, which is only started the first time input is read with a time
+// limit. Once it's running every line comes through it, so nothing else is left competing for stdin.
+static STDIN_LINES: OnceLock<Mutex<Receiver<io::Result<String>>>> = OnceLock::new();
+
+// Function to read one line from stdin, through the background reader if it has been started.
+fn read_line() -> io::Result<String> {
+    match STDIN_LINES.get() {
+        Some(lines) => receive_line(lines, None).map(Option::unwrap_or_default),
+        None => read_input(&mut io::stdin().lock()),
+    }
+}
+
+// Function to read one line from stdin, giving up with None once the deadline passes. The line someone
+// finishes typing too late is kept for whatever reads input next.
+pub fn read_input_until(deadline: Instant) -> io::Result<Option<String>> {
+    let lines = STDIN_LINES.get_or_init(|| {
+        let (sender, receiver) = mpsc::channel();
+        thread::spawn(move || loop {
+            let line = read_input(&mut io::stdin().lock());
+            let finished = line.is_err();
+            if sender.send(line).is_err() || finished {
+                break;
+            }
+        });
+        Mutex::new(receiver)
+    });
+    receive_line(lines, Some(deadline))
+}
+
+// Helper function to wait for the background reader's next line, until the deadline if there is one. Once the
+// reader has run out of input, every later read fails the same way.
+fn receive_line(
+    lines: &Mutex<Receiver<io::Result<String>>>,
+    deadline: Option<Instant>,
+) -> io::Result<Option<String>> {
+    let lines = lines
+        .lock()
+        .map_err(|_| io::Error::other("the input reader failed"))?;
+    let line = match deadline {
+        Some(deadline) => lines.recv_timeout(deadline.saturating_duration_since(Instant::now())),
+        None => lines.recv().map_err(|_| RecvTimeoutError::Disconnected),
+    };
+    match line {
+        Ok(line) => line.map(Some),
+        Err(RecvTimeoutError::Timeout) => Ok(None),
+        Err(RecvTimeoutError::Disconnected) => Err(io::Error::new(
+            io::ErrorKind::UnexpectedEof,
+            "no more input",
+        )),
+    }
+}
+
+// Something the player asked to do at the firing prompt.
+pub enum PlayerAction {
+    Fire(Vec<(usize, usize)>),
+    Save(String),
+    Load(String),
+    Record(String),
+    Torpedo(Lane),
+    Scan(usize, usize),
+    Sweep(Lane),
+    Undo,
+    Hint,
+    Reveal,
+    Quit,
+    Surrender,
+    TimedOut,
+}
+
+// The special weapons a player can still use at the firing prompt: torpedoes, which can only open a turn, radar
+// scans and radar sweeps.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Arsenal {
+    pub torpedoes: usize,
+    pub scans: usize,
+    pub sweeps: usize,
+}
+
+// Default file used by the save and load commands when no path is given.
+const SAVE_FILE: &str = "battleship_save.json";
+
+// Function to get player input for firing: `shots` coordinates, a torpedo, scan or sweep from the arsenal, or a
+// save/load command. Under a time limit the prompt shows the seconds left, and TimedOut comes back if nothing
+// usable is entered in time.
+pub fn get_player_input(
+    board_size: usize,
+    shots: usize,
+    arsenal: Arsenal,
+) -> io::Result<PlayerAction> {
+    // Torpedoes, scans and sweeps are only offered while there are some left
+    let mut specials = String::new();
+    if arsenal.torpedoes > 0 {
+        specials.push_str(&format!(
+            "torpedo row N or column X ({} left), ",
+            arsenal.torpedoes
+        ));
+    }
+    if arsenal.scans > 0 {
+        specials.push_str(&format!("scan C4 ({} left), ", arsenal.scans));
+    }
+    if arsenal.sweeps > 0 {
+        specials.push_str(&format!("radar R3 or CC ({} left), ", arsenal.sweeps));
+    }
+    let deadline = time_limit().map(|limit| Instant::now() + limit);
+    loop {
+        if let Some(deadline) = deadline {
+            // Rounded up, so the count never shows 0s while there's still time
+            let left = deadline.saturating_duration_since(Instant::now());
+            let seconds = left.as_secs() + u64::from(left.subsec_nanos() > 0);
+            print!(
+                "{}",
+                format!("[{}s left] ", seconds).styled(Style::Highlight)
+            );
+        }
+        if shots == 1 {
+            print!(
+                "{}",
+                format!(
+                    "Enter coordinates to fire (e.g. C4, or row, col), {}save/load/record [file], undo, hint, help, surrender or quit: ",
+                    specials
+                )
+                .styled(Style::Emphasis)
+            );
+        } else {
+            print!(
+                "{}",
+                format!(
+                    "Enter {} coordinates to fire, separated by spaces or semicolons (e.g. A1 B2), {}save/load/record [file], undo, hint, help, surrender or quit: ",
+                    shots, specials
+                )
+                .styled(Style::Emphasis)
+            );
+        }
+        io::stdout().flush()?;
+        let input = match deadline {
+            Some(deadline) => match read_input_until(deadline)? {
+                Some(input) => input,
+                None => {
+                    println!();
+                    return Ok(PlayerAction::TimedOut);
+                }
+            },
+            None => read_line()?,
+        };
+
+        let mut words = input.split_whitespace();
+        match words.next().map(str::to_lowercase).as_deref() {
+            Some("save") => {
+                return Ok(PlayerAction::Save(
+                    words.next().unwrap_or(SAVE_FILE).to_string(),
+                ))
+            }
+            Some("load") => {
+                return Ok(PlayerAction::Load(
+                    words.next().unwrap_or(SAVE_FILE).to_string(),
+                ))
+            }
+            Some("record") => {
+                return Ok(PlayerAction::Record(
+                    words.next().unwrap_or(REPLAY_FILE).to_string(),
+                ))
+            }
+            Some("torpedo") => {
+                let rest: Vec<&str> = words.collect();
+                match parse_lane(&rest.join(" "), board_size) {
+                    Some(lane) => return Ok(PlayerAction::Torpedo(lane)),
+                    None => {
+                        println!(
+                            "{}",
+                            "Aim the torpedo along a row or column of the board, e.g. torpedo row 4 or torpedo column C."
+                                .styled(Style::Warning)
+                        );
+                        continue;
+                    }
+                }
+            }
+            Some("scan") => {
+                let rest: Vec<&str> = words.collect();
+                match parse_coordinates(&rest.join(" "), board_size) {
+                    Ok((row, col)) => return Ok(PlayerAction::Scan(row, col)),
+                    Err(error) => {
+                        print_parse_error(&error, board_size);
+                        continue;
+                    }
+                }
+            }
+            Some("radar") => {
+                let rest: Vec<&str> = words.collect();
+                match parse_sweep(&rest.join(" "), board_size) {
+                    Some(lane) => return Ok(PlayerAction::Sweep(lane)),
+                    None => {
+                        println!(
+                            "{}",
+                            "Sweep a row or column of the board, e.g. radar R3 for row 3 or radar CC for column C."
+                                .styled(Style::Warning)
+                        );
+                        continue;
+                    }
+                }
+            }
+            Some("undo") => return Ok(PlayerAction::Undo),
+            Some("hint") => return Ok(PlayerAction::Hint),
+            // Checked before parsing so "q" is never mistaken for a coordinate
+            Some("quit") | Some("q") => return Ok(PlayerAction::Quit),
+            Some("surrender") => return Ok(PlayerAction::Surrender),
+            Some("help") | Some("?") => {
+                print_legend();
+                continue;
+            }
+            // Left out of the prompt, as it is only for debugging
+            Some("reveal") if DEBUG.load(Ordering::Relaxed) => return Ok(PlayerAction::Reveal),
+            _ => (),
+        }
+
+        let targets = if shots == 1 {
+            parse_coordinates(&input, board_size).map(|target| vec![target])
+        } else {
+            parse_salvo(&input, board_size)
+        };
+        let targets = match targets {
+            Ok(targets) => targets,
+            Err(error) => {
+                print_parse_error(&error, board_size);
+                continue;
+            }
+        };
+        // Repeated and already fired cells are left for `check_salvo`, which knows the board
+        if targets.len() != shots {
+            println!(
+                "{}",
+                format!("Please enter exactly {} coordinates.", shots).styled(Style::Warning)
+            );
+        } else {
+            return Ok(PlayerAction::Fire(targets));
+        }
+    }
+}
+
+// Function to describe a shot's result in a few words, for the replay.
+pub fn describe_outcome(result: &ShotOutcome) -> String {
+    match result {
+        ShotOutcome::Miss => "miss".to_string(),
+        ShotOutcome::Hit => "hit".to_string(),
+        ShotOutcome::Sunk(name) => format!("sank the {}", name),
+        ShotOutcome::AlreadyFired => "already fired there".to_string(),
+        ShotOutcome::OffBoard => "off the board".to_string(),
+        ShotOutcome::Land => "struck land".to_string(),
+    }
+}
+
+// Function to describe one recorded shot as it follows "fired", e.g. "at B5: hit" or
+// "a torpedo along row 4: hit at C4".
+pub fn describe_move(shot: &Move) -> String {
+    let label = coordinate_label(shot.row, shot.col);
+    match shot.torpedo {
+        None => format!("at {}: {}", label, describe_outcome(&shot.outcome)),
+        Some(lane) if shot.outcome == ShotOutcome::Miss => {
+            format!("a torpedo along {}: miss", lane)
+        }
+        Some(lane) => format!(
+            "a torpedo along {}: {} at {}",
+            lane,
+            describe_outcome(&shot.outcome),
+            label
+        ),
+    }
+}
+
+// Function to word the warning for a shot at a cell that was already fired at, e.g.
+// "You already fired at B5 (it was a hit).".
+pub fn already_fired_warning(row: usize, col: usize, state: CellState) -> String {
+    let result = if state == CellState::Hit {
+        "hit"
+    } else {
+        "miss"
+    };
+    format!(
+        "You already fired at {} (it was a {}).",
+        coordinate_label(row, col),
+        result
+    )
+}
+
+// Function to print one line summing up a side's latest turn, e.g. "You fired at B5: hit.", or nothing if the
+// side hasn't fired yet.
+pub fn print_last_turn(name: &str, shots: &[Move]) {
+    if shots.is_empty() {
+        return;
+    }
+    let results: Vec<String> = shots.iter().map(describe_move).collect();
+    println!("{} fired {}.", name, results.join(", "));
+}
+
+// Function to ask a yes/no question, where anything but "y" or "yes" counts as no.
+pub fn ask_yes_no(question: &str) -> io::Result<bool> {
+    print!("{}", question.styled(Style::Emphasis));
+    io::stdout().flush()?;
+    let answer = read_line()?;
+    Ok(matches!(answer.trim().to_lowercase().as_str(), "y" | "yes"))
+}
+
+// Function to print the result of one of the player's shots
+pub fn report_player_shot(result: &ShotOutcome) {
+    match result {
+        ShotOutcome::Miss => println!("{}", "You missed!".styled(Style::Miss)),
+        ShotOutcome::Hit => println!("{}", "You hit a ship!".styled(Style::Hit)),
+        ShotOutcome::Sunk(name) => println!(
+            "{}",
+            format!("You sank the opponent's {}!", name).styled(Style::Highlight)
+        ),
+        ShotOutcome::AlreadyFired => println!("{}", "You already fired there.".styled(Style::Miss)),
+        ShotOutcome::OffBoard => println!("{}", "That's off the board.".styled(Style::Miss)),
+        ShotOutcome::Land => println!("{}", "Struck land!".styled(Style::Land)),
+    }
+}
+
+// Function to print the result of one of the opponent's shots
+pub fn report_opponent_shot(result: &ShotOutcome) {
+    match result {
+        ShotOutcome::Miss => println!("{}", "Opponent missed!".styled(Style::Miss)),
+        ShotOutcome::Hit => println!("{}", "Opponent hit one of your ships!".styled(Style::Hit)),
+        ShotOutcome::Sunk(name) => println!(
+            "{}",
+            format!("Opponent sank your {}!", name).styled(Style::Highlight)
+        ),
+        ShotOutcome::Land => println!("{}", "Opponent struck land!".styled(Style::Land)),
+        // The AI never picks a cell it has already fired at, nor one off the board.
+        ShotOutcome::AlreadyFired | ShotOutcome::OffBoard => (),
+    }
+}
+
+// Function to pause between turns: wait for Enter, or with auto-advance just sleep for the given time
+pub fn wait_to_continue(auto_advance: Option<Duration>) -> io::Result<()> {
+    match auto_advance {
+        Some(delay) => thread::sleep(delay),
+        None => {
+            println!("Press Enter to continue...");
+            read_line()?;
+        }
+    }
+    Ok(())
+}
+
+// Function to ask at startup whether to play the computer or a second player at the same keyboard, returning
+// true for the two-player hotseat mode.
+pub fn choose_hotseat() -> io::Result<bool> {
+    clear_screen();
+    println!("{}", "Choose a game mode:".styled(Style::Emphasis));
+    println!("  1) Play against the computer");
+    println!("  2) Two players taking turns at this keyboard");
+    loop {
+        print!("{}", "Mode [1]: ".styled(Style::Emphasis));
+        io::stdout().flush()?;
+        match read_line()?.trim() {
+            "" | "1" => return Ok(false),
+            "2" => return Ok(true),
+            _ => println!("{}", "Please enter 1 or 2.".styled(Style::Warning)),
+        }
+    }
+}
+
+// Function to blank the screen between hotseat turns so neither player sees the other's fleet, waiting
+// until the next player is at the keyboard.
+pub fn pass_keyboard(player: usize) -> io::Result<()> {
+    clear_screen();
+    println!(
+        "{}",
+        format!("Pass the keyboard to Player {} and press Enter", player).styled(Style::Emphasis)
+    );
+    read_line()?;
+    Ok(())
+}
+
+// Function to format a length of time as minutes and seconds, like "05:32". Past an hour the minutes keep
+// counting up.
+pub fn format_elapsed(elapsed: Duration) -> String {
+    let seconds = elapsed.as_secs();
+    format!("{:02}:{:02}", seconds / 60, seconds % 60)
+}
+
+// Function to print the line of running totals shown above the boards every turn, e.g.
+// "Turn 14 — You: 13 shots, 6 hits — Opponent: 13 shots, 4 hits — 05:32 elapsed", with plain dashes when
+// drawing in ASCII.
+pub fn print_hud(turn: &str, names: [&str; 2], stats: [Stats; 2], elapsed: Duration) {
+    let dash = if *glyphs() == ASCII_GLYPHS {
+        "-"
+    } else {
+        "—"
+    };
+    let sides: Vec<String> = names
+        .iter()
+        .zip(stats)
+        .map(|(name, stats)| format!("{}: {} shots, {} hits", name, stats.shots, stats.hits))
+        .collect();
+    println!(
+        "{} {} {} {} {} elapsed",
+        turn.styled(Style::Emphasis),
+        dash,
+        sides.join(&format!(" {} ", dash)),
+        dash,
+        format_elapsed(elapsed)
+    );
+}
+
+// Function to print the end-of-game summary for both sides, labelled with the given names and how long the game
+// took: shots, hits, misses, accuracy, turns taken, the longest run of hits and the score, then the ships each
+// side sank in order.
+pub fn print_scoreboard(
+    names: [&str; 2],
+    summaries: &[Summary; 2],
+    turns: usize,
+    elapsed: Duration,
+) {
+    println!();
+    println!(
+        "{}",
+        format!(
+            "Game over after {} turns in {}",
+            turns,
+            format_elapsed(elapsed)
+        )
+        .styled(Style::Emphasis)
+    );
+    println!(
+        "{:<10} {:>6} {:>6} {:>8} {:>9} {:>6} {:>7} {:>6}",
+        "", "Shots", "Hits", "Misses", "Accuracy", "Turns", "Streak", "Score"
+    );
+    for (name, summary) in names.into_iter().zip(summaries) {
+        let stats = &summary.stats;
+        println!(
+            "{:<10} {:>6} {:>6} {:>8} {:>8.1}% {:>6} {:>7} {:>6}",
+            name,
+            stats.shots,
+            stats.hits,
+            stats.misses,
+            stats.accuracy(),
+            summary.turns,
+            summary.longest_streak,
+            summary.score()
+        );
+    }
+    for (name, summary) in names.into_iter().zip(summaries) {
+        let sunk = if summary.sunk.is_empty() {
+            "nothing".to_string()
+        } else {
+            summary.sunk.join(", ")
+        };
+        println!("{} sank: {}", name, sunk);
+    }
+}
+
+// Function to explain why typed coordinates were refused: what was wrong with them, then either the rows and
+// columns the board has or how a coordinate is written.
+pub fn print_parse_error(error: &ParseError, board_size: usize) {
+    let hint = match error {
+        ParseError::RowOffBoard(_) | ParseError::ColumnOffBoard(_) => format!(
+            "Rows go from 0 to {} and columns from A to {}.",
+            board_size - 1,
+            (b'A' + board_size as u8 - 1) as char
+        ),
+        _ => "Enter a row and column number separated by a comma, or a column letter and row number like C4."
+            .to_string(),
+    };
+    let reason = error.to_string();
+    let mut letters = reason.chars();
+    let reason = match letters.next() {
+        Some(first) => first.to_uppercase().chain(letters).collect(),
+        None => reason,
+    };
+    println!("{}", format!("{}. {}", reason, hint).styled(Style::Warning));
+}
@@ -0,0 +1,323 @@
+use battle_ship::Orientation::{Horizontal, Vertical};
+use battle_ship::{
+    heatmap, is_valid_coord, suggest_target, torpedo_lane, Arsenal, Board, BoardView, CellState,
+    DensityStrategy, Difficulty, FleetConfig, HuntTargetStrategy, Lane, OpponentAi, Player,
+    PlayerAction, RandomStrategy, ShotOutcome, Strategy, FLEET,
+};
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use std::collections::HashSet;
+
+const DIFFICULTIES: [Difficulty; 3] = [Difficulty::Easy, Difficulty::Normal, Difficulty::Hard];
+
+// Helper to build a board with the standard fleet.
+fn standard_board() -> Board {
+    let mut board = Board::new(10);
+    for (name, size) in FLEET {
+        board
+            .place_ship(name, size, &mut rand::thread_rng())
+            .unwrap();
+    }
+    board
+}
+
+// Helper to play a full game for the AI and count how many shots it needed.
+fn shots_to_win(difficulty: Difficulty) -> usize {
+    let mut board = standard_board();
+    let mut ai = OpponentAi::new(difficulty, rand::random());
+    let mut shots = 0;
+    while !board.is_game_over() {
+        let (row, col) = ai.next_move(&board);
+        let outcome = board.fire(row, col);
+        ai.notify((row, col), &outcome);
+        shots += 1;
+    }
+    shots
+}
+
+#[test]
+fn opponent_never_fires_at_the_same_cell_twice() {
+    for difficulty in DIFFICULTIES {
+        let mut board = standard_board();
+        let mut ai = OpponentAi::new(difficulty, rand::random());
+        let mut fired = HashSet::new();
+
+        for _ in 0..100 {
+            if board.is_game_over() {
+                break;
+            }
+            let (row, col) = ai.next_move(&board);
+            assert!(fired.insert((row, col)), "fired at {row}, {col} twice");
+            let outcome = board.fire(row, col);
+            assert_ne!(outcome, ShotOutcome::AlreadyFired);
+            ai.notify((row, col), &outcome);
+        }
+    }
+}
+
+#[test]
+fn opponent_finishes_within_board_area() {
+    for difficulty in DIFFICULTIES {
+        for _ in 0..20 {
+            assert!(shots_to_win(difficulty) <= 100);
+        }
+    }
+}
+
+#[test]
+fn harder_opponents_need_fewer_shots() {
+    let average = |difficulty| (0..50).map(|_| shots_to_win(difficulty)).sum::<usize>() / 50;
+    let easy = average(Difficulty::Easy);
+    let normal = average(Difficulty::Normal);
+    let hard = average(Difficulty::Hard);
+    assert!(normal < easy, "normal {normal} vs easy {easy}");
+    assert!(hard < normal, "hard {hard} vs normal {normal}");
+}
+
+#[test]
+fn same_seed_produces_the_same_shots() {
+    for difficulty in DIFFICULTIES {
+        let mut board = standard_board();
+        let mut first = OpponentAi::new(difficulty, 42);
+        let mut second = OpponentAi::new(difficulty, 42);
+
+        for _ in 0..30 {
+            let shot = first.next_move(&board);
+            assert_eq!(second.next_move(&board), shot);
+            let outcome = board.fire(shot.0, shot.1);
+            first.notify(shot, &outcome);
+            second.notify(shot, &outcome);
+        }
+    }
+}
+
+#[test]
+fn hard_and_expert_opponents_skip_cells_next_to_sunk_ships_without_touching() {
+    for difficulty in [Difficulty::Hard, Difficulty::Expert] {
+        let mut board = Board::new(10);
+        board.set_no_touching(true);
+        board
+            .place_ship_at("Destroyer", 0, 0, 2, Horizontal)
+            .unwrap();
+        board.place_ship_at("Cruiser", 5, 5, 3, Vertical).unwrap();
+        board.fire(0, 0);
+        board.fire(0, 1);
+
+        let mut ai = OpponentAi::new(difficulty, 7);
+        let mut fired = HashSet::new();
+        while !board.is_game_over() {
+            let (row, col) = ai.next_move(&board);
+            assert!(
+                row > 1 || col > 2,
+                "{difficulty} fired at {row}, {col} next to the sunk Destroyer"
+            );
+            assert!(fired.insert((row, col)), "fired at {row}, {col} twice");
+            board.fire(row, col);
+        }
+    }
+}
+
+#[test]
+fn opponent_stays_on_the_board_when_chasing_ships_in_the_corners() {
+    for difficulty in DIFFICULTIES {
+        let mut board = Board::new(5);
+        board.place_ship_at("Top", 0, 0, 2, Horizontal).unwrap();
+        board.place_ship_at("Bottom", 3, 4, 2, Vertical).unwrap();
+        let mut ai = OpponentAi::new(difficulty, rand::random());
+        while !board.is_game_over() {
+            let (row, col) = ai.next_move(&board);
+            assert!(is_valid_coord(row, col, board.size()));
+            let outcome = board.fire(row, col);
+            ai.notify((row, col), &outcome);
+        }
+    }
+}
+
+#[test]
+fn difficulty_names_parse_in_any_case() {
+    assert_eq!("easy".parse(), Ok(Difficulty::Easy));
+    assert_eq!("Normal".parse(), Ok(Difficulty::Normal));
+    assert_eq!("medium".parse(), Ok(Difficulty::Normal));
+    assert_eq!("HARD".parse(), Ok(Difficulty::Hard));
+    assert_eq!("Expert".parse(), Ok(Difficulty::Expert));
+    assert_eq!("montecarlo".parse(), Ok(Difficulty::Expert));
+    assert!("impossible".parse::<Difficulty>().is_err());
+}
+
+#[test]
+fn heatmap_concentrates_around_a_known_hit() {
+    let mut board = Board::new(10);
+    board.place_ship_at("Cruiser", 4, 4, 3, Horizontal).unwrap();
+    board.fire(4, 5);
+
+    let scores = heatmap(&BoardView::new(&board));
+    let (best_row, best_col) = (0..10)
+        .flat_map(|r| (0..10).map(move |c| (r, c)))
+        .max_by_key(|&(r, c)| scores[r][c])
+        .unwrap();
+    assert_eq!(best_row.abs_diff(4) + best_col.abs_diff(5), 1);
+    assert_eq!(scores[4][5], 0);
+    // Everything next to the hit outscores every cell further away
+    let next_to_hit = [scores[3][5], scores[5][5], scores[4][4], scores[4][6]];
+    let elsewhere = (0..10usize)
+        .flat_map(|r| (0..10usize).map(move |c| (r, c)))
+        .filter(|&(r, c)| r.abs_diff(4) + c.abs_diff(5) > 2)
+        .map(|(r, c)| scores[r][c])
+        .max()
+        .unwrap();
+    assert!(next_to_hit.iter().all(|&score| score > elsewhere));
+}
+
+#[test]
+fn strategies_are_interchangeable() {
+    let strategies: Vec<Box<dyn Strategy>> = vec![
+        Box::new(RandomStrategy),
+        Box::new(HuntTargetStrategy::default()),
+        Box::new(DensityStrategy),
+    ];
+    for mut strategy in strategies {
+        let mut board = standard_board();
+        let mut rng = StdRng::seed_from_u64(3);
+        let mut shots = 0;
+        while !board.is_game_over() {
+            let (row, col) = strategy.choose_target(&BoardView::new(&board), &mut rng);
+            let outcome = board.fire(row, col);
+            assert_ne!(outcome, ShotOutcome::AlreadyFired);
+            strategy.notify((row, col), &outcome);
+            shots += 1;
+        }
+        assert!(shots <= 100);
+    }
+}
+
+#[test]
+fn two_computer_players_can_play_a_whole_game_through_the_player_trait() {
+    let mut rng = StdRng::seed_from_u64(5);
+    let mut players: [Box<dyn Player>; 2] = [
+        Box::new(OpponentAi::new(Difficulty::Easy, 1)),
+        Box::new(OpponentAi::new(Difficulty::Hard, 2)),
+    ];
+    let mut boards = [Board::new(10), Board::new(10)];
+    for (player, board) in players.iter_mut().zip(boards.iter_mut()) {
+        player
+            .place_ships(board, &FleetConfig::standard(), &mut rng)
+            .unwrap();
+    }
+
+    let mut current = 0;
+    while !boards.iter().any(Board::is_game_over) {
+        let target = &mut boards[1 - current];
+        let PlayerAction::Fire(targets) = players[current]
+            .choose_shots(&BoardView::new(target), 1, Arsenal::default())
+            .unwrap()
+        else {
+            panic!("a computer player should only ever fire");
+        };
+        for (row, col) in targets {
+            let outcome = target.fire(row, col);
+            assert_ne!(outcome, ShotOutcome::AlreadyFired);
+            players[current].report_shot((row, col), &outcome);
+        }
+        current = 1 - current;
+    }
+}
+
+#[test]
+fn hunt_target_strategy_fires_next_to_a_reported_hit() {
+    let mut board = Board::new(10);
+    board.place_ship_at("Cruiser", 4, 4, 3, Horizontal).unwrap();
+    let mut strategy = HuntTargetStrategy::default();
+    let mut rng = StdRng::seed_from_u64(1);
+    let outcome = board.fire(4, 5);
+    strategy.notify((4, 5), &outcome);
+
+    let (row, col) = strategy.choose_target(&BoardView::new(&board), &mut rng);
+    assert_eq!(row.abs_diff(4) + col.abs_diff(5), 1);
+}
+
+#[test]
+fn computer_saves_torpedoes_for_open_lanes_with_no_wounded_ship() {
+    let mut board = Board::new(10);
+    board
+        .place_ship_at("Destroyer", 9, 8, 2, Horizontal)
+        .unwrap();
+    assert_eq!(torpedo_lane(&BoardView::new(&board)), None);
+
+    // A fifth of the board fired at, all misses: the emptiest lane is the last column
+    for row in 0..10 {
+        board.fire(row, 0);
+        board.fire(row, 1);
+    }
+    assert_eq!(torpedo_lane(&BoardView::new(&board)), Some(Lane::Column(9)));
+
+    // Not while the Destroyer is hit but still afloat
+    board.fire(9, 8);
+    assert_eq!(torpedo_lane(&BoardView::new(&board)), None);
+    board.fire(9, 9);
+    assert!(torpedo_lane(&BoardView::new(&board)).is_some());
+}
+
+#[test]
+fn opponent_never_fires_at_land() {
+    let fleet = FleetConfig::standard();
+    for difficulty in [
+        Difficulty::Easy,
+        Difficulty::Normal,
+        Difficulty::Hard,
+        Difficulty::Expert,
+    ] {
+        let mut rng = StdRng::seed_from_u64(8);
+        let mut board = Board::new(10);
+        assert_eq!(board.raise_islands(15, &fleet, &mut rng), 15);
+        board.place_fleet(&fleet, &mut rng).unwrap();
+        let land = board.land();
+        let mut ai = OpponentAi::new(difficulty, 8);
+        while !board.is_game_over() {
+            let (row, col) = ai.next_move(&board);
+            assert!(!land.contains(&(row, col)), "fired at land at {row}, {col}");
+            let outcome = board.fire(row, col);
+            ai.notify((row, col), &outcome);
+        }
+    }
+}
+
+#[test]
+fn hints_depend_only_on_what_the_player_can_see() {
+    // Two boards with their ships in different places look the same before any shots, so they get the same hint
+    let mut first = Board::new(10);
+    let mut second = Board::new(10);
+    first
+        .place_fleet(&FleetConfig::standard(), &mut StdRng::seed_from_u64(1))
+        .unwrap();
+    second
+        .place_fleet(&FleetConfig::standard(), &mut StdRng::seed_from_u64(2))
+        .unwrap();
+    assert_eq!(
+        suggest_target(&BoardView::new(&first)),
+        suggest_target(&BoardView::new(&second))
+    );
+
+    // The same misses on both still give the same hint, on a cell that hasn't been fired at
+    let water: Vec<(usize, usize)> = (0..10)
+        .flat_map(|r| (0..10).map(move |c| (r, c)))
+        .filter(|&(r, c)| first.cell(r, c) == CellState::Empty)
+        .filter(|&(r, c)| second.cell(r, c) == CellState::Empty)
+        .take(20)
+        .collect();
+    for &(row, col) in &water {
+        assert_eq!(first.fire(row, col), ShotOutcome::Miss);
+        assert_eq!(second.fire(row, col), ShotOutcome::Miss);
+    }
+    let hint = suggest_target(&BoardView::new(&first));
+    assert_eq!(hint, suggest_target(&BoardView::new(&second)));
+    assert!(!first.has_been_fired_at(hint.0, hint.1));
+}
+
+#[test]
+fn hint_follows_up_a_hit_next_to_it() {
+    let mut board = Board::new(10);
+    board.place_ship_at("Cruiser", 4, 4, 3, Horizontal).unwrap();
+    board.fire(4, 5);
+    let (row, col) = suggest_target(&BoardView::new(&board));
+    assert_eq!(row.abs_diff(4) + col.abs_diff(5), 1);
+}
@@ -0,0 +1,158 @@
+use battle_ship::{Bitboard, Board, CellState, FleetConfig, ShotOutcome, MAX_BOARD_SIZE};
+use proptest::prelude::*;
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+
+// The board as it used to be kept, one cell state per cell with the ships' cells alongside, so the bit layers
+// can be checked against it shot for shot.
+struct Reference {
+    grid: Vec<Vec<CellState>>,
+    ships: Vec<(String, Vec<(usize, usize)>)>,
+}
+
+impl Reference {
+    // Constructor copying the cells and ships of a freshly placed board.
+    fn new(board: &Board) -> Self {
+        Reference {
+            grid: (0..board.size())
+                .map(|r| (0..board.size()).map(|c| board.cell(r, c)).collect())
+                .collect(),
+            ships: board
+                .ships()
+                .iter()
+                .map(|ship| (ship.name.clone(), ship.cells.clone()))
+                .collect(),
+        }
+    }
+
+    // Method to fire at a cell the way `Board::fire` always has.
+    fn fire(&mut self, row: usize, col: usize) -> ShotOutcome {
+        match self.grid[row][col] {
+            CellState::Empty => {
+                self.grid[row][col] = CellState::Miss;
+                ShotOutcome::Miss
+            }
+            CellState::Ship => {
+                self.grid[row][col] = CellState::Hit;
+                let (name, cells) = self
+                    .ships
+                    .iter()
+                    .find(|(_, cells)| cells.contains(&(row, col)))
+                    .unwrap();
+                if cells
+                    .iter()
+                    .all(|&(r, c)| self.grid[r][c] == CellState::Hit)
+                {
+                    ShotOutcome::Sunk(name.clone())
+                } else {
+                    ShotOutcome::Hit
+                }
+            }
+            _ => ShotOutcome::AlreadyFired,
+        }
+    }
+
+    // Method to take a shot back the way `Board::undo_shot` always has.
+    fn undo(&mut self, row: usize, col: usize) {
+        self.grid[row][col] = match self.grid[row][col] {
+            CellState::Miss => CellState::Empty,
+            CellState::Hit => CellState::Ship,
+            state => state,
+        };
+    }
+
+    // Method to check whether every ship cell has been hit.
+    fn is_game_over(&self) -> bool {
+        self.grid
+            .iter()
+            .flatten()
+            .all(|&cell| cell != CellState::Ship)
+    }
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig {
+        cases: 200,
+        failure_persistence: None,
+        ..ProptestConfig::default()
+    })]
+
+    #[test]
+    fn board_matches_the_cell_by_cell_reference(
+        size in 7..=MAX_BOARD_SIZE,
+        seed in any::<u64>(),
+        shots in proptest::collection::vec((0..MAX_BOARD_SIZE, 0..MAX_BOARD_SIZE, any::<bool>()), 1..400),
+    ) {
+        let mut board = Board::new(size);
+        board
+            .place_fleet(&FleetConfig::standard(), &mut StdRng::seed_from_u64(seed))
+            .unwrap();
+        let mut reference = Reference::new(&board);
+        for (row, col, undo) in shots {
+            let (row, col) = (row % size, col % size);
+            if undo {
+                board.undo_shot(row, col);
+                reference.undo(row, col);
+            } else {
+                prop_assert_eq!(board.fire(row, col), reference.fire(row, col));
+            }
+            prop_assert_eq!(board.cell(row, col), reference.grid[row][col]);
+            prop_assert_eq!(
+                board.has_been_fired_at(row, col),
+                matches!(reference.grid[row][col], CellState::Hit | CellState::Miss)
+            );
+            prop_assert_eq!(board.is_game_over(), reference.is_game_over());
+        }
+        for r in 0..size {
+            for c in 0..size {
+                prop_assert_eq!(board.cell(r, c), reference.grid[r][c]);
+            }
+        }
+    }
+}
+
+#[test]
+fn bitboard_holds_every_cell_of_the_largest_board() {
+    let mut cells = Bitboard::EMPTY;
+    assert!(cells.is_empty());
+    let last = MAX_BOARD_SIZE - 1;
+    for (r, c) in [(0, 0), (4, 23), (last, 0), (last, last)] {
+        cells.insert(r, c);
+    }
+    assert_eq!(cells.count(), 4);
+    assert!(cells.contains(last, last));
+    assert!(!cells.contains(last, last - 1));
+    assert_eq!(
+        cells.cells().collect::<Vec<_>>(),
+        vec![(0, 0), (4, 23), (last, 0), (last, last)]
+    );
+    cells.remove(last, last);
+    assert!(!cells.contains(last, last));
+    assert_eq!(cells.count(), 3);
+}
+
+#[test]
+fn bitboard_layers_combine_bit_by_bit() {
+    let mut ships = Bitboard::EMPTY;
+    let mut hits = Bitboard::EMPTY;
+    ships.insert(2, 3);
+    ships.insert(20, 20);
+    hits.insert(2, 3);
+    assert_eq!((ships & !hits).cells().collect::<Vec<_>>(), vec![(20, 20)]);
+    assert_eq!((ships | hits).count(), 2);
+    hits.insert(20, 20);
+    assert!((ships & !hits).is_empty());
+}
+
+#[test]
+fn cloned_board_is_independent_of_the_original() {
+    let mut board = Board::new(10);
+    board
+        .place_fleet(&FleetConfig::standard(), &mut StdRng::seed_from_u64(3))
+        .unwrap();
+    let copy = board.clone();
+    let &(row, col) = &board.ships()[0].cells[0];
+    board.fire(row, col);
+    assert_eq!(board.cell(row, col), CellState::Hit);
+    assert_eq!(copy.cell(row, col), CellState::Ship);
+}
@@ -0,0 +1,864 @@
+use battle_ship::Orientation::{Horizontal, Vertical};
+use battle_ship::{
+    Board, BoardView, CellState, FleetConfig, FleetError, Lane, Orientation, PlacementError, Rules,
+    ShotOutcome, TorpedoOutcome, FLEET,
+};
+use rand::rngs::mock::StepRng;
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+
+// Helper to find an empty cell on a board so misses can be fired at a known coordinate.
+fn empty_cell(board: &Board) -> (usize, usize) {
+    (0..board.size())
+        .flat_map(|r| (0..board.size()).map(move |c| (r, c)))
+        .find(|&(r, c)| board.cell(r, c) == CellState::Empty)
+        .unwrap()
+}
+
+#[test]
+fn placed_ships_occupy_their_cells() {
+    let mut board = Board::new(10);
+    board
+        .place_ship("Carrier", 5, &mut rand::thread_rng())
+        .unwrap();
+    board
+        .place_ship("Destroyer", 2, &mut rand::thread_rng())
+        .unwrap();
+
+    assert_eq!(board.ships().len(), 2);
+    for ship in board.ships() {
+        assert_eq!(ship.cells.len(), ship.size);
+        for &(r, c) in &ship.cells {
+            assert_eq!(board.cell(r, c), CellState::Ship);
+        }
+    }
+}
+
+#[test]
+fn same_seed_places_the_same_fleet() {
+    let place_fleet = |seed| {
+        let mut rng = StdRng::seed_from_u64(seed);
+        let mut board = Board::new(10);
+        for (name, size) in FLEET {
+            board.place_ship(name, size, &mut rng).unwrap();
+        }
+        board
+            .ships()
+            .iter()
+            .map(|ship| ship.cells.clone())
+            .collect::<Vec<_>>()
+    };
+
+    assert_eq!(place_fleet(42), place_fleet(42));
+    assert_ne!(place_fleet(42), place_fleet(43));
+}
+
+#[test]
+fn firing_at_known_coordinates_hits_and_sinks() {
+    let mut board = Board::new(10);
+    board
+        .place_ship("Destroyer", 2, &mut rand::thread_rng())
+        .unwrap();
+    let cells = board.ships()[0].cells.clone();
+
+    assert_eq!(board.fire(cells[0].0, cells[0].1), ShotOutcome::Hit);
+    assert!(!board.is_game_over());
+    assert_eq!(
+        board.fire(cells[1].0, cells[1].1),
+        ShotOutcome::Sunk("Destroyer".to_string())
+    );
+    assert!(board.is_game_over());
+}
+
+#[test]
+fn firing_at_empty_cell_misses() {
+    let mut board = Board::new(10);
+    board
+        .place_ship("Destroyer", 2, &mut rand::thread_rng())
+        .unwrap();
+    let (row, col) = empty_cell(&board);
+
+    assert_eq!(board.fire(row, col), ShotOutcome::Miss);
+    assert_eq!(board.cell(row, col), CellState::Miss);
+}
+
+#[test]
+fn firing_twice_at_ship_cell_reports_already_fired() {
+    let mut board = Board::new(10);
+    board
+        .place_ship("Carrier", 5, &mut rand::thread_rng())
+        .unwrap();
+    let (row, col) = board.ships()[0].cells[0];
+
+    assert_eq!(board.fire(row, col), ShotOutcome::Hit);
+    assert_eq!(board.fire(row, col), ShotOutcome::AlreadyFired);
+    assert_eq!(board.cell(row, col), CellState::Hit);
+    assert_eq!(board.ships()[0].hits, 1);
+}
+
+#[test]
+fn firing_twice_at_empty_cell_reports_already_fired() {
+    let mut board = Board::new(10);
+    board
+        .place_ship("Carrier", 5, &mut rand::thread_rng())
+        .unwrap();
+    let (row, col) = empty_cell(&board);
+
+    assert_eq!(board.fire(row, col), ShotOutcome::Miss);
+    assert_eq!(board.fire(row, col), ShotOutcome::AlreadyFired);
+    assert_eq!(board.cell(row, col), CellState::Miss);
+}
+
+#[test]
+fn place_ship_at_puts_ship_at_requested_cells() {
+    let mut board = Board::new(10);
+    assert_eq!(board.place_ship_at("Cruiser", 2, 3, 3, Horizontal), Ok(()));
+    assert_eq!(board.ships()[0].cells, vec![(2, 3), (2, 4), (2, 5)]);
+    assert_eq!(board.place_ship_at("Destroyer", 5, 5, 2, Vertical), Ok(()));
+    assert_eq!(board.ships()[1].cells, vec![(5, 5), (6, 5)]);
+}
+
+#[test]
+fn place_ship_at_rejects_invalid_positions() {
+    let mut board = Board::new(10);
+    board.place_ship_at("Cruiser", 2, 3, 3, Horizontal).unwrap();
+
+    assert_eq!(
+        board.place_ship_at("Carrier", 0, 7, 5, Horizontal),
+        Err(PlacementError::OutOfBounds)
+    );
+    assert_eq!(
+        board.place_ship_at("Carrier", board.size(), 0, 5, Vertical),
+        Err(PlacementError::OutOfBounds)
+    );
+    assert_eq!(
+        board.place_ship_at("Destroyer", 1, 4, 2, Vertical),
+        Err(PlacementError::Overlap)
+    );
+    assert_eq!(board.ships().len(), 1);
+}
+
+#[test]
+fn boards_can_be_created_at_other_sizes() {
+    let mut board = Board::new(8);
+    assert_eq!(board.size(), 8);
+    assert_eq!(
+        board.place_ship_at("Carrier", 0, 4, 5, Horizontal),
+        Err(PlacementError::OutOfBounds)
+    );
+    assert_eq!(board.place_ship_at("Carrier", 7, 3, 5, Horizontal), Ok(()));
+}
+
+#[test]
+fn fleet_must_fit_the_board() {
+    let fleet = FleetConfig::standard();
+    assert!(fleet.fits(10));
+    assert!(fleet.fits(6));
+    assert!(!fleet.fits(5));
+    let giant = FleetConfig {
+        ships: vec![("Giant".to_string(), 12)],
+    };
+    assert!(!giant.fits(11));
+}
+
+#[test]
+fn built_in_rules_are_the_standard_game() {
+    let rules = Rules::default();
+    assert_eq!(rules.size, 10);
+    assert_eq!(rules.fleet(), FleetConfig::standard());
+}
+
+#[test]
+fn rules_load_from_json_and_are_checked() {
+    let rules = Rules::parse(
+        r#"{"size": 6, "ships": [{"name": "Frigate", "size": 4}, {"name": "Sloop", "size": 2}]}"#,
+    )
+    .unwrap();
+    assert_eq!(rules.size, 6);
+    assert_eq!(
+        rules.fleet().ships,
+        vec![("Frigate".to_string(), 4), ("Sloop".to_string(), 2)]
+    );
+    let mut board = Board::new(rules.size);
+    board
+        .place_fleet(&rules.fleet(), &mut StdRng::seed_from_u64(2))
+        .unwrap();
+    assert_eq!(board.remaining_ships(), vec![4, 2]);
+
+    let ship = |size| format!(r#"{{"name": "Carrier", "size": {}}}"#, size);
+    let rules =
+        |size, ships: &str| Rules::parse(&format!(r#"{{"size": {}, "ships": [{}]}}"#, size, ships));
+    assert!(matches!(rules(4, &ship(5)), Err(FleetError::DoesNotFit(4))));
+    assert!(matches!(
+        rules(0, &ship(1)),
+        Err(FleetError::InvalidSize(0))
+    ));
+    assert!(matches!(
+        rules(99, &ship(1)),
+        Err(FleetError::InvalidSize(99))
+    ));
+    assert!(matches!(rules(10, &ship(0)), Err(FleetError::EmptyShip(_))));
+    assert!(matches!(rules(10, ""), Err(FleetError::Empty)));
+    assert!(matches!(
+        Rules::parse("size = 10"),
+        Err(FleetError::Json(_))
+    ));
+}
+
+#[test]
+fn custom_fleets_parse_from_text() {
+    let fleet = FleetConfig::parse("# Small fleet\nPatrol Boat 2\n\nFrigate 3\n").unwrap();
+    assert_eq!(
+        fleet.ships,
+        vec![("Patrol Boat".to_string(), 2), ("Frigate".to_string(), 3)]
+    );
+    assert_eq!(FleetConfig::standard().ships.len(), FLEET.len());
+
+    assert!(matches!(
+        FleetConfig::parse("Frigate three"),
+        Err(FleetError::InvalidLine(1, _))
+    ));
+    assert!(matches!(
+        FleetConfig::parse("Frigate 3\nRaft 0"),
+        Err(FleetError::InvalidLine(2, _))
+    ));
+    assert!(matches!(
+        FleetConfig::parse("# nothing here\n"),
+        Err(FleetError::Empty)
+    ));
+}
+
+#[test]
+fn placement_gives_up_when_there_is_no_room() {
+    let mut board = Board::new(3);
+    for row in 0..3 {
+        board
+            .place_ship_at("Cruiser", row, 0, 3, Horizontal)
+            .unwrap();
+    }
+    assert_eq!(
+        board.place_ship("Destroyer", 2, &mut rand::thread_rng()),
+        Err(PlacementError::NoRoom)
+    );
+
+    let mut board = Board::new(4);
+    assert_eq!(
+        board.place_fleet(&FleetConfig::standard(), &mut rand::thread_rng()),
+        Err(PlacementError::NoRoom)
+    );
+}
+
+#[test]
+fn placement_finds_the_last_spot_that_fits_on_a_small_board() {
+    // An rng stuck on zero keeps trying A0 across, so the second ship is only placed by the scan
+    let mut rng = StepRng::new(0, 0);
+    let mut board = Board::new(3);
+    board.place_ship("Cruiser", 3, &mut rng).unwrap();
+    board.place_ship("Submarine", 3, &mut rng).unwrap();
+    assert_eq!(board.ships()[0].cells, vec![(0, 0), (0, 1), (0, 2)]);
+    assert_eq!(board.ships()[1].cells, vec![(1, 0), (1, 1), (1, 2)]);
+
+    // Without touching, the only spot left for a second ship would be next to the first
+    let mut board = Board::new(3);
+    board.set_no_touching(true);
+    board.place_ship_at("Cruiser", 1, 0, 3, Horizontal).unwrap();
+    assert_eq!(
+        board.place_ship("Submarine", 3, &mut rng),
+        Err(PlacementError::NoRoom)
+    );
+}
+
+#[test]
+fn undoing_shots_restores_the_board() {
+    let mut board = Board::new(5);
+    board
+        .place_ship_at("Destroyer", 0, 0, 2, Horizontal)
+        .unwrap();
+    let before = board.stats();
+    board.fire(0, 0);
+    board.fire(4, 4);
+    assert_eq!(board.fire(0, 1), ShotOutcome::Sunk("Destroyer".to_string()));
+
+    for (row, col) in [(0, 1), (4, 4), (0, 0)] {
+        board.undo_shot(row, col);
+    }
+    assert_eq!(board.stats(), before);
+    assert_eq!(board.cell(0, 1), CellState::Ship);
+    assert_eq!(board.cell(4, 4), CellState::Empty);
+    assert_eq!(board.remaining_ships(), vec![2]);
+    // Undoing a cell that was never fired at changes nothing
+    board.undo_shot(3, 3);
+    assert_eq!(board.cell(3, 3), CellState::Empty);
+    assert_eq!(board.stats(), before);
+}
+
+#[test]
+fn undoing_shots_on_a_board_saved_without_stats_leaves_them_at_zero() {
+    let mut board = Board::new(5);
+    board
+        .place_ship_at("Destroyer", 0, 0, 2, Horizontal)
+        .unwrap();
+    board.fire(0, 0);
+    board.fire(4, 4);
+    // Saves from before the stats were kept load them as all zero
+    let mut saved = serde_json::to_value(&board).unwrap();
+    saved.as_object_mut().unwrap().remove("stats");
+    let mut board: Board = serde_json::from_value(saved).unwrap();
+    board.undo_shot(0, 0);
+    board.undo_shot(4, 4);
+    assert_eq!(board.stats().shots, 0);
+    assert_eq!(board.stats().hits, 0);
+    assert_eq!(board.stats().misses, 0);
+    assert_eq!(board.cell(0, 0), CellState::Ship);
+    assert_eq!(board.cell(4, 4), CellState::Empty);
+}
+
+#[test]
+fn the_latest_shot_is_remembered_until_it_is_undone() {
+    let mut board = Board::new(5);
+    board
+        .place_ship_at("Destroyer", 0, 0, 2, Horizontal)
+        .unwrap();
+    assert_eq!(board.last_shot(), None);
+    board.fire(0, 0);
+    assert_eq!(board.last_shot(), Some((0, 0)));
+    board.fire(3, 3);
+    assert_eq!(board.last_shot(), Some((3, 3)));
+    // Firing at the same cell again doesn't count as a new shot
+    board.fire(0, 0);
+    assert_eq!(board.last_shot(), Some((3, 3)));
+    board.undo_shot(3, 3);
+    assert_eq!(board.last_shot(), None);
+}
+
+#[test]
+fn touching_ships_are_allowed_by_default() {
+    let mut board = Board::new(10);
+    board.place_ship_at("Cruiser", 2, 2, 3, Horizontal).unwrap();
+    assert!(board.can_place_ship(3, 2, 3, Horizontal));
+    assert!(board.can_place_ship(3, 5, 2, Vertical));
+}
+
+#[test]
+fn no_touching_rule_rejects_adjacent_ships() {
+    let mut board = Board::new(10);
+    board.set_no_touching(true);
+    board.place_ship_at("Cruiser", 2, 2, 3, Horizontal).unwrap();
+
+    // Side by side, end to end and diagonally touching placements are all rejected.
+    assert!(!board.can_place_ship(3, 2, 3, Horizontal));
+    assert!(!board.can_place_ship(2, 5, 2, Horizontal));
+    assert!(!board.can_place_ship(3, 5, 2, Vertical));
+    assert_eq!(
+        board.place_ship_at("Destroyer", 1, 0, 2, Horizontal),
+        Err(PlacementError::Adjacent)
+    );
+    assert_eq!(
+        board.place_ship_at("Destroyer", 2, 3, 2, Vertical),
+        Err(PlacementError::Overlap)
+    );
+
+    // Leaving a one-cell gap is fine.
+    assert_eq!(
+        board.place_ship_at("Destroyer", 4, 2, 2, Horizontal),
+        Ok(())
+    );
+    assert_eq!(board.place_ship_at("Submarine", 0, 6, 3, Vertical), Ok(()));
+}
+
+#[test]
+fn random_fleets_always_fit_without_touching() {
+    let fleet = FleetConfig::standard();
+    let mut rng = StdRng::seed_from_u64(0);
+    for _ in 0..1000 {
+        let mut board = Board::new(10);
+        board.set_no_touching(true);
+        board.place_fleet(&fleet, &mut rng).unwrap();
+        assert_eq!(board.ships().len(), FLEET.len());
+
+        // No cell of one ship may sit next to, or diagonally from, a cell of another ship.
+        for (i, ship) in board.ships().iter().enumerate() {
+            for other in &board.ships()[i + 1..] {
+                for &(r, c) in &ship.cells {
+                    assert!(other
+                        .cells
+                        .iter()
+                        .all(|&(or, oc)| r.abs_diff(or) > 1 || c.abs_diff(oc) > 1));
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn ships_afloat_drops_as_ships_sink() {
+    let mut board = Board::new(10);
+    board.place_ship_at("Cruiser", 0, 0, 3, Horizontal).unwrap();
+    board.place_ship_at("Destroyer", 5, 5, 2, Vertical).unwrap();
+    assert_eq!(board.ships_afloat(), 2);
+
+    board.fire(5, 5);
+    assert_eq!(board.ships_afloat(), 2);
+    board.fire(6, 5);
+    assert_eq!(board.ships_afloat(), 1);
+}
+
+#[test]
+fn remaining_ships_shrink_as_ships_sink() {
+    let mut board = Board::new(10);
+    board.place_ship_at("Cruiser", 0, 0, 3, Horizontal).unwrap();
+    board.place_ship_at("Destroyer", 5, 5, 2, Vertical).unwrap();
+    assert_eq!(board.remaining_ships(), vec![3, 2]);
+
+    board.fire(0, 0);
+    board.fire(5, 5);
+    assert_eq!(board.remaining_ships(), vec![3, 2]);
+    board.fire(6, 5);
+    assert_eq!(board.remaining_ships(), vec![3]);
+    board.fire(0, 1);
+    board.fire(0, 2);
+    assert!(board.remaining_ships().is_empty());
+}
+
+#[test]
+fn stats_count_each_cell_only_once() {
+    let mut board = Board::new(10);
+    board
+        .place_ship_at("Destroyer", 0, 0, 2, Horizontal)
+        .unwrap();
+
+    board.fire(0, 0);
+    board.fire(0, 0);
+    board.fire(5, 5);
+    board.fire(5, 5);
+    board.fire(0, 1);
+
+    let stats = board.stats();
+    assert_eq!(stats.shots, 3);
+    assert_eq!(stats.hits, 2);
+    assert_eq!(stats.misses, 1);
+    assert!((stats.accuracy() - 200.0 / 3.0).abs() < 1e-9);
+    assert_eq!(Board::new(10).stats().accuracy(), 0.0);
+}
+
+#[test]
+fn vertical_ships_run_down_a_single_column() {
+    let mut board = Board::new(10);
+    board.place_ship_at("Cruiser", 2, 6, 3, Vertical).unwrap();
+
+    let ship = &board.ships()[0];
+    assert_eq!(ship.orientation, Orientation::Vertical);
+    assert_eq!(ship.cells, vec![(2, 6), (3, 6), (4, 6)]);
+    for row in 0..10 {
+        let expected = if (2..5).contains(&row) {
+            CellState::Ship
+        } else {
+            CellState::Empty
+        };
+        assert_eq!(board.cell(row, 6), expected);
+    }
+}
+
+#[test]
+fn unfired_copy_restores_the_board_before_any_shots() {
+    let mut board = Board::new(10);
+    board
+        .place_ship_at("Destroyer", 0, 0, 2, Horizontal)
+        .unwrap();
+    board.fire(0, 0);
+    board.fire(0, 1);
+    board.fire(5, 5);
+
+    let fresh = board.unfired();
+    assert_eq!(fresh.ships().len(), 1);
+    assert_eq!(fresh.ships()[0].hits, 0);
+    assert_eq!(fresh.cell(0, 0), CellState::Ship);
+    assert_eq!(fresh.cell(5, 5), CellState::Empty);
+    assert_eq!(fresh.stats().shots, 0);
+    assert!(board.is_game_over() && !fresh.is_game_over());
+}
+
+#[test]
+fn recorded_shots_mark_the_board_without_any_ships() {
+    let mut board = Board::new(5);
+    board.record_shot(0, 0, &ShotOutcome::Miss);
+    board.record_shot(1, 1, &ShotOutcome::Hit);
+    board.record_shot(1, 2, &ShotOutcome::Sunk("Destroyer".to_string()));
+    assert_eq!(board.cell(0, 0), CellState::Miss);
+    assert_eq!(board.cell(1, 1), CellState::Hit);
+    assert_eq!(board.cell(1, 2), CellState::Hit);
+    assert_eq!(board.stats().shots, 3);
+    assert_eq!(board.stats().hits, 2);
+}
+
+#[test]
+fn board_view_hides_ships_that_have_not_been_hit() {
+    let mut board = Board::new(5);
+    board
+        .place_ship_at("Destroyer", 0, 0, 2, Horizontal)
+        .unwrap();
+    board.place_ship_at("Cruiser", 2, 0, 3, Horizontal).unwrap();
+    board.fire(0, 0);
+    board.fire(0, 1);
+    board.fire(2, 0);
+    board.fire(4, 4);
+
+    let view = BoardView::new(&board);
+    assert_eq!(view.cell(2, 1), CellState::Empty);
+    assert_eq!(view.cell(2, 0), CellState::Hit);
+    assert_eq!(view.cell(4, 4), CellState::Miss);
+    assert!(view.is_sunk_at(0, 0));
+    assert!(!view.is_sunk_at(2, 0));
+    assert_eq!(view.remaining_ships(), vec![3]);
+    for row in 0..5 {
+        for col in 0..5 {
+            assert_ne!(view.cell(row, col), CellState::Ship);
+        }
+    }
+}
+
+#[test]
+fn firing_at_the_same_cell_again_changes_nothing() {
+    let mut board = Board::new(5);
+    board.place_ship_at("Cruiser", 0, 0, 3, Horizontal).unwrap();
+    assert!(!board.has_been_fired_at(0, 0));
+    assert_eq!(board.fire(0, 0), ShotOutcome::Hit);
+    assert_eq!(board.fire(4, 4), ShotOutcome::Miss);
+    let stats = board.stats();
+
+    assert_eq!(board.fire(0, 0), ShotOutcome::AlreadyFired);
+    assert_eq!(board.fire(4, 4), ShotOutcome::AlreadyFired);
+    assert_eq!(board.cell(0, 0), CellState::Hit);
+    assert_eq!(board.cell(4, 4), CellState::Miss);
+    assert!(board.has_been_fired_at(0, 0));
+    assert!(board.has_been_fired_at(4, 4));
+    assert_eq!(board.stats(), stats);
+    assert_eq!(board.ships()[0].hits, 1);
+}
+
+#[test]
+fn firing_off_the_board_is_refused_without_touching_it() {
+    let mut board = Board::new(5);
+    board.place_ship_at("Cruiser", 0, 2, 3, Horizontal).unwrap();
+    let before = board.clone();
+    for (row, col) in [(5, 0), (0, 5), (4, 7), (26, 26), (usize::MAX, 0)] {
+        assert_eq!(
+            board.fire(row, col),
+            ShotOutcome::OffBoard,
+            "({row}, {col})"
+        );
+        board.undo_shot(row, col);
+        board.record_shot(row, col, &ShotOutcome::Hit);
+    }
+    assert_eq!(board.stats(), before.stats());
+    assert_eq!(board.last_shot(), None);
+    for r in 0..5 {
+        for c in 0..5 {
+            assert_eq!(board.cell(r, c), before.cell(r, c));
+        }
+    }
+    assert!(!board.is_game_over());
+}
+
+#[test]
+fn fleets_parse_from_a_list_of_sizes() {
+    let fleet = FleetConfig::parse_list("5,4,3,3,2").unwrap();
+    assert_eq!(fleet, FleetConfig::standard());
+
+    let fleet = FleetConfig::parse_list("Flagship 6, 2, 2, 1").unwrap();
+    let names: Vec<&str> = fleet.ships.iter().map(|(name, _)| name.as_str()).collect();
+    assert_eq!(names, ["Flagship", "Destroyer", "Ship 3", "Ship 4"]);
+    assert_eq!(
+        fleet
+            .ships
+            .iter()
+            .map(|&(_, size)| size)
+            .collect::<Vec<_>>(),
+        [6, 2, 2, 1]
+    );
+
+    assert!(matches!(
+        FleetConfig::parse_list("5,,3"),
+        Err(FleetError::InvalidEntry(entry)) if entry.is_empty()
+    ));
+    assert!(matches!(
+        FleetConfig::parse_list("5,0"),
+        Err(FleetError::InvalidEntry(_))
+    ));
+}
+
+#[test]
+fn a_single_one_cell_ship_is_a_whole_game() {
+    let fleet = FleetConfig::parse_list("1").unwrap();
+    assert!(fleet.fits(2));
+    assert!(!fleet.fits(1));
+    let mut board = Board::new(2);
+    board
+        .place_fleet(&fleet, &mut StdRng::seed_from_u64(1))
+        .unwrap();
+    assert_eq!(board.remaining_ships(), vec![1]);
+    let (row, col) = board.ships()[0].origin;
+    assert!(!board.is_game_over());
+    assert!(matches!(board.fire(row, col), ShotOutcome::Sunk(_)));
+    assert!(board.is_game_over());
+    assert_eq!(board.ships_afloat(), 0);
+}
+
+#[test]
+fn torpedo_strikes_the_first_unhit_ship_part_along_its_lane() {
+    let mut board = Board::new(10);
+    board.place_ship_at("Cruiser", 4, 3, 3, Horizontal).unwrap();
+    board.place_ship_at("Destroyer", 6, 2, 2, Vertical).unwrap();
+
+    // Along a row from the left: past the miss at A4, into the Cruiser's first part
+    board.fire(4, 0);
+    assert_eq!(
+        board.fire_torpedo(Lane::Row(4)),
+        TorpedoOutcome::Struck {
+            row: 4,
+            col: 3,
+            outcome: ShotOutcome::Hit
+        }
+    );
+    // The next torpedo passes over that hit and strikes the part behind it
+    assert_eq!(
+        board.fire_torpedo(Lane::Row(4)),
+        TorpedoOutcome::Struck {
+            row: 4,
+            col: 4,
+            outcome: ShotOutcome::Hit
+        }
+    );
+    // Down a column from the top
+    board.fire(7, 2);
+    assert_eq!(
+        board.fire_torpedo(Lane::Column(2)),
+        TorpedoOutcome::Struck {
+            row: 6,
+            col: 2,
+            outcome: ShotOutcome::Sunk("Destroyer".to_string())
+        }
+    );
+    assert_eq!(board.last_shot(), Some((6, 2)));
+}
+
+#[test]
+fn torpedo_that_misses_leaves_the_board_unmarked() {
+    let mut board = Board::new(10);
+    board
+        .place_ship_at("Destroyer", 0, 0, 2, Horizontal)
+        .unwrap();
+    assert_eq!(board.fire_torpedo(Lane::Row(5)), TorpedoOutcome::Missed);
+    assert_eq!(board.fire_torpedo(Lane::Column(9)), TorpedoOutcome::Missed);
+    assert_eq!(board.fire_torpedo(Lane::Row(10)), TorpedoOutcome::Missed);
+    assert_eq!(board.stats().shots, 0);
+    assert!((0..10).all(|i| !board.has_been_fired_at(5, i) && !board.has_been_fired_at(i, 9)));
+}
+
+#[test]
+fn scan_reveals_a_three_by_three_area_clamped_to_the_board() {
+    let mut board = Board::new(10);
+    board.place_ship_at("Cruiser", 4, 3, 3, Horizontal).unwrap();
+    board.place_ship_at("Destroyer", 8, 9, 2, Vertical).unwrap();
+
+    // Centred on D5: rows 4 to 6 and columns C to E hold two of the Cruiser's parts
+    assert_eq!(board.scan(5, 3), 2);
+    assert!(board.is_revealed(4, 2) && board.is_revealed(6, 4));
+    assert!(!board.is_revealed(3, 3) && !board.is_revealed(5, 5));
+
+    // At the corner the area shifts inwards to H7..J9 instead of hanging off the board
+    assert_eq!(board.scan(9, 9), 2);
+    assert!(board.is_revealed(7, 7) && board.is_revealed(9, 9));
+    assert!(!board.is_revealed(6, 9) && !board.is_revealed(9, 6));
+
+    // Scanning doesn't fire, and hit ship parts no longer count
+    assert_eq!(board.stats().shots, 0);
+    board.fire(4, 3);
+    assert_eq!(board.scan(5, 3), 1);
+    assert!(BoardView::new(&board).is_revealed(4, 4));
+}
+
+#[test]
+fn sweep_tells_whether_a_lane_holds_an_unhit_ship_part_without_marking_it() {
+    let mut board = Board::new(10);
+    board.place_ship_at("Cruiser", 4, 3, 3, Horizontal).unwrap();
+    assert!(board.sweep(Lane::Row(4)));
+    assert!(board.sweep(Lane::Column(5)));
+    assert!(!board.sweep(Lane::Row(5)));
+    assert!(!board.sweep(Lane::Column(2)));
+    assert!(!board.sweep(Lane::Row(10)));
+
+    // Nothing is fired at or revealed, and a lane whose ship parts are all hit comes up empty
+    assert_eq!(board.stats().shots, 0);
+    assert!(!board.is_revealed(4, 3));
+    board.fire(4, 4);
+    assert!(!board.sweep(Lane::Column(4)));
+    assert!(board.sweep(Lane::Row(4)));
+}
+
+#[test]
+fn near_ship_checks_all_eight_neighbours_but_not_the_cell_itself() {
+    let mut board = Board::new(10);
+    board
+        .place_ship_at("Destroyer", 0, 0, 2, Horizontal)
+        .unwrap();
+    assert!(board.is_near_ship(1, 2));
+    assert!(board.is_near_ship(1, 0));
+    assert!(board.is_near_ship(0, 2));
+    assert!(!board.is_near_ship(0, 3));
+    assert!(!board.is_near_ship(2, 1));
+    assert!(!board.is_near_ship(10, 0));
+
+    // Hit ship parts still count, and a lone ship cell has no ship around it
+    board.fire(0, 1);
+    assert!(board.is_near_ship(1, 2));
+    board
+        .place_ship_at("Submarine", 5, 5, 1, Horizontal)
+        .unwrap();
+    assert!(!board.is_near_ship(5, 5));
+}
+
+#[test]
+fn scan_on_a_board_smaller_than_the_area_covers_all_of_it() {
+    let mut board = Board::new(2);
+    board
+        .place_ship_at("Destroyer", 1, 0, 2, Horizontal)
+        .unwrap();
+    assert_eq!(board.scan(0, 0), 2);
+    assert!((0..2).all(|r| (0..2).all(|c| board.is_revealed(r, c))));
+}
+
+#[test]
+fn diagonal_ships_run_down_to_either_side() {
+    let mut board = Board::new(10);
+    board.set_diagonal(true);
+    assert_eq!(
+        board.place_ship_at("Cruiser", 1, 2, 3, Orientation::DiagonalDownRight),
+        Ok(())
+    );
+    assert_eq!(
+        board.place_ship_at("Destroyer", 5, 9, 2, Orientation::DiagonalDownLeft),
+        Ok(())
+    );
+    assert_eq!(board.ships()[0].cells, vec![(1, 2), (2, 3), (3, 4)]);
+    assert_eq!(board.ships()[1].cells, vec![(5, 9), (6, 8)]);
+    assert_eq!(board.cell(2, 3), CellState::Ship);
+    assert_eq!(board.cell(2, 2), CellState::Empty);
+}
+
+#[test]
+fn diagonal_ships_must_stay_on_the_board() {
+    let mut board = Board::new(10);
+    board.set_diagonal(true);
+    assert_eq!(
+        board.place_ship_at("Carrier", 0, 6, 5, Orientation::DiagonalDownRight),
+        Err(PlacementError::OutOfBounds)
+    );
+    assert_eq!(
+        board.place_ship_at("Carrier", 6, 5, 5, Orientation::DiagonalDownRight),
+        Err(PlacementError::OutOfBounds)
+    );
+    assert_eq!(
+        board.place_ship_at("Carrier", 0, 3, 5, Orientation::DiagonalDownLeft),
+        Err(PlacementError::OutOfBounds)
+    );
+    assert_eq!(
+        board.place_ship_at("Carrier", 0, 4, 5, Orientation::DiagonalDownLeft),
+        Ok(())
+    );
+    assert_eq!(
+        board.place_ship_at("Destroyer", 2, 2, 2, Orientation::DiagonalDownRight),
+        Err(PlacementError::Overlap)
+    );
+}
+
+#[test]
+fn diagonal_ships_are_refused_unless_the_rule_is_on() {
+    let mut board = Board::new(10);
+    assert!(!board.diagonal());
+    assert_eq!(board.orientations(), &Orientation::ALL);
+    assert_eq!(
+        board.place_ship_at("Cruiser", 1, 2, 3, Orientation::DiagonalDownRight),
+        Err(PlacementError::Diagonal)
+    );
+    assert!(board.ships().is_empty());
+}
+
+#[test]
+fn random_fleets_with_diagonals_fit_without_touching() {
+    let fleet = FleetConfig::standard();
+    let mut rng = StdRng::seed_from_u64(1);
+    let mut diagonal_ships = 0;
+    for _ in 0..300 {
+        let mut board = Board::new(10);
+        board.set_diagonal(true);
+        board.set_no_touching(true);
+        board.place_fleet(&fleet, &mut rng).unwrap();
+        assert_eq!(board.ships().len(), FLEET.len());
+        for (i, ship) in board.ships().iter().enumerate() {
+            let ((r0, c0), (r1, c1)) = (ship.cells[0], ship.cells[1]);
+            if r0 != r1 && c0 != c1 {
+                diagonal_ships += 1;
+            }
+            for other in &board.ships()[i + 1..] {
+                for &(r, c) in &ship.cells {
+                    assert!(other
+                        .cells
+                        .iter()
+                        .all(|&(or, oc)| r.abs_diff(or) > 1 || c.abs_diff(oc) > 1));
+                }
+            }
+        }
+    }
+    assert!(diagonal_ships > 0);
+}
+
+#[test]
+fn land_blocks_placement_and_turns_shots_away() {
+    let mut board = Board::new(10);
+    board.raise_land(2, 4);
+    assert_eq!(board.cell(2, 4), CellState::Land);
+    assert_eq!(board.land(), vec![(2, 4)]);
+    assert_eq!(
+        board.place_ship_at("Cruiser", 2, 2, 3, Horizontal),
+        Err(PlacementError::Land)
+    );
+    assert_eq!(board.place_ship_at("Cruiser", 3, 2, 3, Horizontal), Ok(()));
+
+    // Land can't be raised under a ship
+    board.raise_land(3, 3);
+    assert_eq!(board.cell(3, 3), CellState::Ship);
+
+    assert_eq!(board.fire(2, 4), ShotOutcome::Land);
+    assert_eq!(board.fire(2, 4), ShotOutcome::Land);
+    assert_eq!(board.cell(2, 4), CellState::Land);
+    assert!(!board.has_been_fired_at(2, 4));
+    assert_eq!(board.stats().shots, 0);
+    assert_eq!(board.stats().misses, 0);
+    assert_eq!(board.last_shot(), None);
+}
+
+#[test]
+fn islands_always_leave_room_for_the_fleet() {
+    let fleet = FleetConfig::standard();
+    let mut rng = StdRng::seed_from_u64(4);
+    for _ in 0..20 {
+        let mut board = Board::new(10);
+        assert_eq!(board.raise_islands(20, &fleet, &mut rng), 20);
+        assert_eq!(board.land().len(), 20);
+        // Placing the fleet starts over as often as it needs to without washing the land away
+        board.place_fleet(&fleet, &mut rng).unwrap();
+        assert_eq!(board.land().len(), 20);
+        for ship in board.ships() {
+            assert!(ship.cells.iter().all(|cell| !board.land().contains(cell)));
+        }
+    }
+
+    // With far too much land asked for, there's less of it, but the fleet still fits
+    let fleet = FleetConfig::parse_list("4,3").unwrap();
+    let mut board = Board::new(5);
+    let raised = board.raise_islands(16, &fleet, &mut rng);
+    assert!(raised < 16);
+    assert_eq!(board.land().len(), raised);
+    board.place_fleet(&fleet, &mut rng).unwrap();
+}
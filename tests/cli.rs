@@ -0,0 +1,379 @@
+use std::env;
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::{self, Command, Stdio};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::thread;
+use std::time::Duration;
+
+// Counter that gives every game run by `run_game_with` its own scratch stats file.
+static RUNS: AtomicUsize = AtomicUsize::new(0);
+
+// Helper to build a path in the temp directory for a test's lifetime stats, so no test touches the real ones.
+fn stats_file(name: &str) -> PathBuf {
+    env::temp_dir().join(format!("battle_ship_stats_{}_{}.json", name, process::id()))
+}
+
+// Helper to run the game against the computer with the given lines typed in, returning everything it printed.
+fn run_game(input: &str) -> String {
+    run_game_with(&[], input)
+}
+
+// Helper to run the game against the computer with extra command-line flags and the given lines typed in. Any
+// lifetime stats go to a scratch file that is thrown away afterwards.
+fn run_game_with(flags: &[&str], input: &str) -> String {
+    let stats = stats_file(&format!("run{}", RUNS.fetch_add(1, Ordering::Relaxed)));
+    let output = run_game_keeping_stats(&stats, flags, input);
+    let _ = fs::remove_file(stats);
+    output
+}
+
+// Helper to run the game against the computer, keeping the lifetime stats in the given file.
+fn run_game_keeping_stats(stats: &Path, flags: &[&str], input: &str) -> String {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_battle_ship"))
+        .args(["--vs-computer", "--no-color", "--ascii", "--seed", "3"])
+        .args(flags)
+        .env("BATTLESHIP_STATS", stats)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+    child
+        .stdin
+        .take()
+        .unwrap()
+        .write_all(input.as_bytes())
+        .unwrap();
+    let output = child.wait_with_output().unwrap();
+    String::from_utf8_lossy(&output.stdout).into_owned()
+}
+
+// Helper to run the game against the computer, typing the first lines, then nothing for a while, then the
+// rest, with the input kept open in between.
+fn run_game_with_pause(flags: &[&str], before: &str, pause: Duration, after: &str) -> String {
+    let stats = stats_file(&format!("run{}", RUNS.fetch_add(1, Ordering::Relaxed)));
+    let mut child = Command::new(env!("CARGO_BIN_EXE_battle_ship"))
+        .args(["--vs-computer", "--no-color", "--ascii", "--seed", "3"])
+        .args(flags)
+        .env("BATTLESHIP_STATS", &stats)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+    let mut input = child.stdin.take().unwrap();
+    input.write_all(before.as_bytes()).unwrap();
+    thread::sleep(pause);
+    input.write_all(after.as_bytes()).unwrap();
+    drop(input);
+    let output = child.wait_with_output().unwrap();
+    let _ = fs::remove_file(stats);
+    String::from_utf8_lossy(&output.stdout).into_owned()
+}
+
+#[test]
+fn repeated_shot_is_refused_without_ending_the_turn() {
+    // Place the fleet at random, fire at A0, let the computer fire back, then try A0 again
+    let output = run_game("all\n\nA0\n\n\nA0\n");
+    assert!(output.contains("You already fired at A0 (it was a "));
+    let opponent_shots = output.matches("Opponent missed!").count()
+        + output.matches("Opponent hit").count()
+        + output.matches("Opponent sank").count();
+    assert_eq!(opponent_shots, 1);
+}
+
+#[test]
+fn previous_turns_are_summed_up_under_the_boards() {
+    // Fire at A0, let the computer fire back, then fire at B1 to get a third turn on screen
+    let output = run_game("all\n\nA0\n\n\nB1\n\n\n");
+    assert!(output.contains("You fired at A0: "));
+    assert!(output.contains("Opponent fired at "));
+    assert!(output.contains("You fired at B1: "));
+}
+
+#[test]
+fn undo_takes_back_the_last_turn_and_the_reply() {
+    // Undo before any shot, then fire at A0, let the computer reply, undo, and fire at A0 again
+    let output = run_game("all\n\nundo\nA0\n\n\nundo\nA0\n");
+    assert!(output.contains("There's nothing to undo yet."));
+    assert!(output.contains("Took back your last turn and the reply to it."));
+    assert!(!output.contains("You already fired at A0"));
+    assert_eq!(output.matches("You fired at A0: ").count(), 1);
+}
+
+#[test]
+fn reveal_only_works_in_debug_mode_and_keeps_the_turn() {
+    let output = run_game("all\n\nreveal\n");
+    assert!(!output.contains("The board you're firing at:"));
+
+    // The shot after the reveal is still this turn's
+    let output = run_game_with(&["--debug"], "all\n\nreveal\nA0\n");
+    assert!(output.contains("The board you're firing at:"));
+    assert!(output.contains("You missed!") || output.contains("You hit a ship!"));
+}
+
+#[test]
+fn the_latest_shot_is_bracketed_from_the_next_redraw() {
+    // The first board drawn has no shots to mark; after A0 and the reply, A0 is the latest shot at the
+    // opponent's board
+    let output = run_game("all\n\nA0\n\n\n");
+    let before = &output[..output.find("Press Enter to continue").unwrap()];
+    assert!(!before.contains("[o]") && !before.contains("[X]") && !before.contains("[*]"));
+    assert!(output.contains(" 0 [o]") || output.contains(" 0 [X]"));
+}
+
+#[test]
+fn a_game_that_reaches_the_turn_limit_is_a_draw() {
+    let output = run_game_with(&["--max-turns", "1"], "all\n\nA0\n\n\n");
+    assert!(output.contains("Turn 1 of 1"));
+    assert!(output.contains("The turn limit of 1 has been reached. The game is a draw!"));
+
+    let output = run_game("all\n\nA0\n\n\n");
+    assert!(output.contains("Turn 1 ") && output.contains("Turn 2 "));
+    assert!(!output.contains("draw"));
+}
+
+#[test]
+fn torpedoes_are_counted_down_and_handed_back_by_undo() {
+    // Fire the only torpedo, try another, undo the turn and fire it again
+    let output = run_game_with(
+        &["--torpedoes", "1"],
+        "all\n\ntorpedo row 3\n\n\ntorpedo column C\nundo\ntorpedo column C\n\n\n",
+    );
+    assert!(output.contains("Torpedoes left: 1"));
+    assert!(output.contains("You fired a torpedo along row 3."));
+    assert!(output.contains("Torpedoes left: 0"));
+    assert!(output.contains("You have no torpedoes left."));
+    assert!(output.contains("Took back your last turn and the reply to it."));
+    assert!(output.contains("You fired a torpedo along column C."));
+
+    let output = run_game("all\n\ntorpedo row 3\n");
+    assert!(!output.contains("Torpedoes left"));
+    assert!(output.contains("You have no torpedoes left."));
+}
+
+#[test]
+fn undoing_a_reply_that_sank_a_ship_gives_its_salvo_shot_back() {
+    // The computer's first salvo sinks the player's cruiser, leaving four shots until the turn is undone
+    let output = run_game_with(&["--salvo"], "all\n\nA0 B0 C0 D0 E0\n\n\nundo\n");
+    let sunk = output.find("Opponent sank your Cruiser!").unwrap();
+    let undone = output
+        .find("Took back your last turn and the reply to it.")
+        .unwrap();
+    assert!(output[sunk..undone].contains("Enter 4 coordinates"));
+    assert!(output[undone..].contains("Enter 5 coordinates"));
+}
+
+#[test]
+fn undo_is_refused_in_hotseat_mode() {
+    // Both players place and fire once, then Player 1 tries to take back Player 2's shot
+    let output = run_game_with(&["--hotseat"], "\nall\n\nall\n\n\nA0\n\n\nA0\n\n\nundo\n");
+    assert!(output.contains(
+        "Undo isn't available in hotseat mode, as it would take back the other player's turn."
+    ));
+    assert!(!output.contains("Took back your last turn"));
+}
+
+#[test]
+fn simulate_flag_plays_headless_games_and_prints_the_totals() {
+    let output = Command::new(env!("CARGO_BIN_EXE_battle_ship"))
+        .args(["--simulate", "20", "--seed", "1", "--p1", "random"])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("Games played: 20"));
+    assert!(stdout.contains("Easy") && stdout.contains("Normal"));
+    assert!(!stdout.contains("Your Board"));
+}
+
+#[test]
+fn scans_reveal_cells_without_using_up_the_turn() {
+    // Scan the top-left corner, try a scan off the board, then fire and use the last scan
+    let output = run_game_with(
+        &["--scans", "2"],
+        "all\n\nscan A0\nscan Q9\nA0\n\n\nscan J9\nscan B2\n",
+    );
+    assert!(output.contains("Scans left: 2"));
+    assert!(output.contains("The scan around A0 found "));
+    assert!(output.contains("Column Q is off the board."));
+    assert!(output.contains("You fired at A0: "));
+    assert!(output.contains("The scan around J9 found "));
+    assert!(output.contains("You have no scans left."));
+}
+
+#[test]
+fn rematches_keep_a_session_score() {
+    // Surrender twice, saying yes to the first rematch and no to the second
+    let output = run_game_with(&[], "all\n\nsurrender\nn\ny\nall\n\nsurrender\nn\nn\n");
+    assert_eq!(output.matches("Play again? [y/N]").count(), 2);
+    assert!(output.contains("Session score: You 0 - 1 Opponent"));
+    assert!(output.contains("Final score: You 0 - 2 Opponent"));
+    assert!(!output.contains("Goodbye"));
+}
+
+#[test]
+fn heat_rule_calls_a_miss_next_to_a_ship_close() {
+    // With seed 3 the opponent's ship in column E runs down the side of F0
+    let close = "You missed!\nClose! That shot landed right next to a ship.";
+    assert!(run_game_with(&["--heat"], "all\n\nF0\n").contains(close));
+    assert!(!run_game("all\n\nF0\n").contains("Close!"));
+}
+
+#[test]
+fn salvo_with_a_bad_cell_is_asked_for_again_in_full() {
+    // A repeated cell, then a good salvo, then one with a cell fired at last turn
+    let output = run_game_with(
+        &["--salvo"],
+        "all\n\nA0 A0 B1 C2 D3\nA0 B1 C2 D3 E4\n\n\nE0 B1 F1 J9\nE0 F1 G1 J9\n",
+    );
+    assert!(
+        output.contains("That salvo can't be fired, as some cells are listed more than once: A0.")
+    );
+    assert!(output.contains("some cells are already fired at: B1."));
+    assert_eq!(output.matches("Enter the whole salvo again.").count(), 2);
+    assert!(output
+        .contains("You fired at A0: miss, at B1: miss, at C2: miss, at D3: miss, at E4: miss."));
+    assert!(!output.contains("at B1: miss, at F1"));
+}
+
+#[test]
+fn hint_suggests_a_shot_without_taking_the_turn_and_is_counted() {
+    let output = run_game("all\n\nhint\nhint\nsurrender\nn\nn\n");
+    assert_eq!(output.matches("Hint: try ").count(), 2);
+    assert!(!output.contains("Opponent missed!"));
+    assert!(output.contains("Hints used by You: 2"));
+    assert!(!output.contains("Hints used by Opponent"));
+}
+
+#[test]
+fn each_turn_shows_the_running_totals() {
+    // Fire at A0, then see both shots counted on the next turn's line
+    let output = run_game("all\n\nA0\n\n\n");
+    assert!(output
+        .contains("Turn 1 - You: 0 shots, 0 hits - Opponent: 0 shots, 0 hits - 00:00 elapsed"));
+    assert!(output.contains("Turn 2 - You: 1 shots, 0 hits - Opponent: 1 shots, "));
+    assert!(output.contains("Opponent difficulty: Normal   Seed: 3"));
+}
+
+#[test]
+fn archipelago_shows_land_on_both_boards_and_shots_strike_it() {
+    // With seed 3 the opponent's board has land at B2
+    let output = run_game_with(&["--archipelago", "8"], "all\n\nB2\n");
+    assert!(output.contains("Place your Carrier (5 cells) as coordinate + H/V"));
+    assert!(output.contains(" 2     ^  "));
+    assert!(output.contains("  ^  Land"));
+    assert!(output.contains("Struck land!"));
+    assert!(!output.contains("You missed!"));
+}
+
+#[test]
+fn radar_sweep_takes_the_turn_and_only_says_yes_or_no() {
+    // Sweep row 3, which ends the turn, then find the sweep used up
+    let output = run_game_with(&["--sweeps", "1"], "all\n\nradar R3\n\n\nradar CC\nA0\n");
+    assert!(output.contains("Radar sweeps left: 1"));
+    assert!(output.contains("radar R3 or CC (1 left)"));
+    assert!(output.contains("You swept row 3 with radar."));
+    assert!(
+        output.contains("Ships detected in row 3.")
+            || output.contains("No ships detected in row 3.")
+    );
+    assert!(output.contains("Radar sweeps left: 0"));
+    assert!(output.contains("You have no radar sweeps left."));
+}
+
+// Helper to run the program with only the given flags and the lifetime stats in the given file.
+fn run_flags_keeping_stats(stats: &Path, flags: &[&str]) -> String {
+    let output = Command::new(env!("CARGO_BIN_EXE_battle_ship"))
+        .args(flags)
+        .env("BATTLESHIP_STATS", stats)
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    String::from_utf8_lossy(&output.stdout).into_owned()
+}
+
+#[test]
+fn finished_games_add_up_in_the_lifetime_stats() {
+    let stats = stats_file("lifetime");
+    let _ = fs::remove_file(&stats);
+    let output = run_game_keeping_stats(&stats, &[], "all\n\nsurrender\n");
+    assert!(output.contains("Lifetime: 0 wins / 1 losses in 1 games, no wins yet, best score: 0"));
+    // The record so far is shown as the next session starts, before any ships are placed
+    let output = run_game_keeping_stats(&stats, &[], "all\n\nsurrender\n");
+    let record = output
+        .find("Lifetime: 0 wins / 1 losses in 1 games")
+        .unwrap();
+    assert!(record < output.find("Your Board").unwrap());
+    assert!(output.contains("Lifetime: 0 wins / 2 losses in 2 games"));
+
+    let output = run_flags_keeping_stats(&stats, &["--stats"]);
+    assert!(output.contains("Lifetime: 0 wins / 2 losses"));
+    assert!(!output.contains("Your Board"));
+
+    // A corrupt file is started afresh with a warning rather than stopping the game
+    fs::write(&stats, "{ not json").unwrap();
+    let output = run_game_keeping_stats(&stats, &[], "all\n\nsurrender\n");
+    assert!(output.contains("Starting your lifetime stats afresh"));
+    assert!(output.contains("Lifetime: 0 wins / 1 losses"));
+
+    let output = run_flags_keeping_stats(&stats, &["--reset-stats"]);
+    assert!(output.contains("Lifetime stats reset."));
+    let output = run_flags_keeping_stats(&stats, &["--stats"]);
+    assert!(output.contains("Lifetime: 0 wins / 0 losses"));
+    fs::remove_file(&stats).unwrap();
+}
+
+#[test]
+fn running_out_of_time_forfeits_the_turn_to_the_computer() {
+    let output = run_game_with_pause(
+        &["--time-limit", "1", "--fast-delay", "0"],
+        "all\n",
+        Duration::from_millis(2500),
+        "surrender\nn\nn\n",
+    );
+    assert!(output.contains("[1s left] Enter coordinates to fire"));
+    assert!(output.contains("Time's up! The rest of this turn is forfeited."));
+    assert!(!output.contains("You missed!") && !output.contains("You hit"));
+    assert!(output.contains("Opponent missed!") || output.contains("Opponent hit"));
+}
+
+#[test]
+fn time_limit_must_be_a_positive_number_of_seconds() {
+    let output = Command::new(env!("CARGO_BIN_EXE_battle_ship"))
+        .args(["--vs-computer", "--time-limit", "0"])
+        .output()
+        .unwrap();
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("Invalid time limit '0'"));
+}
+
+#[test]
+fn a_game_loaded_mid_way_still_counts_in_the_lifetime_stats() {
+    let stats = stats_file("loaded");
+    let save = env::temp_dir().join(format!("battle_ship_loaded_{}.json", process::id()));
+    let _ = fs::remove_file(&stats);
+    let input = format!("all\n\nsave {0}\nload {0}\nsurrender\n", save.display());
+    let output = run_game_keeping_stats(&stats, &[], &input);
+    assert!(output.contains("Game saved to"));
+    assert!(output.contains("Lifetime: 0 wins / 1 losses in 1 games"));
+    let output = run_flags_keeping_stats(&stats, &["--stats"]);
+    assert!(output.contains("Lifetime: 0 wins / 1 losses"));
+    fs::remove_file(&stats).unwrap();
+    fs::remove_file(&save).unwrap();
+}
+
+#[test]
+fn help_lists_the_options_without_starting_a_game() {
+    let output = Command::new(env!("CARGO_BIN_EXE_battle_ship"))
+        .arg("--help")
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.starts_with("Usage: battle_ship"));
+    assert!(stdout.contains("--time-limit SECONDS"));
+    assert!(!stdout.contains("Your Board"));
+}
@@ -0,0 +1,235 @@
+use battle_ship::{
+    already_fired_warning, check_salvo, coordinate_label, is_valid_coord, parse_coordinates,
+    parse_lane, parse_salvo, parse_sweep, read_input, Board, BoardView, CellState, Lane,
+    Orientation, ParseError, SalvoError,
+};
+use std::io::{self, Cursor};
+
+#[test]
+fn parses_row_and_column_numbers() {
+    assert_eq!(parse_coordinates("3, 7", 10), Ok((3, 7)));
+    assert_eq!(parse_coordinates("0,9\n", 10), Ok((0, 9)));
+}
+
+#[test]
+fn parses_letter_and_row() {
+    assert_eq!(parse_coordinates("C4", 10), Ok((4, 2)));
+    assert_eq!(parse_coordinates("B5", 10), Ok((5, 1)));
+    assert_eq!(parse_coordinates("b5", 10), Ok((5, 1)));
+    assert_eq!(parse_coordinates("B 5", 10), Ok((5, 1)));
+    assert_eq!(parse_coordinates("  J9  ", 10), Ok((9, 9)));
+}
+
+#[test]
+fn rejects_out_of_range_letters_and_numbers() {
+    let column = |text: &str| Err(ParseError::ColumnOffBoard(text.to_string()));
+    let row = |text: &str| Err(ParseError::RowOffBoard(text.to_string()));
+    assert_eq!(parse_coordinates("Z9", 10), column("Z"));
+    assert_eq!(parse_coordinates("k0", 10), column("K"));
+    assert_eq!(parse_coordinates("A10", 10), row("10"));
+    assert_eq!(parse_coordinates("10, 0", 10), row("10"));
+    assert_eq!(parse_coordinates("0, 12", 10), column("12"));
+}
+
+#[test]
+fn rejects_malformed_input() {
+    let not_a_number = |text: &str| Err(ParseError::NotANumber(text.to_string()));
+    let cases = [
+        ("", Err(ParseError::WrongCount(0))),
+        ("3", Err(ParseError::WrongCount(1))),
+        ("C", Err(ParseError::WrongCount(1))),
+        ("1, 2, 3", Err(ParseError::WrongCount(3))),
+        ("4C", Err(ParseError::InvalidFormat)),
+        ("5B", Err(ParseError::InvalidFormat)),
+        ("a, b", not_a_number("a")),
+        ("B5x", not_a_number("5x")),
+        ("-1, 3", not_a_number("-1")),
+    ];
+    for (input, expected) in cases {
+        assert_eq!(parse_coordinates(input, 10), expected, "{input:?}");
+    }
+}
+
+#[test]
+fn tolerates_spacing_and_trailing_commas_but_not_negative_or_huge_numbers() {
+    let cases = [
+        ("  3 ,7  ", Ok((3, 7))),
+        ("3, 7,", Ok((3, 7))),
+        ("\t c 4 \n", Ok((4, 2))),
+        (",", Err(ParseError::WrongCount(0))),
+        ("3,", Err(ParseError::WrongCount(1))),
+        ("3, -7", Err(ParseError::NotANumber("-7".to_string()))),
+        ("+3, 7", Err(ParseError::NotANumber("+3".to_string()))),
+        (
+            "99999999999999999999999, 1",
+            Err(ParseError::RowOffBoard(
+                "99999999999999999999999".to_string(),
+            )),
+        ),
+        (
+            "A99999999999999999999999",
+            Err(ParseError::RowOffBoard(
+                "99999999999999999999999".to_string(),
+            )),
+        ),
+    ];
+    for (input, expected) in cases {
+        assert_eq!(parse_coordinates(input, 10), expected, "{input:?}");
+    }
+}
+
+#[test]
+fn parses_salvos_separated_by_spaces_or_semicolons() {
+    assert_eq!(
+        parse_salvo("A1 B2 c3", 10),
+        Ok(vec![(1, 0), (2, 1), (3, 2)])
+    );
+    assert_eq!(parse_salvo("3, 7; C4;", 10), Ok(vec![(3, 7), (4, 2)]));
+    assert_eq!(
+        parse_salvo("A1 Z2", 10),
+        Err(ParseError::ColumnOffBoard("Z".to_string()))
+    );
+    assert_eq!(parse_salvo("", 10), Ok(vec![]));
+}
+
+#[test]
+fn salvo_naming_a_cell_twice_is_refused_whole() {
+    let board = Board::new(10);
+    let view = BoardView::new(&board);
+    let salvo = parse_salvo("A1 B2 A1 C3 B2 A1", 10).unwrap();
+    let error = check_salvo(&salvo, &view).unwrap_err();
+    assert_eq!(
+        error,
+        SalvoError {
+            duplicates: vec![(1, 0), (2, 1)],
+            already_fired: vec![],
+        }
+    );
+    assert_eq!(error.to_string(), "listed more than once: A1, B2");
+    assert_eq!(
+        check_salvo(&parse_salvo("A1 B2 C3", 10).unwrap(), &view),
+        Ok(())
+    );
+}
+
+#[test]
+fn salvo_with_a_cell_already_fired_at_is_refused_whole() {
+    let mut board = Board::new(10);
+    board
+        .place_ship_at("Destroyer", 2, 1, 2, Orientation::Horizontal)
+        .unwrap();
+    board.fire(2, 1);
+    board.fire(5, 5);
+    let salvo = parse_salvo("B2 D4 F5 B2", 10).unwrap();
+    let error = check_salvo(&salvo, &BoardView::new(&board)).unwrap_err();
+    assert_eq!(error.duplicates, vec![(2, 1)]);
+    assert_eq!(error.already_fired, vec![(2, 1), (5, 5)]);
+    assert_eq!(
+        error.to_string(),
+        "listed more than once: B2; already fired at: B2, F5"
+    );
+    // Checking fires nothing, so the rest of the salvo is still open
+    assert!(!board.has_been_fired_at(4, 3));
+}
+
+#[test]
+fn parses_torpedo_lanes_on_the_board() {
+    assert_eq!(parse_lane("row 4", 10), Some(Lane::Row(4)));
+    assert_eq!(parse_lane("Column c", 10), Some(Lane::Column(2)));
+    assert_eq!(parse_lane("col J", 10), Some(Lane::Column(9)));
+    for input in [
+        "",
+        "row",
+        "row 10",
+        "column K",
+        "column 3",
+        "col CD",
+        "diagonal 3",
+        "row 4 5",
+    ] {
+        assert_eq!(parse_lane(input, 10), None, "{input:?}");
+    }
+}
+
+#[test]
+fn parses_radar_sweeps_in_short_or_long_form() {
+    assert_eq!(parse_sweep("R3", 10), Some(Lane::Row(3)));
+    assert_eq!(parse_sweep("cc", 10), Some(Lane::Column(2)));
+    assert_eq!(parse_sweep(" CJ ", 10), Some(Lane::Column(9)));
+    assert_eq!(parse_sweep("row 4", 10), Some(Lane::Row(4)));
+    assert_eq!(parse_sweep("col D", 10), Some(Lane::Column(3)));
+    for input in ["", "R", "C", "R10", "CK", "C3", "RC", "R 3 4", "row"] {
+        assert_eq!(parse_sweep(input, 10), None, "{input:?}");
+    }
+}
+
+#[test]
+fn labels_match_letter_and_row_notation() {
+    assert_eq!(coordinate_label(4, 2), "C4");
+    assert_eq!(parse_coordinates(&coordinate_label(9, 0), 10), Ok((9, 0)));
+}
+
+#[test]
+fn reading_past_the_end_of_input_fails() {
+    let mut input = Cursor::new("C4\n");
+    assert_eq!(read_input(&mut input).unwrap(), "C4\n");
+    let error = read_input(&mut input).unwrap_err();
+    assert_eq!(error.kind(), io::ErrorKind::UnexpectedEof);
+}
+
+#[test]
+fn valid_coordinates_stop_one_short_of_the_size() {
+    for size in [1, 10, 26] {
+        assert!(is_valid_coord(0, 0, size));
+        assert!(is_valid_coord(size - 1, size - 1, size));
+        assert!(!is_valid_coord(size, 0, size));
+        assert!(!is_valid_coord(0, size, size));
+    }
+}
+
+#[test]
+fn typed_coordinates_are_checked_against_the_board_size() {
+    for size in [5, 10, 26] {
+        let last = (b'A' + size as u8 - 1) as char;
+        let past = (b'A' + size as u8) as char;
+        assert_eq!(parse_coordinates("A0", size), Ok((0, 0)));
+        assert_eq!(
+            parse_coordinates(&format!("{}{}", last, size - 1), size),
+            Ok((size - 1, size - 1))
+        );
+        assert_eq!(
+            parse_coordinates(&format!("A{}", size), size),
+            Err(ParseError::RowOffBoard(size.to_string()))
+        );
+        assert_eq!(
+            parse_coordinates(&format!("{}, {}", size - 1, size - 1), size),
+            Ok((size - 1, size - 1))
+        );
+        assert_eq!(
+            parse_coordinates(&format!("{}, 0", size), size),
+            Err(ParseError::RowOffBoard(size.to_string()))
+        );
+        assert_eq!(
+            parse_coordinates(&format!("0, {}", size), size),
+            Err(ParseError::ColumnOffBoard(size.to_string()))
+        );
+        if size < 26 {
+            assert_eq!(
+                parse_coordinates(&format!("{}0", past), size),
+                Err(ParseError::ColumnOffBoard(past.to_string()))
+            );
+        }
+    }
+}
+
+#[test]
+fn repeated_shot_warning_names_the_cell_and_its_result() {
+    assert_eq!(
+        already_fired_warning(5, 1, CellState::Hit),
+        "You already fired at B5 (it was a hit)."
+    );
+    assert_eq!(
+        already_fired_warning(0, 0, CellState::Miss),
+        "You already fired at A0 (it was a miss)."
+    );
+}
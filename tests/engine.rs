@@ -0,0 +1,191 @@
+use battle_ship::{Board, FleetConfig, ShotOutcome, ENGINE_PROTOCOL_VERSION};
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use std::io::{BufRead, BufReader, Write};
+use std::process::{Child, ChildStdin, ChildStdout, Command, Stdio};
+
+// A bot on the other end of the engine's pipes, reading what the engine says and writing its own lines.
+struct Bot {
+    engine: Child,
+    to_engine: ChildStdin,
+    from_engine: BufReader<ChildStdout>,
+}
+
+impl Bot {
+    // Constructor starting the game in engine mode with a fixed seed.
+    fn start() -> Self {
+        Bot::start_with(&[])
+    }
+
+    // Constructor starting the game in engine mode with a fixed seed and extra command-line flags.
+    fn start_with(flags: &[&str]) -> Self {
+        let mut engine = Command::new(env!("CARGO_BIN_EXE_battle_ship"))
+            .args(["--engine", "--seed", "7"])
+            .args(flags)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::null())
+            .spawn()
+            .unwrap();
+        let to_engine = engine.stdin.take().unwrap();
+        let from_engine = BufReader::new(engine.stdout.take().unwrap());
+        Bot {
+            engine,
+            to_engine,
+            from_engine,
+        }
+    }
+
+    // Method to read the engine's next line.
+    fn hear(&mut self) -> String {
+        let mut line = String::new();
+        self.from_engine.read_line(&mut line).unwrap();
+        line.trim_end().to_string()
+    }
+
+    // Method to send the engine a line.
+    fn say(&mut self, line: &str) {
+        writeln!(self.to_engine, "{}", line).unwrap();
+    }
+
+    // Method to read the engine's greeting down to its request to place, returning the board size and fleet.
+    fn greet(&mut self) -> (usize, FleetConfig) {
+        assert_eq!(
+            self.hear(),
+            format!("battleship {}", ENGINE_PROTOCOL_VERSION)
+        );
+        let size = self.hear().strip_prefix("size ").unwrap().parse().unwrap();
+        let mut ships = Vec::new();
+        loop {
+            let line = self.hear();
+            if line == "place" {
+                break;
+            }
+            let (ship_size, name) = line.strip_prefix("ship ").unwrap().split_once(' ').unwrap();
+            ships.push((name.to_string(), ship_size.parse().unwrap()));
+        }
+        (size, FleetConfig { ships })
+    }
+
+    // Method to read the engine's next shot.
+    fn hear_fire(&mut self) -> (usize, usize) {
+        let line = self.hear();
+        let (row, col) = line.strip_prefix("fire ").unwrap().split_once(' ').unwrap();
+        (row.parse().unwrap(), col.parse().unwrap())
+    }
+}
+
+#[test]
+fn a_whole_game_can_be_played_through_the_engine_protocol() {
+    let mut bot = Bot::start();
+    let (size, fleet) = bot.greet();
+    assert_eq!(size, 10);
+    assert_eq!(fleet, FleetConfig::standard());
+    let mut board = Board::new(size);
+    board
+        .place_fleet(&fleet, &mut StdRng::seed_from_u64(11))
+        .unwrap();
+    bot.say("place");
+
+    // The bot fires at every cell in turn, and answers the engine's shots truthfully from its own board
+    let mut targets = (0..size).flat_map(|row| (0..size).map(move |col| (row, col)));
+    let mut engine_shots = Vec::new();
+    let mut engine_sank = 0;
+    let mut bot_sank = 0;
+    let winner = loop {
+        let line = bot.hear();
+        if let Some(winner) = line.strip_prefix("winner ") {
+            break winner.to_string();
+        }
+        let cell: Vec<usize> = line
+            .strip_prefix("fire ")
+            .unwrap()
+            .split(' ')
+            .map(|n| n.parse().unwrap())
+            .collect();
+        let (row, col) = (cell[0], cell[1]);
+        assert!(!engine_shots.contains(&(row, col)));
+        engine_shots.push((row, col));
+        let result = match board.fire(row, col) {
+            ShotOutcome::Miss => "result miss".to_string(),
+            ShotOutcome::Hit => "result hit".to_string(),
+            ShotOutcome::Sunk(name) => {
+                engine_sank += 1;
+                format!("result sunk {}", name)
+            }
+            outcome => panic!("the engine's shot came out as {:?}", outcome),
+        };
+        bot.say(&result);
+        if board.is_game_over() {
+            continue;
+        }
+
+        let (row, col) = targets.next().unwrap();
+        bot.say(&format!("fire {} {}", row, col));
+        let reply = bot.hear();
+        assert!(reply.starts_with("result "), "unexpected reply: {}", reply);
+        bot_sank += usize::from(reply.starts_with("result sunk "));
+    };
+
+    match winner.as_str() {
+        "engine" => assert!(board.is_game_over() && engine_sank == fleet.ships.len()),
+        "bot" => assert_eq!(bot_sank, fleet.ships.len()),
+        other => panic!("unexpected winner {}", other),
+    }
+    assert!(bot.engine.wait().unwrap().success());
+}
+
+#[test]
+fn bad_lines_are_answered_with_an_error_and_asked_for_again() {
+    let mut bot = Bot::start();
+    bot.greet();
+    bot.say("fire 0 0");
+    assert_eq!(bot.hear(), "error expected place");
+    bot.say("place");
+    assert!(bot.hear().starts_with("fire "));
+    bot.say("result maybe");
+    assert!(bot.hear().starts_with("error "));
+    bot.say("result sunk Rowboat");
+    assert_eq!(bot.hear(), "error no ship by that name is afloat");
+    bot.say("result miss");
+    bot.say("fire 10 0");
+    assert_eq!(bot.hear(), "error that cell is off the board");
+    bot.say("fire a b");
+    assert_eq!(bot.hear(), "error expected fire <row> <col>");
+    bot.say("fire 0 0");
+    assert!(bot.hear().starts_with("result "));
+    assert!(bot.hear().starts_with("fire "));
+    bot.say("result miss");
+    bot.say("fire 0 0");
+    assert_eq!(bot.hear(), "error already fired at that cell");
+    bot.say("quit");
+    assert!(bot.engine.wait().unwrap().success());
+}
+
+#[test]
+fn a_hard_engine_follows_up_a_hit_next_to_it() {
+    for difficulty in ["hard", "expert"] {
+        let mut bot = Bot::start_with(&["--difficulty", difficulty]);
+        bot.greet();
+        bot.say("place");
+        let hit = bot.hear_fire();
+        bot.say("result hit");
+        // Every shot after the hit misses, so the engine should keep trying the cells around it
+        for col in 0..3 {
+            bot.say(&format!("fire 9 {}", col));
+            assert!(bot.hear().starts_with("result "));
+            let (row, col) = bot.hear_fire();
+            assert_eq!(
+                row.abs_diff(hit.0) + col.abs_diff(hit.1),
+                1,
+                "{} fired at {:?} after a hit at {:?}",
+                difficulty,
+                (row, col),
+                hit
+            );
+            bot.say("result miss");
+        }
+        bot.say("quit");
+        assert!(bot.engine.wait().unwrap().success());
+    }
+}
@@ -0,0 +1,361 @@
+use battle_ship::{
+    Difficulty, FleetConfig, GameEvent, GameSettings, GameState, ShotOutcome, Side, TurnReport,
+};
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use std::cell::RefCell;
+use std::rc::Rc;
+
+// Helper to build settings for a seeded game against the computer on a standard board.
+fn settings() -> GameSettings {
+    GameSettings {
+        size: 10,
+        difficulty: Difficulty::Normal,
+        fleet: FleetConfig::standard(),
+        salvo: false,
+        hit_again: false,
+        heat: false,
+        no_touching: false,
+        spread: false,
+        diagonal: false,
+        land: 0,
+        hotseat: false,
+        seed: Some(21),
+        max_turns: None,
+        torpedoes: 0,
+        scans: 0,
+        sweeps: 0,
+        auto_advance: None,
+    }
+}
+
+// Helper to start a game with the given settings and the player's fleet placed from a fixed seed.
+fn new_game(settings: GameSettings) -> GameState {
+    let mut game = GameState::new(settings).unwrap();
+    game.player_board_mut()
+        .place_fleet(&FleetConfig::standard(), &mut StdRng::seed_from_u64(5))
+        .unwrap();
+    game
+}
+
+// Helper to list every cell of the opponent's fleet, ship by ship.
+fn opponent_ship_cells(game: &GameState) -> Vec<(usize, usize)> {
+    game.opponent_board()
+        .ships()
+        .iter()
+        .flat_map(|ship| ship.cells.clone())
+        .collect()
+}
+
+#[test]
+fn scripted_game_is_won_by_sinking_every_ship() {
+    let mut game = new_game(settings());
+    let targets = opponent_ship_cells(&game);
+    let mut opponent_shots = 0;
+    let mut last = None;
+    for (i, &(row, col)) in targets.iter().enumerate() {
+        let report = game.player_fire(row, col).unwrap();
+        assert!(matches!(
+            report.outcome,
+            ShotOutcome::Hit | ShotOutcome::Sunk(_)
+        ));
+        assert!(report.turn_over);
+        assert_eq!(game.turns(), i + 1);
+        if report.game_over {
+            last = Some(report);
+            break;
+        }
+        // The computer can't win in the time it takes to fire at every one of its ships' cells
+        let reply = game.opponent_take_turn().unwrap();
+        assert!(reply.turn_over && !reply.game_over);
+        opponent_shots += 1;
+    }
+
+    let last = last.unwrap();
+    assert_eq!(last.winner, Some(Side::Player));
+    assert!(matches!(last.outcome, ShotOutcome::Sunk(_)));
+    assert!(game.is_over());
+    assert_eq!(game.turns(), targets.len());
+    assert_eq!(opponent_shots, targets.len() - 1);
+    assert_eq!(game.history().len(), 2 * targets.len() - 1);
+    assert_eq!(game.player_fire(0, 0), None);
+    assert_eq!(game.opponent_take_turn(), None);
+}
+
+#[test]
+fn each_side_fires_only_on_its_own_turn() {
+    let mut game = new_game(settings());
+    assert_eq!(game.opponent_take_turn(), None);
+    let (row, col) = opponent_ship_cells(&game)[0];
+    assert!(game.player_fire(row, col).unwrap().turn_over);
+    assert!(!game.is_player_turn());
+    assert_eq!(game.player_fire(0, 0), None);
+    assert!(game.opponent_take_turn().unwrap().turn_over);
+    assert!(game.is_player_turn());
+}
+
+#[test]
+fn repeated_and_off_board_shots_keep_the_turn() {
+    let mut game = new_game(settings());
+    let (row, col) = opponent_ship_cells(&game)[0];
+    game.player_fire(row, col).unwrap();
+    game.opponent_take_turn().unwrap();
+
+    let repeat = game.player_fire(row, col).unwrap();
+    assert_eq!(repeat.outcome, ShotOutcome::AlreadyFired);
+    assert!(!repeat.turn_over);
+    assert_eq!(
+        game.player_fire(10, 0).unwrap().outcome,
+        ShotOutcome::OffBoard
+    );
+    assert!(game.is_player_turn());
+    assert_eq!(game.turns(), 1);
+    assert_eq!(game.history().len(), 2);
+}
+
+#[test]
+fn hits_keep_the_turn_under_the_hit_again_rule() {
+    let mut game = new_game(GameSettings {
+        hit_again: true,
+        ..settings()
+    });
+    let targets = opponent_ship_cells(&game);
+    for &(row, col) in &targets[..3] {
+        assert!(!game.player_fire(row, col).unwrap().turn_over);
+    }
+    let water = (0..10)
+        .flat_map(|r| (0..10).map(move |c| (r, c)))
+        .find(|cell| !targets.contains(cell))
+        .unwrap();
+    let miss = game.player_fire(water.0, water.1).unwrap();
+    assert_eq!(miss.outcome, ShotOutcome::Miss);
+    assert!(miss.turn_over);
+    assert_eq!(game.turns(), 1);
+}
+
+#[test]
+fn salvo_turns_last_one_shot_per_surviving_ship() {
+    let mut game = new_game(GameSettings {
+        salvo: true,
+        ..settings()
+    });
+    let targets = opponent_ship_cells(&game);
+    let reports: Vec<TurnReport> = targets[..5]
+        .iter()
+        .map(|&(row, col)| game.player_fire(row, col).unwrap())
+        .collect();
+    assert!(reports[..4].iter().all(|report| !report.turn_over));
+    assert!(reports[4].turn_over);
+    assert_eq!(game.turns(), 1);
+}
+
+#[test]
+fn reaching_the_turn_limit_ends_the_game_in_a_draw() {
+    let mut game = new_game(GameSettings {
+        max_turns: Some(2),
+        ..settings()
+    });
+    let targets = opponent_ship_cells(&game);
+    for &(row, col) in &targets[..2] {
+        game.player_fire(row, col).unwrap();
+        let reply = game.opponent_take_turn().unwrap();
+        assert_eq!(reply.winner, None);
+        assert_eq!(reply.game_over, game.turns() == 2);
+    }
+    assert!(game.is_over());
+    assert_eq!(game.player_fire(targets[2].0, targets[2].1), None);
+}
+
+#[test]
+fn events_follow_every_shot_and_the_end_of_the_game() {
+    let mut game = new_game(settings());
+    let events = Rc::new(RefCell::new(Vec::new()));
+    let log = Rc::clone(&events);
+    game.set_event_callback(move |event| log.borrow_mut().push(event));
+
+    let ship = game.opponent_board().ships()[0].clone();
+    let (row, col) = ship.cells[0];
+    game.player_fire(row, col).unwrap();
+    // Both fleets are announced before the first shot
+    let placed = 2 * FleetConfig::standard().ships.len();
+    assert_eq!(
+        events.borrow()[placed..],
+        [
+            GameEvent::ShotFired {
+                side: Side::Player,
+                row,
+                col,
+                outcome: ShotOutcome::Hit,
+                torpedo: None,
+            },
+            GameEvent::ShipHit {
+                side: Side::Player,
+                row,
+                col
+            },
+        ]
+    );
+
+    let targets = opponent_ship_cells(&game);
+    for &(row, col) in &targets[1..] {
+        if !game.is_player_turn() {
+            game.opponent_take_turn().unwrap();
+        }
+        game.player_fire(row, col).unwrap();
+    }
+    let events = events.borrow();
+    let sunk: Vec<&GameEvent> = events
+        .iter()
+        .filter(|event| {
+            matches!(
+                event,
+                GameEvent::ShipSunk {
+                    side: Side::Player,
+                    ..
+                }
+            )
+        })
+        .collect();
+    assert_eq!(sunk.len(), FleetConfig::standard().ships.len());
+    assert_eq!(
+        sunk[0],
+        &GameEvent::ShipSunk {
+            side: Side::Player,
+            name: ship.name.clone()
+        }
+    );
+    assert_eq!(
+        events.last(),
+        Some(&GameEvent::GameOver {
+            winner: Some(Side::Player)
+        })
+    );
+}
+
+#[test]
+fn a_scripted_game_produces_its_events_in_order() {
+    let fleet = FleetConfig {
+        ships: vec![("Destroyer".to_string(), 2)],
+    };
+    let mut game = GameState::new(GameSettings {
+        fleet: fleet.clone(),
+        ..settings()
+    })
+    .unwrap();
+    game.player_board_mut()
+        .place_fleet(&fleet, &mut StdRng::seed_from_u64(5))
+        .unwrap();
+    let events = Rc::new(RefCell::new(Vec::new()));
+    let log = Rc::clone(&events);
+    game.set_event_callback(move |event| log.borrow_mut().push(event));
+
+    let own = game.player_board().ships()[0].cells.clone();
+    let theirs = game.opponent_board().ships()[0].cells.clone();
+    let mut expected = vec![
+        GameEvent::ShipPlaced {
+            side: Side::Player,
+            name: "Destroyer".to_string(),
+            cells: own,
+        },
+        GameEvent::ShipPlaced {
+            side: Side::Opponent,
+            name: "Destroyer".to_string(),
+            cells: theirs.clone(),
+        },
+    ];
+    // The player misses, then sinks the computer's destroyer a cell at a time, with the computer firing back
+    // after each of the player's turns but the last
+    let (row, col) = (0..10)
+        .flat_map(|row| (0..10).map(move |col| (row, col)))
+        .find(|cell| !theirs.contains(cell))
+        .unwrap();
+    let shots = [
+        ((row, col), ShotOutcome::Miss),
+        (theirs[0], ShotOutcome::Hit),
+        (theirs[1], ShotOutcome::Sunk("Destroyer".to_string())),
+    ];
+    for ((row, col), outcome) in shots {
+        game.player_fire(row, col).unwrap();
+        expected.push(GameEvent::ShotFired {
+            side: Side::Player,
+            row,
+            col,
+            outcome: outcome.clone(),
+            torpedo: None,
+        });
+        if outcome != ShotOutcome::Miss {
+            expected.push(GameEvent::ShipHit {
+                side: Side::Player,
+                row,
+                col,
+            });
+        }
+        if game.is_over() {
+            break;
+        }
+        let reply = game.opponent_take_turn().unwrap();
+        let shot = game.history().last().unwrap().clone();
+        expected.push(GameEvent::ShotFired {
+            side: Side::Opponent,
+            row: shot.row,
+            col: shot.col,
+            outcome: reply.outcome.clone(),
+            torpedo: None,
+        });
+        if reply.outcome == ShotOutcome::Hit {
+            expected.push(GameEvent::ShipHit {
+                side: Side::Opponent,
+                row: shot.row,
+                col: shot.col,
+            });
+        }
+    }
+    expected.push(GameEvent::ShipSunk {
+        side: Side::Player,
+        name: "Destroyer".to_string(),
+    });
+    expected.push(GameEvent::GameOver {
+        winner: Some(Side::Player),
+    });
+    assert_eq!(*events.borrow(), expected);
+}
+
+#[test]
+fn a_miss_next_to_a_ship_is_a_near_miss_under_the_heat_rule() {
+    let mut game = new_game(GameSettings {
+        heat: true,
+        ..settings()
+    });
+    let events = Rc::new(RefCell::new(Vec::new()));
+    let log = Rc::clone(&events);
+    game.set_event_callback(move |event| log.borrow_mut().push(event));
+
+    let ships = opponent_ship_cells(&game);
+    let board = game.opponent_board();
+    let mut cells = (0..10).flat_map(|row| (0..10).map(move |col| (row, col)));
+    let (row, col) = cells
+        .clone()
+        .find(|&(row, col)| !ships.contains(&(row, col)) && board.is_near_ship(row, col))
+        .unwrap();
+    let (far_row, far_col) = cells
+        .find(|&(row, col)| !ships.contains(&(row, col)) && !board.is_near_ship(row, col))
+        .unwrap();
+    game.player_fire(row, col).unwrap();
+    assert_eq!(
+        events.borrow().last(),
+        Some(&GameEvent::NearMiss {
+            side: Side::Player,
+            row,
+            col
+        })
+    );
+    game.opponent_take_turn().unwrap();
+    game.player_fire(far_row, far_col).unwrap();
+    assert!(matches!(
+        events.borrow().last(),
+        Some(GameEvent::ShotFired {
+            outcome: ShotOutcome::Miss,
+            ..
+        })
+    ));
+}
@@ -0,0 +1,83 @@
+use battle_ship::{load_stats, save_stats, LifetimeStats, Side, StatsError};
+use std::env;
+use std::fs;
+use std::path::PathBuf;
+
+// Helper to build a unique path in the temp directory for a test's stats file.
+fn temp_path(name: &str) -> PathBuf {
+    env::temp_dir()
+        .join(format!("battle_ship_lifetime_{}", std::process::id()))
+        .join(format!("{}.json", name))
+}
+
+#[test]
+fn wins_losses_and_the_best_game_are_tallied() {
+    let mut stats = LifetimeStats::default();
+    assert_eq!(
+        stats.to_string(),
+        "Lifetime: 0 wins / 0 losses in 0 games, no wins yet"
+    );
+    stats.record(Some(Side::Player), 50, 1080);
+    stats.record(Some(Side::Opponent), 70, -150);
+    stats.record(Some(Side::Player), 34, 1210);
+    stats.record(Some(Side::Player), 41, 1250);
+    stats.record(None, 30, 40);
+    assert_eq!(stats.games, 5);
+    assert_eq!(stats.wins, 3);
+    assert_eq!(stats.losses, 1);
+    assert_eq!(stats.shots, 225);
+    assert_eq!(stats.best_game, Some(34));
+    assert_eq!(stats.best_score, Some(1250));
+    assert_eq!(
+        stats.to_string(),
+        "Lifetime: 3 wins / 1 losses in 5 games, best game: 34 shots, best score: 1250"
+    );
+}
+
+#[test]
+fn stats_round_trip_through_a_new_directory() {
+    let path = temp_path("round_trip");
+    let mut stats = LifetimeStats::default();
+    stats.record(Some(Side::Player), 40, 1100);
+    save_stats(&path, &stats).unwrap();
+    let loaded = load_stats(&path);
+    fs::remove_dir_all(path.parent().unwrap()).unwrap();
+    assert_eq!(loaded.unwrap(), stats);
+}
+
+#[test]
+fn missing_stats_start_empty_and_corrupt_ones_are_reported() {
+    let path = temp_path("missing");
+    assert_eq!(load_stats(&path).unwrap(), LifetimeStats::default());
+
+    let path = env::temp_dir().join(format!(
+        "battle_ship_lifetime_corrupt_{}.json",
+        std::process::id()
+    ));
+    fs::write(&path, "[1, 2").unwrap();
+    let result = load_stats(&path);
+    fs::remove_file(&path).unwrap();
+    assert!(matches!(result, Err(StatsError::Json(_))));
+}
+
+#[test]
+fn stats_from_before_games_were_counted_count_their_wins_and_losses() {
+    let path = env::temp_dir().join(format!(
+        "battle_ship_lifetime_old_{}.json",
+        std::process::id()
+    ));
+    fs::write(
+        &path,
+        r#"{"wins": 2, "losses": 3, "shots": 200, "best_game": 40}"#,
+    )
+    .unwrap();
+    let result = load_stats(&path);
+    fs::remove_file(&path).unwrap();
+    let stats = result.unwrap();
+    assert_eq!(stats.games, 5);
+    assert_eq!(stats.best_score, None);
+    assert_eq!(
+        stats.to_string(),
+        "Lifetime: 2 wins / 3 losses in 5 games, best game: 40 shots"
+    );
+}
@@ -0,0 +1,402 @@
+use battle_ship::{
+    commitment, placements, sha256_hex, spectate, verify_reveal, Board, CheatError, Connection,
+    Difficulty, FleetConfig, GameSettings, Message, Move, NetError, Placement, ShotOutcome, Side,
+    PROTOCOL_VERSION,
+};
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use std::io::Write;
+use std::net::{TcpListener, TcpStream};
+use std::sync::mpsc;
+use std::thread;
+
+// Helper to open a connection to a local peer, handing the peer's end of the socket to `peer` on another
+// thread.
+fn connect_to(peer: impl FnOnce(TcpStream) + Send + 'static) -> Connection {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let address = listener.local_addr().unwrap();
+    thread::spawn(move || peer(listener.accept().unwrap().0));
+    Connection::connect(&address.to_string()).unwrap()
+}
+
+// Helper to build the settings of a network game on a standard board.
+fn settings() -> GameSettings {
+    GameSettings {
+        size: 10,
+        difficulty: Difficulty::Normal,
+        fleet: FleetConfig::standard(),
+        salvo: false,
+        hit_again: false,
+        heat: false,
+        no_touching: false,
+        spread: false,
+        diagonal: false,
+        land: 0,
+        hotseat: false,
+        seed: None,
+        max_turns: None,
+        torpedoes: 0,
+        scans: 0,
+        sweeps: 0,
+        auto_advance: None,
+    }
+}
+
+// The cells one side fired at, in order, with the results the other side reported.
+type Transcript = Vec<((usize, usize), ShotOutcome)>;
+
+// Helper to place a standard fleet from a seed and fire at every cell along the main diagonal and the row
+// below it, returning the placements and the honest transcript of results.
+fn honest_game(seed: u64) -> (Vec<Placement>, Transcript) {
+    let mut board = Board::new(10);
+    board
+        .place_fleet(&FleetConfig::standard(), &mut StdRng::seed_from_u64(seed))
+        .unwrap();
+    let fleet = placements(&board);
+    let shots = (0..10)
+        .flat_map(|i| [(i, i), (9, i)])
+        .map(|(row, col)| ((row, col), board.fire(row, col)))
+        .collect();
+    (fleet, shots)
+}
+
+// Helper to make a plain shot for a spectator to be sent.
+fn shot(turn: usize, side: Side, row: usize, col: usize, outcome: ShotOutcome) -> Move {
+    Move {
+        turn,
+        side,
+        row,
+        col,
+        outcome,
+        torpedo: None,
+    }
+}
+
+// Helper to connect to a hosted game as a spectator, without drawing anything.
+fn join_as_spectator(address: &str) -> Connection {
+    let mut connection = Connection::connect(address).unwrap();
+    connection
+        .send(&Message::Spectate {
+            version: PROTOCOL_VERSION,
+        })
+        .unwrap();
+    connection
+}
+
+// Helper to write a raw line to the peer's socket.
+fn send_line(stream: &mut TcpStream, line: &str) {
+    writeln!(stream, "{}", line).unwrap();
+}
+
+#[test]
+fn messages_cross_the_connection_unchanged() {
+    let mut connection = connect_to(|stream| {
+        let mut peer = Connection::new(stream).unwrap();
+        // Echo everything back until the other side sends GameOver
+        loop {
+            let message = peer.receive().unwrap();
+            peer.send(&message).unwrap();
+            if message == Message::GameOver {
+                break;
+            }
+        }
+    });
+    let messages = [
+        Message::Fire { row: 3, col: 9 },
+        Message::Result {
+            outcome: ShotOutcome::Sunk("Destroyer".to_string()),
+        },
+        Message::GameOver,
+    ];
+    for message in messages {
+        connection.send(&message).unwrap();
+        assert_eq!(connection.receive().unwrap(), message);
+    }
+}
+
+#[test]
+fn handshake_succeeds_when_both_games_match() {
+    let mut connection = connect_to(|stream| {
+        Connection::new(stream)
+            .unwrap()
+            .handshake(10, &FleetConfig::standard())
+            .unwrap();
+    });
+    assert!(connection.handshake(10, &FleetConfig::standard()).is_ok());
+}
+
+#[test]
+fn handshake_rejects_another_protocol_version() {
+    let mut connection = connect_to(|mut stream| {
+        let hello = Message::Hello {
+            version: PROTOCOL_VERSION + 1,
+            size: 10,
+            fleet: FleetConfig::standard(),
+        };
+        send_line(&mut stream, &serde_json::to_string(&hello).unwrap());
+    });
+    assert!(matches!(
+        connection.handshake(10, &FleetConfig::standard()),
+        Err(NetError::VersionMismatch(version)) if version == PROTOCOL_VERSION + 1
+    ));
+}
+
+#[test]
+fn handshake_rejects_a_different_board_size() {
+    let mut connection = connect_to(|stream| {
+        let _ = Connection::new(stream)
+            .unwrap()
+            .handshake(12, &FleetConfig::standard());
+    });
+    assert!(matches!(
+        connection.handshake(10, &FleetConfig::standard()),
+        Err(NetError::Mismatch(_))
+    ));
+}
+
+#[test]
+fn malformed_messages_are_reported() {
+    let mut connection = connect_to(|mut stream| send_line(&mut stream, "FIRE AT WILL"));
+    assert!(matches!(
+        connection.receive(),
+        Err(NetError::Malformed(text)) if text == "FIRE AT WILL"
+    ));
+}
+
+#[test]
+fn a_closed_connection_is_reported_as_a_disconnect() {
+    let mut connection = connect_to(drop);
+    assert!(matches!(connection.receive(), Err(NetError::Disconnected)));
+}
+
+#[test]
+fn sha256_matches_the_published_test_vectors() {
+    assert_eq!(
+        sha256_hex(b""),
+        "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855"
+    );
+    assert_eq!(
+        sha256_hex(b"abc"),
+        "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+    );
+    assert_eq!(
+        sha256_hex(b"abcdbcdecdefdefgefghfghighijhijkijkljklmklmnlmnomnopnopq"),
+        "248d6a61d20638b8e5c026930c3e6039a33ce45964ff2167f6ecedd419db06c1"
+    );
+}
+
+#[test]
+fn commitment_ignores_ship_order_but_not_the_salt() {
+    let (fleet, _) = honest_game(4);
+    let mut reordered = fleet.clone();
+    reordered.reverse();
+    assert_eq!(commitment(&fleet, "salt"), commitment(&reordered, "salt"));
+    assert_ne!(commitment(&fleet, "salt"), commitment(&fleet, "pepper"));
+}
+
+#[test]
+fn honest_transcript_passes_verification() {
+    let (fleet, shots) = honest_game(4);
+    let hash = commitment(&fleet, "a1b2");
+    assert_eq!(
+        verify_reveal(&hash, &fleet, "a1b2", &settings(), &shots),
+        Ok(())
+    );
+}
+
+#[test]
+fn tampered_result_is_caught() {
+    let (fleet, mut shots) = honest_game(4);
+    let hash = commitment(&fleet, "a1b2");
+    let index = shots
+        .iter()
+        .position(|(_, outcome)| *outcome != ShotOutcome::Miss)
+        .unwrap();
+    let ((row, col), actual) = shots[index].clone();
+    shots[index].1 = ShotOutcome::Miss;
+    assert_eq!(
+        verify_reveal(&hash, &fleet, "a1b2", &settings(), &shots),
+        Err(CheatError::Result {
+            row,
+            col,
+            reported: ShotOutcome::Miss,
+            actual,
+        })
+    );
+}
+
+#[test]
+fn board_swapped_after_committing_is_caught() {
+    let (fleet, _) = honest_game(4);
+    let (other_fleet, other_shots) = honest_game(5);
+    let hash = commitment(&fleet, "a1b2");
+    assert_eq!(
+        verify_reveal(&hash, &other_fleet, "a1b2", &settings(), &other_shots),
+        Err(CheatError::Commitment)
+    );
+    assert_eq!(
+        verify_reveal(&hash, &fleet, "b2a1", &settings(), &[]),
+        Err(CheatError::Commitment)
+    );
+}
+
+#[test]
+fn illegal_or_short_fleet_is_caught_even_with_a_matching_hash() {
+    let (mut fleet, _) = honest_game(4);
+    fleet[1].row = fleet[0].row;
+    fleet[1].col = fleet[0].col;
+    fleet[1].orientation = fleet[0].orientation;
+    let hash = commitment(&fleet, "a1b2");
+    assert!(matches!(
+        verify_reveal(&hash, &fleet, "a1b2", &settings(), &[]),
+        Err(CheatError::Board(_))
+    ));
+
+    let (mut fleet, _) = honest_game(4);
+    fleet.pop();
+    let hash = commitment(&fleet, "a1b2");
+    assert!(matches!(
+        verify_reveal(&hash, &fleet, "a1b2", &settings(), &[]),
+        Err(CheatError::Board(_))
+    ));
+}
+
+#[test]
+fn spectators_see_every_shot_and_late_ones_catch_up_from_a_snapshot() {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let address = listener.local_addr().unwrap().to_string();
+    let first = shot(1, Side::Player, 2, 3, ShotOutcome::Hit);
+    let second = shot(1, Side::Opponent, 0, 0, ShotOutcome::Miss);
+    let ended = Message::Ended {
+        host_fleet: Vec::new(),
+        guest_fleet: Vec::new(),
+    };
+    let (fired, fired_first) = mpsc::channel();
+    let (caught_up, late_caught_up) = mpsc::channel();
+    let host = {
+        let (first, second, ended) = (first.clone(), second.clone(), ended.clone());
+        thread::spawn(move || {
+            let mut host = Connection::accept(listener).unwrap();
+            // The guest's Hello is still there for the handshake after the spectator was taken in
+            assert!(matches!(host.receive().unwrap(), Message::Hello { .. }));
+            host.broadcast(Message::Snapshot {
+                size: 10,
+                moves: Vec::new(),
+            });
+            host.broadcast(Message::Shot { shot: first });
+            fired.send(()).unwrap();
+            late_caught_up.recv().unwrap();
+            host.broadcast(Message::Shot { shot: second });
+            host.broadcast(ended);
+        })
+    };
+
+    // One spectator is waiting before the guest arrives
+    let mut early = join_as_spectator(&address);
+    let mut guest = Connection::connect(&address).unwrap();
+    guest
+        .send(&Message::Hello {
+            version: PROTOCOL_VERSION,
+            size: 10,
+            fleet: FleetConfig::standard(),
+        })
+        .unwrap();
+    fired_first.recv().unwrap();
+    // One that drops straight away doesn't hold anything up
+    drop(join_as_spectator(&address));
+    let mut late = join_as_spectator(&address);
+    assert_eq!(
+        late.receive().unwrap(),
+        Message::Snapshot {
+            size: 10,
+            moves: vec![first.clone()],
+        }
+    );
+    caught_up.send(()).unwrap();
+    host.join().unwrap();
+
+    for message in [
+        Message::Snapshot {
+            size: 10,
+            moves: Vec::new(),
+        },
+        Message::Shot {
+            shot: first.clone(),
+        },
+        Message::Shot {
+            shot: second.clone(),
+        },
+        ended.clone(),
+    ] {
+        assert_eq!(early.receive().unwrap(), message);
+    }
+    assert_eq!(late.receive().unwrap(), Message::Shot { shot: second });
+    assert_eq!(late.receive().unwrap(), ended);
+    drop(guest);
+}
+
+#[test]
+fn spectating_follows_the_game_to_the_revealed_boards() {
+    let (fleet, _) = honest_game(4);
+    let target = fleet[0].clone();
+    let mut connection = connect_to(move |stream| {
+        let mut host = Connection::new(stream).unwrap();
+        assert_eq!(
+            host.receive().unwrap(),
+            Message::Spectate {
+                version: PROTOCOL_VERSION
+            }
+        );
+        host.send(&Message::Snapshot {
+            size: 10,
+            moves: vec![shot(1, Side::Player, 9, 9, ShotOutcome::Miss)],
+        })
+        .unwrap();
+        host.send(&Message::Shot {
+            shot: shot(1, Side::Opponent, target.row, target.col, ShotOutcome::Hit),
+        })
+        .unwrap();
+        host.send(&Message::Ended {
+            host_fleet: fleet,
+            guest_fleet: Vec::new(),
+        })
+        .unwrap();
+    });
+    assert!(spectate(&mut connection).is_ok());
+}
+
+#[test]
+fn spectating_a_game_that_sends_nonsense_is_an_error() {
+    let mut connection = connect_to(|stream| {
+        let mut host = Connection::new(stream).unwrap();
+        host.receive().unwrap();
+        host.send(&Message::Fire { row: 1, col: 1 }).unwrap();
+    });
+    assert!(matches!(
+        spectate(&mut connection),
+        Err(NetError::Unexpected(_))
+    ));
+}
+
+#[test]
+fn hosting_drops_silent_and_confused_connections_and_waits_for_the_guest() {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let address = listener.local_addr().unwrap().to_string();
+    let host = thread::spawn(move || {
+        let mut host = Connection::accept(listener).unwrap();
+        host.handshake(10, &FleetConfig::standard())
+    });
+
+    // Someone who connects and says nothing, like a port scan, is given up on after a while
+    let _silent = TcpStream::connect(&address).unwrap();
+    let mut garbled = TcpStream::connect(&address).unwrap();
+    send_line(&mut garbled, "not a message");
+    let mut out_of_turn = TcpStream::connect(&address).unwrap();
+    send_line(
+        &mut out_of_turn,
+        &serde_json::to_string(&Message::Fire { row: 0, col: 0 }).unwrap(),
+    );
+    let mut guest = Connection::connect(&address).unwrap();
+    assert!(guest.handshake(10, &FleetConfig::standard()).is_ok());
+    assert!(host.join().unwrap().is_ok());
+}
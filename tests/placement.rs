@@ -0,0 +1,162 @@
+use battle_ship::{Board, CellState, FleetConfig, Orientation, PlacementError, MAX_BOARD_SIZE};
+use proptest::prelude::*;
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use std::collections::HashSet;
+
+// Strategy for one requested placement: a ship size, a starting cell that may lie past the board's edge, and
+// an orientation, diagonals included.
+fn placement() -> impl proptest::strategy::Strategy<Value = (usize, usize, usize, Orientation)> {
+    (
+        1..=6usize,
+        0..=MAX_BOARD_SIZE + 2,
+        0..=MAX_BOARD_SIZE + 2,
+        proptest::sample::select(Orientation::WITH_DIAGONALS.to_vec()),
+    )
+}
+
+// Helper to check that every ship lies wholly on the board, that no two ships share a cell, and that the grid
+// marks exactly the ships' cells.
+fn assert_ships_fit(board: &Board) {
+    let mut occupied = HashSet::new();
+    for ship in board.ships() {
+        assert_eq!(ship.cells.len(), ship.size);
+        for &(r, c) in &ship.cells {
+            assert!(
+                r < board.size() && c < board.size(),
+                "{} leaves the board",
+                ship.name
+            );
+            assert!(
+                occupied.insert((r, c)),
+                "{} overlaps another ship",
+                ship.name
+            );
+        }
+    }
+    for r in 0..board.size() {
+        for c in 0..board.size() {
+            assert_eq!(
+                board.cell(r, c) == CellState::Ship,
+                occupied.contains(&(r, c))
+            );
+        }
+    }
+}
+
+proptest! {
+    // Hundreds of boards each, without writing failing cases into the source tree
+    #![proptest_config(ProptestConfig {
+        cases: 500,
+        failure_persistence: None,
+        ..ProptestConfig::default()
+    })]
+
+    #[test]
+    fn requested_placements_stay_on_the_board_and_apart(
+        size in 1..=MAX_BOARD_SIZE,
+        no_touching in any::<bool>(),
+        diagonal in any::<bool>(),
+        placements in proptest::collection::vec(placement(), 1..20),
+    ) {
+        let mut board = Board::new(size);
+        board.set_no_touching(no_touching);
+        board.set_diagonal(diagonal);
+        for (ship_size, row, col, orientation) in placements {
+            let (end_row, end_col) = match orientation {
+                Orientation::Horizontal => (row, col + ship_size - 1),
+                Orientation::Vertical => (row + ship_size - 1, col),
+                Orientation::DiagonalDownRight => (row + ship_size - 1, col + ship_size - 1),
+                Orientation::DiagonalDownLeft => (row + ship_size - 1, col.wrapping_sub(ship_size - 1)),
+            };
+            let on_board = row < size && col < size && end_row < size && end_col < size;
+            let allowed = board.can_place_ship(row, col, ship_size, orientation);
+            let result = board.place_ship_at("Ship", row, col, ship_size, orientation);
+            // A diagonal ship is refused outright without the rule, and one that would hang over the edge as
+            // out of bounds, whatever else is in the way
+            prop_assert_eq!(result.is_ok(), allowed);
+            if orientation.is_diagonal() && !diagonal {
+                prop_assert_eq!(result, Err(PlacementError::Diagonal));
+            } else if !on_board {
+                prop_assert_eq!(result, Err(PlacementError::OutOfBounds));
+            }
+            assert_ships_fit(&board);
+        }
+    }
+
+    #[test]
+    fn random_placements_stay_on_the_board_and_apart(
+        size in 1..=MAX_BOARD_SIZE,
+        no_touching in any::<bool>(),
+        spread in any::<bool>(),
+        diagonal in any::<bool>(),
+        ships in proptest::collection::vec(1..=6usize, 1..12),
+        seed in any::<u64>(),
+    ) {
+        let mut board = Board::new(size);
+        board.set_no_touching(no_touching);
+        board.set_spread(spread);
+        board.set_diagonal(diagonal);
+        let mut rng = StdRng::seed_from_u64(seed);
+        for ship_size in ships {
+            let placed = board.ships().len();
+            match board.place_ship("Ship", ship_size, &mut rng) {
+                Ok(()) => prop_assert_eq!(board.ships().len(), placed + 1),
+                Err(error) => {
+                    prop_assert_eq!(error, PlacementError::NoRoom);
+                    prop_assert_eq!(board.ships().len(), placed);
+                }
+            }
+            assert_ships_fit(&board);
+        }
+    }
+}
+
+// Helper to measure how spread out a fleet is: the average distance of each ship's middle from the middle of
+// the whole fleet.
+fn fleet_spread(board: &Board) -> f64 {
+    let middles: Vec<(f64, f64)> = board
+        .ships()
+        .iter()
+        .map(|ship| {
+            let (rows, cols) = ship
+                .cells
+                .iter()
+                .fold((0, 0), |(rows, cols), &(r, c)| (rows + r, cols + c));
+            (
+                rows as f64 / ship.size as f64,
+                cols as f64 / ship.size as f64,
+            )
+        })
+        .collect();
+    let count = middles.len() as f64;
+    let center_row = middles.iter().map(|&(r, _)| r).sum::<f64>() / count;
+    let center_col = middles.iter().map(|&(_, c)| c).sum::<f64>() / count;
+    middles
+        .iter()
+        .map(|&(r, c)| ((r - center_row).powi(2) + (c - center_col).powi(2)).sqrt())
+        .sum::<f64>()
+        / count
+}
+
+#[test]
+fn spread_placement_keeps_fleets_further_apart_than_pure_random() {
+    let average_spread = |spread: bool| {
+        let mut rng = StdRng::seed_from_u64(11);
+        let mut total = 0.0;
+        for _ in 0..500 {
+            let mut board = Board::new(10);
+            board.set_spread(spread);
+            board
+                .place_fleet(&FleetConfig::default(), &mut rng)
+                .unwrap();
+            total += fleet_spread(&board);
+        }
+        total / 500.0
+    };
+    // Pure random placement averages a little over 3 cells on a 10x10 board
+    let random = average_spread(false);
+    let spread = average_spread(true);
+    assert!(spread > 3.4, "spread placement only averaged {spread:.2}");
+    assert!(spread > random);
+}
@@ -0,0 +1,89 @@
+use battle_ship::style::{self, Style, Styled};
+use battle_ship::Orientation::{Horizontal, Vertical};
+use battle_ship::{render, Board, RenderOptions, ASCII_GLYPHS, UNICODE_GLYPHS};
+
+// Helper to set up a 4x4 board with a sunk Submarine at D3, a hit on the Destroyer at A0 and a miss at C2,
+// the Submarine being the latest shot.
+fn small_board() -> Board {
+    let mut board = Board::new(4);
+    board
+        .place_ship_at("Destroyer", 0, 0, 2, Horizontal)
+        .unwrap();
+    board.place_ship_at("Cruiser", 1, 3, 2, Vertical).unwrap();
+    board
+        .place_ship_at("Submarine", 3, 3, 1, Horizontal)
+        .unwrap();
+    board.fire(0, 0);
+    board.fire(2, 2);
+    board.fire(3, 3);
+    board
+}
+
+// Helper to get the plain-text options for a glyph set.
+fn plain(hide_ships: bool, ascii: bool) -> RenderOptions {
+    RenderOptions {
+        hide_ships,
+        color: false,
+        glyphs: if ascii {
+            &ASCII_GLYPHS
+        } else {
+            &UNICODE_GLYPHS
+        },
+    }
+}
+
+#[test]
+fn renders_the_whole_board_with_the_latest_shot_in_brackets() {
+    assert_eq!(
+        render(&small_board(), &plain(false, true)),
+        concat!(
+            "    A  B  C  D \n",
+            " 0  X  #  ~  ~ \n",
+            " 1  ~  ~  ~  # \n",
+            " 2  ~  ~  o  # \n",
+            " 3  ~  ~  ~ [*]\n",
+        )
+    );
+}
+
+#[test]
+fn hidden_ships_leave_only_the_shots() {
+    assert_eq!(
+        render(&small_board(), &plain(true, true)),
+        concat!(
+            "    A  B  C  D \n",
+            " 0  X          \n",
+            " 1             \n",
+            " 2        o    \n",
+            " 3          [*]\n",
+        )
+    );
+}
+
+#[test]
+fn unicode_glyphs_draw_the_same_layout() {
+    assert_eq!(
+        render(&small_board(), &plain(false, false)),
+        concat!(
+            "    A  B  C  D \n",
+            " 0  ●  ■  □  □ \n",
+            " 1  □  □  □  ■ \n",
+            " 2  □  □  ·  ■ \n",
+            " 3  □  □  □ [✖]\n",
+        )
+    );
+}
+
+#[test]
+fn colored_boards_pick_out_the_latest_shot_with_a_highlight_instead() {
+    style::set_enabled(true);
+    let options = RenderOptions {
+        color: true,
+        ..plain(false, false)
+    };
+    let rendered = render(&small_board(), &options);
+    assert!(rendered.contains(&"✖".styled(Style::Latest).to_string()));
+    assert!(rendered.contains(&"●".styled(Style::Hit).to_string()));
+    // Color codes contain '[' too, so look for a closing bracket instead
+    assert!(!rendered.contains(']'));
+}
@@ -0,0 +1,214 @@
+use battle_ship::{
+    load_replay, save_replay, CellState, Difficulty, FleetConfig, GameSettings, GameState, Lane,
+    Move, Replay, ReplayError, ShotOutcome, Side, TorpedoOutcome, REPLAY_VERSION,
+};
+use serde_json::Value;
+use std::env;
+use std::fs;
+
+// Helper to build a unique path in the temp directory for a test's replay file.
+fn temp_path(name: &str) -> String {
+    env::temp_dir()
+        .join(format!(
+            "battle_ship_replay_{}_{}.json",
+            name,
+            std::process::id()
+        ))
+        .to_string_lossy()
+        .into_owned()
+}
+
+// Helper to record a replay of a seeded game where both sides fire down the first column, taking the outcomes
+// from the real boards.
+fn recorded_replay() -> Replay {
+    let mut game = GameState::new(GameSettings {
+        size: 10,
+        difficulty: Difficulty::Normal,
+        fleet: FleetConfig::standard(),
+        salvo: false,
+        hit_again: false,
+        heat: false,
+        no_touching: true,
+        spread: false,
+        diagonal: false,
+        land: 0,
+        hotseat: false,
+        seed: Some(11),
+        max_turns: None,
+        torpedoes: 0,
+        scans: 0,
+        sweeps: 0,
+        auto_advance: None,
+    })
+    .unwrap();
+    let mut rng = rand::thread_rng();
+    game.player_board_mut()
+        .place_fleet(&FleetConfig::standard(), &mut rng)
+        .unwrap();
+    let mut replay = Replay::from_game(&game);
+    let mut player_board = game.player_board().clone();
+    let mut opponent_board = game.opponent_board().clone();
+    for row in 0..10 {
+        replay.moves.push(Move {
+            turn: row + 1,
+            side: Side::Player,
+            row,
+            col: 0,
+            outcome: opponent_board.fire(row, 0),
+            torpedo: None,
+        });
+        replay.moves.push(Move {
+            turn: row + 1,
+            side: Side::Opponent,
+            row,
+            col: 0,
+            outcome: player_board.fire(row, 0),
+            torpedo: None,
+        });
+    }
+    replay
+}
+
+#[test]
+fn replay_records_both_fleets() {
+    let replay = recorded_replay();
+    assert_eq!(replay.version, REPLAY_VERSION);
+    assert_eq!(replay.player_fleet.len(), 5);
+    assert_eq!(replay.opponent_fleet.len(), 5);
+    assert!(replay.no_touching);
+}
+
+#[test]
+fn replay_round_trips_and_rebuilds_the_same_boards() {
+    let replay = recorded_replay();
+    let path = temp_path("round_trip");
+    save_replay(&path, &replay).unwrap();
+    let loaded = load_replay(&path).unwrap();
+    fs::remove_file(&path).unwrap();
+    assert_eq!(loaded, replay);
+
+    let (player_board, opponent_board) = loaded.check().unwrap();
+    assert_eq!(player_board.stats().shots, 10);
+    assert_eq!(opponent_board.stats().shots, 10);
+}
+
+#[test]
+fn replay_with_a_wrong_outcome_is_rejected() {
+    let mut replay = recorded_replay();
+    let shot = &mut replay.moves[0];
+    shot.outcome = match shot.outcome {
+        ShotOutcome::Miss => ShotOutcome::Hit,
+        _ => ShotOutcome::Miss,
+    };
+    assert!(matches!(replay.check(), Err(ReplayError::Inconsistent(_))));
+}
+
+#[test]
+fn replay_fires_recorded_torpedoes_again() {
+    let mut replay = recorded_replay();
+    let (_, mut opponent_board) = replay.check().unwrap();
+    // Every lane down the board, recorded with what each torpedo really did
+    for col in 1..10 {
+        let lane = Lane::Column(col);
+        let (row, col, outcome) = match opponent_board.fire_torpedo(lane) {
+            TorpedoOutcome::Struck { row, col, outcome } => (row, col, outcome),
+            TorpedoOutcome::Missed => (9, col, ShotOutcome::Miss),
+        };
+        replay.moves.push(Move {
+            turn: 11,
+            side: Side::Player,
+            row,
+            col,
+            outcome,
+            torpedo: Some(lane),
+        });
+    }
+    assert!(replay.check().is_ok());
+
+    // A torpedo recorded as striking somewhere it doesn't is caught
+    let last = replay.moves.last_mut().unwrap();
+    last.row = (last.row + 1) % 10;
+    last.outcome = ShotOutcome::Hit;
+    assert!(matches!(replay.check(), Err(ReplayError::Inconsistent(_))));
+}
+
+#[test]
+fn replay_with_overlapping_ships_is_rejected() {
+    let mut replay = recorded_replay();
+    let first = replay.player_fleet[0].clone();
+    replay.player_fleet.push(first);
+    assert!(matches!(replay.check(), Err(ReplayError::Inconsistent(_))));
+}
+
+#[test]
+fn replay_with_a_shot_off_the_board_is_rejected() {
+    let mut replay = recorded_replay();
+    replay.moves[0].row = 10;
+    assert!(matches!(replay.check(), Err(ReplayError::Inconsistent(_))));
+}
+
+#[test]
+fn replay_from_a_newer_version_is_refused() {
+    let path = temp_path("newer");
+    let mut json = serde_json::to_value(recorded_replay()).unwrap();
+    json["version"] = Value::from(REPLAY_VERSION + 1);
+    fs::write(&path, json.to_string()).unwrap();
+    let result = load_replay(&path);
+    fs::remove_file(&path).unwrap();
+    assert!(matches!(result, Err(ReplayError::UnsupportedVersion(_))));
+}
+
+#[test]
+fn replay_without_optional_fields_still_loads() {
+    let path = temp_path("minimal");
+    fs::write(
+        &path,
+        r#"{
+            "version": 1,
+            "size": 5,
+            "player_fleet": [
+                {"name": "Destroyer", "size": 2, "row": 0, "col": 0, "orientation": "Horizontal"}
+            ],
+            "opponent_fleet": [
+                {"name": "Destroyer", "size": 2, "row": 3, "col": 3, "orientation": "Vertical"}
+            ],
+            "moves": [
+                {"side": "Player", "row": 4, "col": 3, "outcome": "Hit"}
+            ]
+        }"#,
+    )
+    .unwrap();
+    let result = load_replay(&path);
+    fs::remove_file(&path).unwrap();
+    let replay = result.unwrap();
+    assert!(!replay.hotseat);
+    assert_eq!(replay.moves[0].turn, 0);
+    assert!(replay.check().is_ok());
+}
+
+#[test]
+fn replay_rebuilds_land_and_shots_that_struck_it() {
+    let mut replay = recorded_replay();
+    let (player_board, _) = replay.boards().unwrap();
+    let shore = (0..10)
+        .flat_map(|r| (1..10).map(move |c| (r, c)))
+        .find(|&(r, c)| player_board.cell(r, c) == CellState::Empty)
+        .unwrap();
+    replay.player_land.push(shore);
+    replay.moves.push(Move {
+        turn: 11,
+        side: Side::Opponent,
+        row: shore.0,
+        col: shore.1,
+        outcome: ShotOutcome::Land,
+        torpedo: None,
+    });
+    let (player_board, _) = replay.check().unwrap();
+    assert_eq!(player_board.land(), vec![shore]);
+    assert_eq!(player_board.stats().shots, 10);
+
+    // A ship recorded on land can't have been placed there
+    let ship = &replay.player_fleet[0];
+    replay.player_land.push((ship.row, ship.col));
+    assert!(matches!(replay.check(), Err(ReplayError::Inconsistent(_))));
+}
@@ -0,0 +1,216 @@
+use battle_ship::{
+    load_game, save_game, Difficulty, FleetConfig, GameSettings, GameState, Move, SaveError,
+    ShotOutcome, Side, FLEET,
+};
+use serde_json::Value;
+use std::env;
+use std::fs;
+use std::time::Duration;
+
+// Helper to build a unique path in the temp directory for a test's save file.
+fn temp_path(name: &str) -> String {
+    env::temp_dir()
+        .join(format!("battle_ship_{}_{}.json", name, std::process::id()))
+        .to_string_lossy()
+        .into_owned()
+}
+
+// Helper to set up a seeded game with both fleets placed and a shot fired at each board.
+fn game_in_progress() -> GameState {
+    let mut game = GameState::new(GameSettings {
+        size: 10,
+        difficulty: Difficulty::Hard,
+        fleet: FleetConfig::standard(),
+        salvo: false,
+        hit_again: false,
+        heat: false,
+        no_touching: false,
+        spread: false,
+        diagonal: false,
+        land: 0,
+        hotseat: false,
+        seed: Some(7),
+        max_turns: None,
+        torpedoes: 0,
+        scans: 0,
+        sweeps: 0,
+        auto_advance: None,
+    })
+    .unwrap();
+    for (name, size) in FLEET {
+        game.player_board_mut()
+            .place_ship(name, size, &mut rand::thread_rng())
+            .unwrap();
+    }
+    game.player_board_mut().fire(0, 0);
+    game
+}
+
+// Helper to save a game, hand its JSON to `tamper`, write it back and try to load it.
+fn load_tampered(name: &str, tamper: impl FnOnce(&mut Value)) -> Result<GameState, SaveError> {
+    let path = temp_path(name);
+    save_game(&path, &game_in_progress()).unwrap();
+    let mut json: Value = serde_json::from_str(&fs::read_to_string(&path).unwrap()).unwrap();
+    tamper(&mut json);
+    fs::write(&path, json.to_string()).unwrap();
+    let result = load_game(&path);
+    fs::remove_file(&path).unwrap();
+    result
+}
+
+#[test]
+fn saved_game_loads_back_identically() {
+    let game = game_in_progress();
+    let path = temp_path("round_trip");
+    save_game(&path, &game).unwrap();
+    let loaded = load_game(&path).unwrap();
+    fs::remove_file(&path).unwrap();
+
+    assert_eq!(loaded.is_player_turn(), game.is_player_turn());
+    assert_eq!(loaded.turns(), game.turns());
+    for (original, restored) in [
+        (game.player_board(), loaded.player_board()),
+        (game.opponent_board(), loaded.opponent_board()),
+    ] {
+        assert_eq!(original.size(), restored.size());
+        assert_eq!(original.ships().len(), restored.ships().len());
+        assert_eq!(original.stats(), restored.stats());
+        for r in 0..original.size() {
+            for c in 0..original.size() {
+                assert_eq!(original.cell(r, c), restored.cell(r, c));
+            }
+        }
+    }
+}
+
+#[test]
+fn loading_missing_or_corrupt_files_fails() {
+    assert!(matches!(
+        load_game(&temp_path("missing")),
+        Err(SaveError::Io(_))
+    ));
+
+    let path = temp_path("corrupt");
+    fs::write(&path, "{ not json").unwrap();
+    let result = load_game(&path);
+    fs::remove_file(&path).unwrap();
+    assert!(matches!(result, Err(SaveError::Json(_))));
+}
+
+#[test]
+fn loading_a_grid_of_the_wrong_size_fails() {
+    let result = load_tampered("short_grid", |json| {
+        json["player_board"]["grid"].as_array_mut().unwrap().pop();
+    });
+    assert!(matches!(result, Err(SaveError::Invalid(_))));
+}
+
+#[test]
+fn loading_an_unknown_cell_value_fails() {
+    let result = load_tampered("bad_cell", |json| {
+        json["opponent_board"]["grid"][0][0] = Value::from("Sunk");
+    });
+    assert!(matches!(result, Err(SaveError::Json(_))));
+}
+
+#[test]
+fn loading_ships_that_disagree_with_the_grid_fails() {
+    let result = load_tampered("ship_off_grid", |json| {
+        json["player_board"]["ships"][0]["cells"][0] = serde_json::json!([10, 10]);
+    });
+    assert!(matches!(result, Err(SaveError::Invalid(_))));
+
+    let result = load_tampered("ship_on_water", |json| {
+        let (row, col) = (
+            json["opponent_board"]["ships"][0]["cells"][0][0]
+                .as_u64()
+                .unwrap() as usize,
+            json["opponent_board"]["ships"][0]["cells"][0][1]
+                .as_u64()
+                .unwrap() as usize,
+        );
+        json["opponent_board"]["grid"][row][col] = Value::from("Empty");
+    });
+    assert!(matches!(result, Err(SaveError::Invalid(_))));
+}
+
+#[test]
+fn moves_round_trip_through_json() {
+    let history = vec![
+        Move {
+            turn: 1,
+            side: Side::Player,
+            row: 3,
+            col: 4,
+            outcome: ShotOutcome::Sunk("Destroyer".to_string()),
+            torpedo: None,
+        },
+        Move {
+            turn: 1,
+            side: Side::Opponent,
+            row: 0,
+            col: 9,
+            outcome: ShotOutcome::Miss,
+            torpedo: None,
+        },
+    ];
+    let json = serde_json::to_string(&history).unwrap();
+    let restored: Vec<Move> = serde_json::from_str(&json).unwrap();
+    assert_eq!(restored, history);
+    assert!(game_in_progress().history().is_empty());
+}
+
+#[test]
+fn rematch_starts_from_fresh_boards() {
+    let mut game = game_in_progress();
+    let opponent_ships: Vec<_> = game
+        .opponent_board()
+        .ships()
+        .iter()
+        .map(|ship| ship.cells.clone())
+        .collect();
+    game.new_round().unwrap();
+
+    assert!(game.player_board().ships().is_empty());
+    assert_eq!(game.player_board().stats().shots, 0);
+    assert_eq!(game.opponent_board().ships().len(), opponent_ships.len());
+    assert_ne!(
+        game.opponent_board()
+            .ships()
+            .iter()
+            .map(|ship| ship.cells.clone())
+            .collect::<Vec<_>>(),
+        opponent_ships
+    );
+    assert!(game.history().is_empty());
+    assert_eq!(game.turns(), 0);
+    assert!(game.is_player_turn());
+    assert_eq!(game.session(), [0, 0]);
+}
+
+#[test]
+fn saves_from_before_sessions_load_with_no_games_won() {
+    let game = load_tampered("no_session", |json| {
+        json.as_object_mut().unwrap().remove("session");
+    })
+    .unwrap();
+    assert_eq!(game.session(), [0, 0]);
+}
+
+#[test]
+fn saved_games_keep_the_time_played_so_far() {
+    // A clock saved at 5 minutes 32 seconds carries on from there once loaded
+    let game = load_tampered("clock", |json| {
+        json["clock"] = serde_json::json!({ "secs": 332, "nanos": 0 });
+    })
+    .unwrap();
+    assert!(game.elapsed() >= Duration::from_secs(332));
+    assert!(game.elapsed() < Duration::from_secs(340));
+
+    // Saves from before the clock start it from nothing
+    let game = load_tampered("no_clock", |json| {
+        json.as_object_mut().unwrap().remove("clock");
+    })
+    .unwrap();
+    assert!(game.elapsed() < Duration::from_secs(10));
+}
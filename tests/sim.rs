@@ -0,0 +1,116 @@
+use battle_ship::{
+    run_game, simulate, Difficulty, FleetConfig, GameResult, GameSettings, HuntTargetStrategy,
+    RandomStrategy, Side, SimulationReport,
+};
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+
+// Helper to build settings for a seeded headless game on a standard board.
+fn settings(salvo: bool) -> GameSettings {
+    GameSettings {
+        size: 10,
+        difficulty: Difficulty::Normal,
+        fleet: FleetConfig::standard(),
+        salvo,
+        hit_again: false,
+        heat: false,
+        no_touching: false,
+        spread: false,
+        diagonal: false,
+        land: 0,
+        hotseat: false,
+        seed: Some(11),
+        max_turns: None,
+        torpedoes: 0,
+        scans: 0,
+        sweeps: 0,
+        auto_advance: None,
+    }
+}
+
+#[test]
+fn a_headless_game_ends_with_the_winner_sinking_the_whole_fleet() {
+    for salvo in [false, true] {
+        let mut rng = StdRng::seed_from_u64(4);
+        let result = run_game(
+            &mut RandomStrategy,
+            &mut HuntTargetStrategy::default(),
+            &settings(salvo),
+            &mut rng,
+        )
+        .unwrap();
+        let winner = result.winner as usize;
+        assert!((17..=100).contains(&result.shots[winner]));
+        if !salvo {
+            // One shot a turn, and the first side never trails the second
+            assert_eq!(result.turns, result.shots[0]);
+            assert!(result.shots[0] - result.shots[1] <= 1);
+        }
+    }
+}
+
+#[test]
+fn simulations_with_the_same_seed_give_the_same_report() {
+    let players = [Difficulty::Easy, Difficulty::Hard];
+    let first = simulate(players, 50, &settings(false)).unwrap();
+    let second = simulate(players, 50, &settings(false)).unwrap();
+    assert_eq!(first, second);
+    assert_eq!(first.games, 50);
+    assert_eq!(first.wins[0] + first.wins[1], 50);
+    assert_eq!(first.distribution.iter().sum::<usize>(), 50);
+    assert!(first.win_rate(Side::Opponent) > first.win_rate(Side::Player));
+}
+
+#[test]
+fn reports_total_wins_and_shots_per_side() {
+    let mut report = SimulationReport::default();
+    for (winner, shots) in [
+        (Side::Player, [40, 39]),
+        (Side::Player, [60, 59]),
+        (Side::Opponent, [55, 55]),
+    ] {
+        report.record(&GameResult {
+            winner,
+            shots,
+            turns: shots[0],
+        });
+    }
+    assert_eq!(report.games, 3);
+    assert_eq!(report.wins, [2, 1]);
+    assert!((report.win_rate(Side::Player) - 200.0 / 3.0).abs() < 1e-9);
+    assert_eq!(report.average_shots_to_win(Side::Player), 50.0);
+    assert_eq!(report.average_shots_to_win(Side::Opponent), 55.0);
+    assert_eq!(report.distribution, [0, 0, 0, 0, 1, 1, 1]);
+}
+
+#[test]
+fn hits_earn_extra_shots_under_the_hit_again_rule() {
+    let mut settings = settings(false);
+    settings.hit_again = true;
+    let mut rng = StdRng::seed_from_u64(8);
+    let result = run_game(
+        &mut HuntTargetStrategy::default(),
+        &mut HuntTargetStrategy::default(),
+        &settings,
+        &mut rng,
+    )
+    .unwrap();
+    // Every turn ends on a miss except the winner's last, which sinks the final ship, so the winner fired
+    // one shot per ship cell plus one miss for each earlier turn
+    let fleet_cells: usize = settings.fleet.ships.iter().map(|&(_, size)| size).sum();
+    assert_eq!(
+        result.shots[result.winner as usize],
+        fleet_cells + result.turns - 1
+    );
+}
+
+#[test]
+fn monte_carlo_expert_beats_the_random_ai_almost_every_game() {
+    // The random side fires first, so the expert starts every game a shot behind
+    let report = simulate([Difficulty::Easy, Difficulty::Expert], 10, &settings(false)).unwrap();
+    assert!(
+        report.win_rate(Side::Opponent) >= 95.0,
+        "the expert only won {:.0}% of games",
+        report.win_rate(Side::Opponent)
+    );
+}
@@ -0,0 +1,42 @@
+use battle_ship::style::{self, Style, Styled};
+use battle_ship::{ASCII_GLYPHS, UNICODE_GLYPHS};
+
+#[test]
+fn disabled_styles_print_plain_text() {
+    style::set_enabled(false);
+    assert!(!style::enabled());
+    for kind in [
+        Style::Emphasis,
+        Style::Warning,
+        Style::Hit,
+        Style::Miss,
+        Style::Sunk,
+        Style::Wreck,
+    ] {
+        assert_eq!("Carrier".styled(kind).to_string(), "Carrier");
+    }
+
+    style::set_enabled(true);
+    assert!(style::enabled());
+    assert_ne!("Carrier".styled(Style::Hit).to_string(), "Carrier");
+}
+
+#[test]
+fn both_glyph_sets_are_one_column_and_distinct() {
+    for glyphs in [&UNICODE_GLYPHS, &ASCII_GLYPHS] {
+        let all = [
+            glyphs.water,
+            glyphs.ship,
+            glyphs.hit,
+            glyphs.sunk,
+            glyphs.miss,
+        ];
+        for (i, glyph) in all.iter().enumerate() {
+            assert_eq!(glyph.chars().count(), 1);
+            assert!(!all[..i].contains(glyph));
+        }
+    }
+    assert!(
+        ASCII_GLYPHS.hit.is_ascii() && ASCII_GLYPHS.sunk.is_ascii() && ASCII_GLYPHS.miss.is_ascii()
+    );
+}
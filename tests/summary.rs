@@ -0,0 +1,102 @@
+use battle_ship::{
+    format_elapsed, summarize, Move, ShotOutcome, Side, MISS_PENALTY, SINK_BONUS, TURN_PENALTY,
+    WIN_BONUS,
+};
+use std::time::Duration;
+
+// Helper to build a move for a side on a given turn.
+fn shot(turn: usize, side: Side, outcome: ShotOutcome) -> Move {
+    Move {
+        turn,
+        side,
+        row: 0,
+        col: 0,
+        outcome,
+        torpedo: None,
+    }
+}
+
+#[test]
+fn summary_counts_each_sides_shots_separately() {
+    let history = vec![
+        shot(1, Side::Player, ShotOutcome::Hit),
+        shot(1, Side::Opponent, ShotOutcome::Miss),
+        shot(2, Side::Player, ShotOutcome::Miss),
+        shot(2, Side::Opponent, ShotOutcome::Hit),
+    ];
+    let player = summarize(&history, Side::Player);
+    assert_eq!(player.stats.shots, 2);
+    assert_eq!(player.stats.hits, 1);
+    assert_eq!(player.stats.misses, 1);
+    assert_eq!(player.turns, 2);
+    let opponent = summarize(&history, Side::Opponent);
+    assert_eq!(opponent.stats.hits, 1);
+    assert_eq!(opponent.turns, 2);
+}
+
+#[test]
+fn summary_tracks_the_longest_run_of_hits_across_turns() {
+    let history = vec![
+        shot(1, Side::Player, ShotOutcome::Hit),
+        shot(2, Side::Player, ShotOutcome::Miss),
+        shot(3, Side::Player, ShotOutcome::Hit),
+        shot(3, Side::Opponent, ShotOutcome::Miss),
+        shot(4, Side::Player, ShotOutcome::Hit),
+        shot(5, Side::Player, ShotOutcome::Sunk("Destroyer".to_string())),
+        shot(6, Side::Player, ShotOutcome::Miss),
+    ];
+    assert_eq!(summarize(&history, Side::Player).longest_streak, 3);
+}
+
+#[test]
+fn summary_lists_sunk_ships_in_order_and_skips_repeats() {
+    let history = vec![
+        shot(1, Side::Player, ShotOutcome::Sunk("Submarine".to_string())),
+        shot(2, Side::Player, ShotOutcome::AlreadyFired),
+        shot(3, Side::Player, ShotOutcome::Sunk("Carrier".to_string())),
+    ];
+    let summary = summarize(&history, Side::Player);
+    assert_eq!(summary.sunk, vec!["Submarine", "Carrier"]);
+    assert_eq!(summary.stats.shots, 2);
+    assert_eq!(summary.turns, 2);
+    assert!(summarize(&history, Side::Opponent).sunk.is_empty());
+}
+
+#[test]
+fn salvo_shots_in_one_turn_count_as_a_single_turn() {
+    let history = vec![
+        shot(1, Side::Player, ShotOutcome::Miss),
+        shot(1, Side::Player, ShotOutcome::Hit),
+        shot(1, Side::Player, ShotOutcome::Hit),
+    ];
+    let summary = summarize(&history, Side::Player);
+    assert_eq!(summary.turns, 1);
+    assert_eq!(summary.longest_streak, 2);
+}
+
+#[test]
+fn score_rewards_wins_and_sinkings_and_charges_for_misses_and_turns() {
+    let history = vec![
+        shot(1, Side::Player, ShotOutcome::Miss),
+        shot(2, Side::Player, ShotOutcome::Hit),
+        shot(3, Side::Player, ShotOutcome::Sunk("Destroyer".to_string())),
+        shot(3, Side::Opponent, ShotOutcome::Miss),
+    ];
+    let mut player = summarize(&history, Side::Player);
+    assert_eq!(player.score(), SINK_BONUS - MISS_PENALTY - 3 * TURN_PENALTY);
+    player.won = true;
+    assert_eq!(
+        player.score(),
+        WIN_BONUS + SINK_BONUS - MISS_PENALTY - 3 * TURN_PENALTY
+    );
+    // A losing side that only missed ends up below zero
+    let opponent = summarize(&history, Side::Opponent);
+    assert_eq!(opponent.score(), -MISS_PENALTY - TURN_PENALTY);
+}
+
+#[test]
+fn elapsed_time_reads_as_minutes_and_seconds() {
+    assert_eq!(format_elapsed(Duration::ZERO), "00:00");
+    assert_eq!(format_elapsed(Duration::from_millis(332_900)), "05:32");
+    assert_eq!(format_elapsed(Duration::from_secs(75 * 60 + 2)), "75:02");
+}